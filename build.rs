@@ -0,0 +1,90 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// @notice Solidity libraries vendored into the binary for `create --offline`, from the submodules of contract-template
+const VENDORED_LIBRARIES: &[&str] = &["coprocessor-base-contract", "forge-std"];
+
+/// @notice Checked-in file holding the base contract tag new projects install, it must match the tag of the submodule
+const BASE_CONTRACT_VERSION_FILE: &str = "contract-template/base-contract-version";
+
+/// @notice Environment variable set by the release workflow to make missing libraries an error instead of a warning
+const REQUIRE_VENDORED_ENV: &str = "COPROCESSOR_REQUIRE_VENDORED";
+
+/// @notice Function to copy a directory recursively, skipping git metadata
+/// @param from The directory to copy from
+/// @param to The directory to copy into
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+/// @notice Function to check whether a library submodule is checked out
+/// @param source The folder of the submodule
+/// @return true if the folder holds anything besides git metadata
+fn is_checked_out(source: &Path) -> bool {
+    fs::read_dir(source).is_ok_and(|entries| {
+        entries
+            .filter_map(Result::ok)
+            .any(|entry| entry.file_name() != ".git")
+    })
+}
+
+/// @notice Copies the checked out library submodules into OUT_DIR so they can be embedded with include_dir,
+/// @notice and exposes the base contract tag of the checked-in version file as `BASE_CONTRACT_VERSION`.
+/// @notice Missing submodules fail the release workflow, other builds such as `cargo install` warn and embed an empty folder.
+fn main() {
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    let require_vendored = env::var_os(REQUIRE_VENDORED_ENV).is_some();
+    let vendor_dir = Path::new(&out_dir).join("vendor");
+    let _ = fs::remove_dir_all(&vendor_dir);
+    println!("cargo:rerun-if-env-changed={}", REQUIRE_VENDORED_ENV);
+
+    for library in VENDORED_LIBRARIES {
+        let source = Path::new("contract-template/lib").join(library);
+        let target = vendor_dir.join(library);
+        println!("cargo:rerun-if-changed={}", source.display());
+
+        if !is_checked_out(&source) {
+            let message = format!(
+                "{} is not checked out, run `git submodule update --init --recursive`",
+                source.display()
+            );
+            if require_vendored {
+                panic!("{}", message);
+            }
+            println!(
+                "cargo:warning={}, create --offline will report it as missing",
+                message
+            );
+        }
+
+        let copied = if source.is_dir() {
+            copy_dir(&source, &target)
+        } else {
+            fs::create_dir_all(&target)
+        };
+        if let Err(e) = copied {
+            panic!("Failed to vendor {}: {}", library, e);
+        }
+    }
+
+    println!("cargo:rerun-if-changed={}", BASE_CONTRACT_VERSION_FILE);
+    let version = fs::read_to_string(BASE_CONTRACT_VERSION_FILE)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", BASE_CONTRACT_VERSION_FILE, e));
+    println!("cargo:rustc-env=BASE_CONTRACT_VERSION={}", version.trim());
+}
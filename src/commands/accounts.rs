@@ -0,0 +1,238 @@
+use crate::commands::devnet::active_devnet_config;
+use crate::helpers::config::{get_network_profile, DEVNET_MNEMONIC, DEVNET_PRIVATE_KEY};
+use crate::helpers::process::{report_process_error, run_captured, SHORT_TIMEOUT};
+use crate::helpers::rpc::{
+    address_from_private_key, format_ether, format_units, get_balance, get_token_balance,
+    get_token_decimals, has_code, is_address, parse_units, rpc_call, send_unlocked_transaction,
+    transfer_token,
+};
+use colored::Colorize;
+use serde_json::json;
+use std::process::Command;
+
+/// @notice Function to derive the private key of a pre-funded anvil account from the anvil mnemonic using cast
+/// @param index The index of the account
+/// @return an Option containing the private key of the account
+fn derive_devnet_key(index: usize) -> Option<String> {
+    let cast_status = match run_captured(
+        Command::new("cast")
+            .arg("wallet")
+            .arg("private-key")
+            .arg("--mnemonic")
+            .arg(DEVNET_MNEMONIC)
+            .arg("--mnemonic-index")
+            .arg(index.to_string()),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(cast_status) => cast_status,
+        Err(e) => {
+            report_process_error("CAST", &e);
+            return None;
+        }
+    };
+
+    if cast_status.status.success() {
+        Some(
+            String::from_utf8_lossy(&cast_status.stdout)
+                .trim()
+                .to_string(),
+        )
+    } else {
+        None
+    }
+}
+
+/// @notice Function to get the pre-funded accounts of the devnet anvil node
+/// @param rpc The url of the anvil node
+/// @return an Option containing the addresses of the accounts, the first one is the devnet treasury
+fn devnet_account_addresses(rpc: &str) -> Option<Vec<String>> {
+    match rpc_call(rpc, "eth_accounts", json!([])) {
+        Ok(accounts) => Some(
+            accounts
+                .as_array()
+                .map(|accounts| {
+                    accounts
+                        .iter()
+                        .filter_map(|account| account.as_str().map(|a| a.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        Err(e) => {
+            eprintln!("❌ {} {}", "RPC::ERROR::".red(), e.red());
+            println!(
+                "{}",
+                "Please ensure the devnet is running, run the start-devnet command.".yellow()
+            );
+            None
+        }
+    }
+}
+
+/// @notice Function to get the devnet payment token, if it is deployed
+/// @param rpc The url of the anvil node
+/// @return an Option containing the address and decimals of the payment token
+fn devnet_payment_token(rpc: &str) -> Option<(String, u32)> {
+    let token = get_network_profile("devnet")?.payment_token?;
+    if !has_code(rpc, &token).unwrap_or(false) {
+        return None;
+    }
+    let decimals = get_token_decimals(rpc, &token).unwrap_or(18);
+    Some((token, decimals))
+}
+
+/// @notice Entry point function to list the pre-funded devnet accounts with their balances and private keys
+pub fn devnet_accounts() {
+    let rpc = active_devnet_config().anvil_rpc();
+    let Some(accounts) = devnet_account_addresses(&rpc) else {
+        return;
+    };
+    let payment_token = devnet_payment_token(&rpc);
+
+    let rows: Vec<(String, String, String, String)> = accounts
+        .iter()
+        .enumerate()
+        .map(|(index, address)| {
+            let eth = get_balance(&rpc, address)
+                .map(|balance| format!("{} ETH", format_ether(balance)))
+                .unwrap_or_else(|_| String::from("--"));
+            let tokens = payment_token
+                .as_ref()
+                .and_then(|(token, decimals)| {
+                    get_token_balance(&rpc, token, address)
+                        .and_then(|balance| format_units(balance, *decimals))
+                        .ok()
+                })
+                .unwrap_or_else(|| String::from("--"));
+
+            // Only show keys of accounts derived from the default anvil mnemonic
+            let private_key = derive_devnet_key(index)
+                .filter(|key| {
+                    address_from_private_key(key)
+                        .is_some_and(|derived| derived.eq_ignore_ascii_case(address))
+                })
+                .unwrap_or_else(|| String::from("--"));
+
+            (address.clone(), eth, tokens, private_key)
+        })
+        .collect();
+
+    // Calculate the width of the columns
+    let eth_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+    let tokens_width = rows
+        .iter()
+        .map(|row| row.2.len())
+        .max()
+        .unwrap_or(0)
+        .max("Payment token".len());
+
+    println!(
+        "{:<3}  {:<42}  {:<eth_width$}  {:<tokens_width$}  {}",
+        "#".bold(),
+        "Address".bold(),
+        "Balance".bold(),
+        "Payment token".bold(),
+        "Private key".bold(),
+        eth_width = eth_width,
+        tokens_width = tokens_width
+    );
+    for (index, (address, eth, tokens, private_key)) in rows.into_iter().enumerate() {
+        let label = if index == 0 {
+            format!("{} (treasury)", private_key)
+        } else {
+            private_key
+        };
+        println!(
+            "{:<3}  {:<42}  {:<eth_width$}  {:<tokens_width$}  {}",
+            index,
+            address,
+            eth,
+            tokens,
+            label,
+            eth_width = eth_width,
+            tokens_width = tokens_width
+        );
+    }
+}
+
+/// @notice Entry point function to send ETH and payment tokens from the devnet treasury to an address
+/// @param address The address to fund
+/// @param amount The amount of ETH to send, e.g. `1.5`
+/// @param tokens The amount of payment tokens to send, if None the same amount as the ETH is sent
+pub fn devnet_fund(address: String, amount: String, tokens: Option<String>) {
+    if !is_address(&address) {
+        println!("❌ {} {}", "Invalid address".red(), address.red());
+        return;
+    }
+
+    let value = match parse_units(&amount, 18) {
+        Ok(value) => value,
+        Err(e) => {
+            println!("❌ {}", e.red());
+            return;
+        }
+    };
+
+    let rpc = active_devnet_config().anvil_rpc();
+    let Some(treasury) = address_from_private_key(DEVNET_PRIVATE_KEY)
+        .or_else(|| devnet_account_addresses(&rpc)?.into_iter().next())
+    else {
+        println!(
+            "{}",
+            "Could not determine the devnet treasury account.".red()
+        );
+        return;
+    };
+
+    if value > 0 {
+        match send_unlocked_transaction(
+            &rpc,
+            json!({
+                "from": treasury,
+                "to": address,
+                "value": format!("0x{:x}", value),
+            }),
+        ) {
+            Ok(transaction_hash) => println!(
+                "✅ {} {} ({})",
+                "Sent".green(),
+                format!("{} ETH to {}", amount, address).green(),
+                transaction_hash
+            ),
+            Err(e) => {
+                eprintln!("❌ {} {}", "Failed to send ETH:".red(), e.red());
+                return;
+            }
+        }
+    }
+
+    let Some((token, decimals)) = devnet_payment_token(&rpc) else {
+        println!(
+            "{}",
+            "The devnet payment token is not deployed, skipping payment tokens.".yellow()
+        );
+        return;
+    };
+
+    let tokens = tokens.unwrap_or(amount);
+    let token_amount = match parse_units(&tokens, decimals) {
+        Ok(token_amount) => token_amount,
+        Err(e) => {
+            println!("❌ {}", e.red());
+            return;
+        }
+    };
+    if token_amount == 0 {
+        return;
+    }
+
+    match transfer_token(&rpc, &token, &treasury, &address, token_amount) {
+        Ok(transaction_hash) => println!(
+            "✅ {} {} ({})",
+            "Sent".green(),
+            format!("{} payment tokens to {}", tokens, address).green(),
+            transaction_hash
+        ),
+        Err(e) => eprintln!("❌ {} {}", "Failed to send payment tokens:".red(), e.red()),
+    }
+}
@@ -0,0 +1,147 @@
+use crate::commands::devnet::{active_devnet, active_devnet_config, record_devnet_baseline};
+use crate::helpers::rpc::{parse_quantity, rpc_call};
+use chrono::{Local, TimeZone};
+use colored::Colorize;
+use serde_json::json;
+
+/// @notice Function to print the latest block of the devnet chain
+/// @param rpc The url of the anvil node
+fn print_latest_block(rpc: &str) {
+    let block = match rpc_call(rpc, "eth_getBlockByNumber", json!(["latest", false])) {
+        Ok(block) => block,
+        Err(e) => {
+            eprintln!("❌ {} {}", "RPC::ERROR::".red(), e.red());
+            return;
+        }
+    };
+
+    let number = parse_quantity(&block["number"]).unwrap_or_default();
+    let timestamp = parse_quantity(&block["timestamp"]).unwrap_or_default() as i64;
+    let time = Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default();
+
+    let data = vec![
+        ("Block", number.to_string()),
+        ("Timestamp", format!("{} ({})", timestamp, time)),
+    ];
+
+    // Calculate the width of the first column
+    let max_width = data.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, value) in data {
+        println!("{:<width$}  {}", name, value, width = max_width);
+    }
+}
+
+/// @notice Function to call an anvil RPC method on the devnet and report failures
+/// @param rpc The url of the anvil node
+/// @param method The JSON-RPC method to call
+/// @param params The parameters of the call
+/// @return an Option containing the result of the call
+fn devnet_rpc(rpc: &str, method: &str, params: serde_json::Value) -> Option<serde_json::Value> {
+    match rpc_call(rpc, method, params) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            eprintln!("❌ {} {}", "RPC::ERROR::".red(), e.red());
+            println!(
+                "{}",
+                "Please ensure the devnet is running, run the start-devnet command.".yellow()
+            );
+            None
+        }
+    }
+}
+
+/// @notice Entry point function to mine blocks on the devnet
+/// @param blocks The number of blocks to mine
+/// @param interval The number of seconds between the timestamps of the mined blocks, if None anvil's default is used
+pub fn devnet_mine(blocks: u64, interval: Option<u64>) {
+    let rpc = active_devnet_config().anvil_rpc();
+    let mut params = vec![json!(format!("0x{:x}", blocks))];
+    if let Some(interval) = interval {
+        params.push(json!(format!("0x{:x}", interval)));
+    }
+
+    if devnet_rpc(&rpc, "anvil_mine", json!(params)).is_none() {
+        return;
+    }
+    println!(
+        "✅ {} {} {}",
+        "Mined".green(),
+        blocks.to_string().green(),
+        "blocks.".green()
+    );
+    print_latest_block(&rpc);
+}
+
+/// @notice Entry point function to move the devnet clock forward and mine a block with the new time
+/// @param seconds The number of seconds to move the clock forward by
+/// @param timestamp The exact timestamp of the next block, used instead of seconds when set
+pub fn devnet_time_travel(seconds: Option<u64>, timestamp: Option<u64>) {
+    let rpc = active_devnet_config().anvil_rpc();
+
+    let moved = match (timestamp, seconds) {
+        (Some(timestamp), _) => {
+            devnet_rpc(&rpc, "anvil_setNextBlockTimestamp", json!([timestamp])).is_some()
+        }
+        (None, Some(seconds)) => devnet_rpc(&rpc, "evm_increaseTime", json!([seconds])).is_some(),
+        (None, None) => {
+            println!(
+                "{}",
+                "Please provide a number of seconds or a --to timestamp.".red()
+            );
+            false
+        }
+    };
+
+    // The new time only applies once a block is mined
+    if !moved || devnet_rpc(&rpc, "evm_mine", json!([])).is_none() {
+        return;
+    }
+    println!("✅ {}", "Devnet clock moved forward.".green());
+    print_latest_block(&rpc);
+}
+
+/// @notice Entry point function to revert the devnet chain to its state right after the devnet was started or restored,
+/// @notice keeping the co-processor contracts deployed on start
+pub fn devnet_reset() {
+    let Some(snapshot_id) = active_devnet()
+        .filter(|record| record["running"] == true)
+        .map(|record| record["baseline_snapshot"].clone())
+        .filter(|snapshot_id| !snapshot_id.is_null())
+    else {
+        println!(
+            "{}",
+            "No devnet baseline recorded, please restart the devnet with the stop-devnet and start-devnet commands."
+                .red()
+        );
+        return;
+    };
+
+    let rpc = active_devnet_config().anvil_rpc();
+    match devnet_rpc(&rpc, "evm_revert", json!([snapshot_id])) {
+        Some(serde_json::Value::Bool(true)) => {}
+        Some(_) => {
+            println!(
+                "{}",
+                "The devnet baseline is no longer available, please restart the devnet with the stop-devnet and start-devnet commands."
+                    .red()
+            );
+            return;
+        }
+        None => return,
+    }
+
+    // Reverting consumes the snapshot, take a new one so the devnet can be reset again
+    record_devnet_baseline();
+    println!("✅ {}", "Devnet chain reset to its baseline.".green());
+    println!(
+        "{}",
+        "Deployment records and solver state are kept, redeploy your contracts before using them again."
+            .yellow()
+    );
+    print_latest_block(&rpc);
+}
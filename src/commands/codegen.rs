@@ -0,0 +1,842 @@
+use crate::commands::deploy_script::{find_project_dir, foundry_dir, DEFAULT_CONTRACT_NAME};
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// @notice Encoding helpers the generated backend code builds on, per backend language
+const ABI_HELPERS: &[(&str, &str)] = &[
+    (
+        "python",
+        include_str!("../../templates/codegen/coprocessor_abi.py"),
+    ),
+    (
+        "javascript",
+        include_str!("../../templates/codegen/coprocessor_abi.js"),
+    ),
+];
+
+/// @notice Keywords and reserved words of Solidity, and the global `abi`, `msg`, `block` and `tx` the adapter can't shadow
+const SOLIDITY_RESERVED: &str = "abi abstract after alias anonymous apply as assembly auto block bool break \
+    byte bytes calldata case catch constant constructor continue contract copyof days default define \
+    delete do else emit enum ether event external fallback false final finney for from function gwei \
+    hex hours if immutable implements import in indexed inline interface internal is let library \
+    macro mapping match memory minutes modifier msg mutable new null of override partial payable \
+    pragma private promise public pure receive reference relocatable return returns sealed seconds \
+    sizeof static storage string struct super supports switch szabo this throw true try tx type \
+    typedef typeof unchecked unicode using var view virtual weeks wei while years";
+
+/// @notice Keywords of Python, and builtins the generated helpers use
+const PYTHON_RESERVED: &str = "False None True and as assert async await break class continue \
+    def del elif else except finally for from global if import in input is lambda nonlocal not or \
+    pass raise return try while with yield";
+
+/// @notice Reserved words of JavaScript, and globals the generated helpers use
+const JAVASCRIPT_RESERVED: &str = "arguments await break case catch class const continue debugger \
+    default delete do else enum eval export extends false finally for function if implements \
+    import in instanceof interface let new null package private protected public return static \
+    super switch this throw true try typeof var void while with yield Buffer BigInt Object";
+
+/// @notice Names declared by the generated adapter and helpers, including the parameters of handleNotice
+const GENERATED_NAMES: &str = "NoticeReceived callCoprocessor handleNotice notice payloadHash \
+    runExecution abi_decode abi_encode decode_input encode_notice INPUT_FIELDS INPUT_TYPES \
+    NOTICE_TYPES";
+
+/// @notice Function to check whether a name is a Solidity type, which are keywords, e.g. `uint256`, `bytes32` or `fixed128x18`
+/// @param name The name to check
+/// @return true if the name is a type
+fn is_type_name(name: &str) -> bool {
+    let fixed = name
+        .strip_prefix("ufixed")
+        .or_else(|| name.strip_prefix("fixed"))
+        .is_some_and(|size| {
+            size.is_empty()
+                || size.split_once('x').is_some_and(|(bits, decimals)| {
+                    [bits, decimals]
+                        .iter()
+                        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+                })
+        });
+    fixed || canonical_type(name).is_some()
+}
+
+/// @notice Function to check whether a name can't be used as a field, as it is reserved in Solidity or the backend languages
+/// @param name The name to check
+/// @return true if the name is reserved
+fn is_reserved_name(name: &str) -> bool {
+    is_type_name(name)
+        || [
+            SOLIDITY_RESERVED,
+            PYTHON_RESERVED,
+            JAVASCRIPT_RESERVED,
+            GENERATED_NAMES,
+        ]
+        .iter()
+        .any(|names| names.split_whitespace().any(|reserved| reserved == name))
+}
+
+/// @notice A field of the task input or of the notice payload of a program
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    abi_type: String,
+}
+
+/// @notice Input and notice payloads of a program, read from a schema file
+#[derive(Debug, Clone)]
+struct Schema {
+    contract_name: String,
+    input: Vec<Field>,
+    notice: Vec<Field>,
+}
+
+/// @notice Function to check a name can be used as an identifier in Solidity and in the backend languages
+/// @param name The name to check
+/// @return true if the name is a valid identifier
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// @notice Function to validate an ABI type of a schema field, resolving the `uint` and `int` aliases
+/// @param abi_type The type of the field
+/// @return an Option containing the canonical type, None if the type is not supported
+fn canonical_type(abi_type: &str) -> Option<String> {
+    let abi_type = abi_type.trim();
+    match abi_type {
+        "address" | "bool" | "bytes" | "string" => return Some(abi_type.to_string()),
+        "uint" => return Some(String::from("uint256")),
+        "int" => return Some(String::from("int256")),
+        _ => {}
+    }
+
+    // Sizes are plain decimal numbers, `uint08` or `uint+8` are not valid Solidity types
+    let sized = |prefix: &str, valid: &dyn Fn(u32) -> bool| {
+        abi_type
+            .strip_prefix(prefix)
+            .filter(|size| !size.starts_with('0') && size.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|size| size.parse::<u32>().ok())
+            .filter(|size| valid(*size))
+            .map(|_| abi_type.to_string())
+    };
+    sized("uint", &|size| size % 8 == 0 && (8..=256).contains(&size))
+        .or_else(|| sized("int", &|size| size % 8 == 0 && (8..=256).contains(&size)))
+        .or_else(|| sized("bytes", &|size| (1..=32).contains(&size)))
+}
+
+/// @notice Function to read the fields of a payload from a schema
+/// @param schema The parsed schema file
+/// @param key The key of the payload, `input` or `notice`
+/// @return A result containing the fields of the payload, or a description of the first invalid field
+fn parse_fields(schema: &Table, key: &str) -> Result<Vec<Field>, String> {
+    let Some(fields) = schema.get(key) else {
+        return Ok(Vec::new());
+    };
+    let fields = fields
+        .as_array()
+        .ok_or_else(|| format!("`{}` must be an array of fields", key))?;
+
+    let mut parsed: Vec<Field> = Vec::new();
+    for field in fields {
+        let name = field
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("every `{}` field needs a name", key))?;
+        let abi_type = field
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("`{}.{}` needs a type", key, name))?;
+
+        if !is_identifier(name) || is_reserved_name(name) {
+            return Err(format!("`{}.{}` is not a valid field name", key, name));
+        }
+        if parsed.iter().any(|field| field.name == name) {
+            return Err(format!("`{}.{}` is declared twice", key, name));
+        }
+        let abi_type = canonical_type(abi_type).ok_or_else(|| {
+            format!(
+                "`{}.{}` has the unsupported type `{}`, use address, bool, bytes, string, bytes<1-32>, int<8-256> or uint<8-256>",
+                key, name, abi_type
+            )
+        })?;
+
+        parsed.push(Field {
+            name: name.to_string(),
+            abi_type,
+        });
+    }
+    Ok(parsed)
+}
+
+/// @notice Function to load and validate a schema file
+/// @param path The path to the schema file
+/// @return A result containing the schema, or a description of why it is invalid
+fn load_schema(path: &Path) -> Result<Schema, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let schema: Table = content
+        .parse()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let contract_name = schema
+        .get("contract")
+        .and_then(Value::as_str)
+        .unwrap_or(DEFAULT_CONTRACT_NAME)
+        .to_string();
+    if !is_identifier(&contract_name) {
+        return Err(format!("`{}` is not a valid contract name", contract_name));
+    }
+
+    Ok(Schema {
+        contract_name,
+        input: parse_fields(&schema, "input")?,
+        notice: parse_fields(&schema, "notice")?,
+    })
+}
+
+/// @notice Function to check a schema file is valid before a project is created from it
+/// @param schema_path The path to the schema file
+/// @return true if the schema is valid
+pub fn check_schema(schema_path: &Path) -> bool {
+    match load_schema(schema_path) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("❌ {} {}", "Invalid schema:".red(), e.red());
+            false
+        }
+    }
+}
+
+/// @notice Function to get the name of the adapter contract generated from a schema file
+/// @param schema_path The path to the schema file
+/// @return an Option containing the contract name, None if the schema is invalid
+pub fn schema_contract_name(schema_path: &Path) -> Option<String> {
+    load_schema(schema_path)
+        .ok()
+        .map(|schema| schema.contract_name)
+}
+
+/// @notice Function to get the Solidity declaration of a field, with a data location for reference types
+/// @param field The field to declare
+/// @return the type and name of the field, e.g. `string memory name`
+fn solidity_parameter(field: &Field) -> String {
+    if field.abi_type == "bytes" || field.abi_type == "string" {
+        format!("{} memory {}", field.abi_type, field.name)
+    } else {
+        format!("{} {}", field.abi_type, field.name)
+    }
+}
+
+/// @notice Function to generate the adapter contract of a schema
+/// @param schema The schema of the program
+/// @return the source of the Solidity contract
+fn generate_solidity(schema: &Schema) -> String {
+    let input_parameters: Vec<String> = schema.input.iter().map(solidity_parameter).collect();
+    let input_names: Vec<&str> = schema.input.iter().map(|f| f.name.as_str()).collect();
+    let notice_parameters: Vec<String> = schema.notice.iter().map(solidity_parameter).collect();
+    let notice_types: Vec<&str> = schema.notice.iter().map(|f| f.abi_type.as_str()).collect();
+    let notice_names: Vec<&str> = schema.notice.iter().map(|f| f.name.as_str()).collect();
+
+    let event_parameters = std::iter::once(String::from("bytes32 indexed payloadHash"))
+        .chain(
+            schema
+                .notice
+                .iter()
+                .map(|field| format!("{} {}", field.abi_type, field.name)),
+        )
+        .collect::<Vec<String>>()
+        .join(", ");
+    let event_arguments = std::iter::once("payloadHash")
+        .chain(notice_names.iter().copied())
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    let decode = match notice_parameters.len() {
+        0 => String::new(),
+        1 => format!(
+            "        {} = abi.decode(notice, ({}));\n",
+            notice_parameters[0], notice_types[0]
+        ),
+        _ => format!(
+            "        ({}) = abi.decode(notice, ({}));\n",
+            notice_parameters.join(", "),
+            notice_types.join(", ")
+        ),
+    };
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+import "../lib/coprocessor-base-contract/src/CoprocessorAdapter.sol";
+
+/// @notice Generated by `cartesi-coprocessor codegen` from the schema of the program
+contract {name} is CoprocessorAdapter {{
+    event NoticeReceived({event_parameters});
+
+    constructor(address _taskIssuerAddress, bytes32 _machineHash)
+        CoprocessorAdapter(_taskIssuerAddress, _machineHash)
+    {{}}
+
+    function runExecution({input_parameters}) external {{
+        callCoprocessor(abi.encode({input_names}));
+    }}
+
+    function handleNotice(bytes32 payloadHash, bytes memory notice) internal override {{
+{decode}        emit NoticeReceived({event_arguments});
+        // Add logic for handling the decoded notice.
+    }}
+
+    // Add your other app logic here
+}}
+"#,
+        name = schema.contract_name,
+        event_parameters = event_parameters,
+        input_parameters = input_parameters.join(", "),
+        input_names = input_names.join(", "),
+        decode = decode,
+        event_arguments = event_arguments,
+    )
+}
+
+/// @notice Function to format the names or types of fields as a list literal, shared by python and javascript
+/// @param values The values to list
+/// @return the list literal, e.g. `["uint256", "string"]`
+fn list_literal(values: &[&str]) -> String {
+    let quoted: Vec<String> = values
+        .iter()
+        .map(|value| format!("\"{}\"", value))
+        .collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+/// @notice Function to generate the encoding helpers of a schema in a backend language
+/// @param schema The schema of the program
+/// @param language The backend language, python or javascript
+/// @return an Option containing the source of the helpers, None if the language is not supported
+fn generate_backend(schema: &Schema, language: &str) -> Option<String> {
+    let (_, runtime) = ABI_HELPERS.iter().find(|(name, _)| *name == language)?;
+    let field_names = |fields: &[Field]| -> Vec<String> {
+        fields.iter().map(|field| field.name.clone()).collect()
+    };
+    let field_types = |fields: &[Field]| -> Vec<String> {
+        fields.iter().map(|field| field.abi_type.clone()).collect()
+    };
+    let as_str = |values: &[String]| -> String {
+        list_literal(&values.iter().map(String::as_str).collect::<Vec<&str>>())
+    };
+
+    let input_names = as_str(&field_names(&schema.input));
+    let input_types = as_str(&field_types(&schema.input));
+    let notice_names = field_names(&schema.notice);
+    let notice_types = as_str(&field_types(&schema.notice));
+
+    let typed = match language {
+        "python" => format!(
+            r#"
+
+INPUT_FIELDS = {input_names}
+INPUT_TYPES = {input_types}
+NOTICE_TYPES = {notice_types}
+
+
+def decode_input(payload):
+    """Decodes the hex payload of a task into a dict of the fields passed to runExecution"""
+    values = abi_decode(INPUT_TYPES, bytes.fromhex(payload[2:]))
+    return dict(zip(INPUT_FIELDS, values))
+
+
+def encode_notice({notice_arguments}):
+    """Encodes the fields of a notice into the hex payload decoded by handleNotice"""
+    return "0x" + abi_encode(NOTICE_TYPES, [{notice_arguments}]).hex()
+"#,
+            input_names = input_names,
+            input_types = input_types,
+            notice_types = notice_types,
+            notice_arguments = notice_names.join(", "),
+        ),
+        _ => format!(
+            r#"
+const INPUT_FIELDS = {input_names};
+const INPUT_TYPES = {input_types};
+const NOTICE_TYPES = {notice_types};
+
+// Decodes the hex payload of a task into an object of the fields passed to runExecution
+function decode_input(payload) {{
+  const values = abi_decode(INPUT_TYPES, Buffer.from(payload.slice(2), "hex"));
+  return Object.fromEntries(INPUT_FIELDS.map((name, index) => [name, values[index]]));
+}}
+
+// Encodes the fields of a notice into the hex payload decoded by handleNotice
+function encode_notice({{ {notice_arguments} }}) {{
+  return "0x" + abi_encode(NOTICE_TYPES, [{notice_arguments}]).toString("hex");
+}}
+
+module.exports = {{ abi_encode, abi_decode, decode_input, encode_notice }};
+"#,
+            input_names = input_names,
+            input_types = input_types,
+            notice_types = notice_types,
+            notice_arguments = notice_names.join(", "),
+        ),
+    };
+    Some(format!("{}{}", runtime, typed))
+}
+
+/// @notice Function to guess the backend language of a program from the files in its directory
+/// @param project_dir The directory of the program
+/// @return an Option containing the language, None if it could not be guessed
+fn detect_language(project_dir: &Path) -> Option<&'static str> {
+    if project_dir.join("tsconfig.json").exists() {
+        Some("typescript")
+    } else if project_dir.join("package.json").exists() {
+        Some("javascript")
+    } else if project_dir.join("requirements.txt").exists() || project_dir.join("dapp.py").exists()
+    {
+        Some("python")
+    } else {
+        None
+    }
+}
+
+/// @notice Function to write a generated file, refusing to replace an existing file unless forced
+/// @param path The path of the file
+/// @param content The content of the file
+/// @param force Whether an existing file can be replaced
+/// @return true if the file was written
+fn write_generated(path: &Path, content: &str, force: bool) -> bool {
+    if path.exists() && !force {
+        eprintln!(
+            "❌ {} {}, {}",
+            path.display().to_string().red(),
+            "already exists".red(),
+            "use --force to replace it.".yellow()
+        );
+        return false;
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match fs::write(path, content) {
+        Ok(_) => {
+            println!(
+                "✅ {} {}",
+                "Generated".green(),
+                path.display().to_string().green()
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("Error writing {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// @notice Function to generate the adapter contract and the backend encoding helpers of a program from its schema
+/// @param project_dir The directory of the program, containing the `contracts` foundry project
+/// @param schema_path The path to the schema file
+/// @param language The backend language, if None it is guessed from the files of the program
+/// @param force Whether existing files can be replaced
+/// @return true if every file was generated
+pub fn generate_from_schema(
+    project_dir: &Path,
+    schema_path: &Path,
+    language: Option<&str>,
+    force: bool,
+) -> bool {
+    let schema = match load_schema(schema_path) {
+        Ok(schema) => schema,
+        Err(e) => {
+            eprintln!("❌ {} {}", "Invalid schema:".red(), e.red());
+            return false;
+        }
+    };
+
+    let contract_path = foundry_dir(project_dir)
+        .join("src")
+        .join(format!("{}.sol", schema.contract_name));
+    if !write_generated(&contract_path, &generate_solidity(&schema), force) {
+        return false;
+    }
+
+    let language = language
+        .map(|language| language.to_lowercase())
+        .or_else(|| detect_language(project_dir).map(String::from));
+    let Some(language) = language else {
+        println!(
+            "{}",
+            "Could not detect the language of the program, pass --language to generate the encoding helpers."
+                .yellow()
+        );
+        return true;
+    };
+    let Some(helpers) = generate_backend(&schema, &language) else {
+        let supported: Vec<&str> = ABI_HELPERS.iter().map(|(name, _)| *name).collect();
+        println!(
+            "{} {}{} {}",
+            "Encoding helpers are not available for".yellow(),
+            language.yellow(),
+            ", they can be generated for".yellow(),
+            supported.join(" and ").yellow()
+        );
+        return true;
+    };
+
+    if language == "python" {
+        let helpers_path = project_dir.join("coprocessor_abi.py");
+        if !write_generated(&helpers_path, &helpers, force) {
+            return false;
+        }
+
+        // The python template only copies dapp.py into the image
+        let dockerfile = fs::read_to_string(project_dir.join("Dockerfile")).unwrap_or_default();
+        if !dockerfile.contains("*.py") && !dockerfile.contains("coprocessor_abi.py") {
+            println!(
+                "{}",
+                "Add `COPY ./coprocessor_abi.py .` to your Dockerfile so the helpers are part of the machine."
+                    .yellow()
+            );
+        }
+        true
+    } else {
+        let src_dir = project_dir.join("src");
+        let helpers_dir = if src_dir.is_dir() {
+            src_dir
+        } else {
+            project_dir.to_path_buf()
+        };
+        write_generated(&helpers_dir.join("coprocessor_abi.js"), &helpers, force)
+    }
+}
+
+/// @notice Entry point function to generate the adapter contract and encoding helpers of the program in the current directory
+/// @param schema The path to the schema file
+/// @param language The backend language, if None it is guessed from the files of the program
+/// @param force Whether existing files can be replaced
+pub fn codegen(schema: String, language: Option<String>, force: bool) {
+    let Some(project_dir) = find_project_dir() else {
+        eprintln!(
+            "❌ {}",
+            "No contracts directory found, please run this command in the directory of your Cartesi program."
+                .red()
+        );
+        return;
+    };
+
+    generate_from_schema(&project_dir, Path::new(&schema), language.as_deref(), force);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process::Command;
+
+    fn fields(schema: &str, key: &str) -> Result<Vec<Field>, String> {
+        parse_fields(&schema.parse::<Table>().unwrap(), key)
+    }
+
+    fn field(name: &str, abi_type: &str) -> Field {
+        Field {
+            name: name.to_string(),
+            abi_type: abi_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn resolves_type_aliases() {
+        assert_eq!(canonical_type("uint").unwrap(), "uint256");
+        assert_eq!(canonical_type(" int ").unwrap(), "int256");
+        for abi_type in [
+            "address", "bool", "bytes", "string", "uint8", "int256", "bytes32",
+        ] {
+            assert_eq!(canonical_type(abi_type).unwrap(), abi_type);
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_types() {
+        for abi_type in [
+            "uint7",
+            "uint264",
+            "int0",
+            "bytes0",
+            "bytes33",
+            "uint08",
+            "uint+8",
+            "uint256[]",
+            "tuple",
+            "",
+        ] {
+            assert!(canonical_type(abi_type).is_none(), "{}", abi_type);
+        }
+    }
+
+    #[test]
+    fn parses_fields_in_order() {
+        let parsed = fields(
+            r#"input = [{ name = "owner", type = "address" }, { name = "amount", type = "uint" }]"#,
+            "input",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(
+            (parsed[0].name.as_str(), parsed[0].abi_type.as_str()),
+            ("owner", "address")
+        );
+        assert_eq!(
+            (parsed[1].name.as_str(), parsed[1].abi_type.as_str()),
+            ("amount", "uint256")
+        );
+        assert!(fields("", "notice").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_fields() {
+        let invalid = [
+            (r#"input = "owner""#, "must be an array"),
+            (r#"input = [{ type = "address" }]"#, "needs a name"),
+            (r#"input = [{ name = "owner" }]"#, "needs a type"),
+            (
+                r#"input = [{ name = "1st", type = "bool" }]"#,
+                "not a valid field name",
+            ),
+            (
+                r#"input = [{ name = "notice", type = "bool" }]"#,
+                "not a valid field name",
+            ),
+            (
+                r#"input = [{ name = "a", type = "bool" }, { name = "a", type = "bool" }]"#,
+                "declared twice",
+            ),
+            (
+                r#"input = [{ name = "a", type = "float" }]"#,
+                "unsupported type",
+            ),
+        ];
+        for (schema, error) in invalid {
+            assert!(
+                fields(schema, "input").unwrap_err().contains(error),
+                "{}",
+                schema
+            );
+        }
+    }
+
+    #[test]
+    fn generates_the_adapter_contract() {
+        let solidity = generate_solidity(&Schema {
+            contract_name: String::from("Voting"),
+            input: vec![field("proposal", "uint256"), field("voter", "address")],
+            notice: vec![field("tally", "uint64"), field("summary", "string")],
+        });
+
+        assert!(solidity.contains("contract Voting is CoprocessorAdapter {"));
+        assert!(
+            solidity.contains("function runExecution(uint256 proposal, address voter) external {")
+        );
+        assert!(solidity.contains("callCoprocessor(abi.encode(proposal, voter));"));
+        assert!(solidity.contains(
+            "event NoticeReceived(bytes32 indexed payloadHash, uint64 tally, string summary);"
+        ));
+        assert!(solidity.contains(
+            "(uint64 tally, string memory summary) = abi.decode(notice, (uint64, string));"
+        ));
+        assert!(solidity.contains("emit NoticeReceived(payloadHash, tally, summary);"));
+    }
+
+    #[test]
+    fn decodes_single_and_empty_notices() {
+        let single = generate_solidity(&Schema {
+            contract_name: String::from("Echo"),
+            input: vec![],
+            notice: vec![field("message", "bytes")],
+        });
+        assert!(single.contains("        bytes memory message = abi.decode(notice, (bytes));"));
+        assert!(single.contains("function runExecution() external {"));
+
+        let empty = generate_solidity(&Schema {
+            contract_name: String::from("Ping"),
+            input: vec![],
+            notice: vec![],
+        });
+        assert!(!empty.contains("abi.decode"));
+        assert!(empty.contains("emit NoticeReceived(payloadHash);"));
+    }
+
+    #[test]
+    fn rejects_reserved_names() {
+        for name in [
+            "uint256",
+            "bytes32",
+            "int8",
+            "fixed128x18",
+            "public",
+            "external",
+            "returns",
+            "struct",
+            "override",
+            "else",
+            "try",
+            "catch",
+            "abi",
+            "lambda",
+            "pass",
+            "yield",
+            "with",
+            "raise",
+            "global",
+            "async",
+            "await",
+            "assert",
+            "del",
+            "default",
+            "case",
+            "switch",
+            "do",
+            "throw",
+            "typeof",
+            "void",
+            "super",
+            "enum",
+            "export",
+            "encode_notice",
+            "NOTICE_TYPES",
+        ] {
+            assert!(is_reserved_name(name), "{}", name);
+        }
+        for name in [
+            "amount",
+            "owner",
+            "uint256s",
+            "bytes33",
+            "fixedPoint",
+            "message",
+        ] {
+            assert!(!is_reserved_name(name), "{}", name);
+        }
+    }
+
+    /// Schema whose input and notice carry a negative int, dynamic string and bytes, a bytesN, a bool and an address
+    fn vector_schema() -> Schema {
+        let fields = vec![
+            field("delta", "int256"),
+            field("label", "string"),
+            field("blob", "bytes"),
+            field("tag", "bytes4"),
+            field("flag", "bool"),
+            field("owner", "address"),
+        ];
+        Schema {
+            contract_name: String::from("Vectors"),
+            input: fields.clone(),
+            notice: fields,
+        }
+    }
+
+    /// ABI encoding of (-1, "hello", 0x1234, 0xdeadbeef, true, 0x9540...3778)
+    fn vector_payload() -> String {
+        [
+            "f".repeat(64),
+            format!("{:064x}", 0xc0),
+            format!("{:064x}", 0x100),
+            format!("deadbeef{}", "0".repeat(56)),
+            format!("{:064x}", 1),
+            format!("{:0>64}", "95401dc811bb5740090279ba06cfa8fcf6113778"),
+            format!("{:064x}", 5),
+            format!("68656c6c6f{}", "0".repeat(54)),
+            format!("{:064x}", 2),
+            format!("1234{}", "0".repeat(60)),
+        ]
+        .concat()
+    }
+
+    /// Writes the generated helpers of a language into a temporary folder and runs a script importing them
+    fn run_backend(
+        language: &str,
+        interpreter: [&str; 2],
+        module: &str,
+        script: &str,
+    ) -> Option<String> {
+        let dir = env::temp_dir().join(format!("codegen-{}-{}", language, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(module),
+            generate_backend(&vector_schema(), language).unwrap(),
+        )
+        .unwrap();
+
+        let output = Command::new(interpreter[0])
+            .arg(interpreter[1])
+            .arg(script.replace("PAYLOAD", &vector_payload()))
+            .current_dir(&dir)
+            .output();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // The runtimes are only checked where the interpreter is installed
+        let output = output.ok()?;
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    #[test]
+    fn python_helpers_match_abi_vectors() {
+        let Some(output) = run_backend(
+            "python",
+            ["python3", "-c"],
+            "coprocessor_abi.py",
+            r#"
+import coprocessor_abi as abi
+print(abi.encode_notice(-1, "hello", "0x1234", "0xdeadbeef", True, "0x95401dc811bb5740090279ba06cfa8fcf6113778"))
+print(sorted(abi.decode_input("0xPAYLOAD").items()))
+print(abi.abi_decode(["int8", "uint8"], abi.abi_encode(["int8", "uint8"], [-128, 255])))
+"#,
+        ) else {
+            return;
+        };
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], format!("0x{}", vector_payload()));
+        assert_eq!(
+            lines[1],
+            "[('blob', '0x1234'), ('delta', -1), ('flag', True), ('label', 'hello'), \
+            ('owner', '0x95401dc811bb5740090279ba06cfa8fcf6113778'), ('tag', '0xdeadbeef')]"
+        );
+        assert_eq!(lines[2], "[-128, 255]");
+    }
+
+    #[test]
+    fn javascript_helpers_match_abi_vectors() {
+        let Some(output) = run_backend(
+            "javascript",
+            ["node", "-e"],
+            "coprocessor_abi.js",
+            r#"
+const abi = require("./coprocessor_abi.js");
+console.log(abi.encode_notice({ delta: -1, label: "hello", blob: "0x1234", tag: "0xdeadbeef", flag: true, owner: "0x95401dc811bb5740090279ba06cfa8fcf6113778" }));
+const input = abi.decode_input("0xPAYLOAD");
+console.log(Object.keys(input).sort().map((key) => `${key}=${input[key]}`).join(","));
+console.log(abi.abi_decode(["int8", "uint8"], abi.abi_encode(["int8", "uint8"], [-128, 255])).join(","));
+"#,
+        ) else {
+            return;
+        };
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], format!("0x{}", vector_payload()));
+        assert_eq!(
+            lines[1],
+            "blob=0x1234,delta=-1,flag=true,label=hello,\
+            owner=0x95401dc811bb5740090279ba06cfa8fcf6113778,tag=0xdeadbeef"
+        );
+        assert_eq!(lines[2], "-128,255");
+    }
+}
@@ -0,0 +1,120 @@
+use crate::commands::deploy::load_deployment_records;
+use crate::commands::deploy_script::foundry_dir;
+use crate::commands::snapshot::snapshots_path;
+use crate::helpers::config::{get_network_profile, NETWORK_NAMES};
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::CompleteEnv;
+use clap_complete::Shell;
+use colored::Colorize;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// @notice Name the completion scripts call the cli by, so they keep working when the binary is reinstalled
+const BIN_NAME: &str = "cartesi-coprocessor";
+
+/// @notice Function to complete network names with the chain id of each network profile
+/// @return the completion candidates for `--network`
+pub fn network_candidates() -> Vec<CompletionCandidate> {
+    NETWORK_NAMES
+        .iter()
+        .filter_map(|name| get_network_profile(name))
+        .map(|profile| {
+            CompletionCandidate::new(&profile.name)
+                .help(Some(format!("chain {}", profile.chain_id).into()))
+        })
+        .collect()
+}
+
+/// @notice Function to complete the names of the contracts in the foundry project of the current directory
+/// @return the completion candidates for `--contract-name`
+pub fn contract_candidates() -> Vec<CompletionCandidate> {
+    let current_dir = env::current_dir().unwrap_or_default();
+    let Ok(entries) = fs::read_dir(foundry_dir(&current_dir).join("src")) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "sol"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// @notice Function to complete the addresses of the contracts recorded in deployment_history, latest first
+/// @return the completion candidates for `--address`, with the contract name and network of each deployment
+pub fn deployment_candidates() -> Vec<CompletionCandidate> {
+    load_deployment_records()
+        .into_iter()
+        .rev()
+        .filter_map(|(_, record)| {
+            let address = record["deployed_to"].as_str().filter(|a| !a.is_empty())?;
+            let help = format!(
+                "{} on {}",
+                record["contract_name"]
+                    .as_str()
+                    .unwrap_or("unknown contract"),
+                record["network"].as_str().unwrap_or("unknown network")
+            );
+            Some(CompletionCandidate::new(address).help(Some(help.into())))
+        })
+        .collect()
+}
+
+/// @notice Function to complete the names of the saved devnet snapshots
+/// @return the completion candidates for `devnet restore`
+pub fn snapshot_candidates() -> Vec<CompletionCandidate> {
+    let Ok(entries) = fs::read_dir(snapshots_path()) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// @notice Entry point function to print the completion script of a shell
+/// @notice The script calls back into the cli on each completion, so networks, contracts and deployments are completed from the current project
+/// @param shell The shell to print the completion script for
+/// @param command The function building the clap command of the cli
+pub fn completions(shell: Shell, command: fn() -> clap::Command) {
+    env::set_var("COMPLETE", shell.to_string());
+
+    if let Err(e) = CompleteEnv::with_factory(command).try_complete([BIN_NAME], None) {
+        eprintln!("❌ {} {}", "Failed to generate completions:".red(), e);
+    }
+}
+
+/// @notice Entry point function to generate the man pages of the cli
+/// @param command The clap command of the cli
+/// @param output The folder to write a page per command into, the page of the top level command is printed if None
+pub fn man(command: clap::Command, output: Option<String>) {
+    let Some(output) = output else {
+        if let Err(e) = clap_mangen::Man::new(command).render(&mut io::stdout()) {
+            eprintln!("❌ {} {}", "Failed to generate man page:".red(), e);
+        }
+        return;
+    };
+
+    let output = PathBuf::from(output);
+    if let Err(e) =
+        fs::create_dir_all(&output).and_then(|_| clap_mangen::generate_to(command, &output))
+    {
+        eprintln!("❌ {} {}", "Failed to generate man pages:".red(), e);
+        return;
+    }
+    println!(
+        "✅ {} {}",
+        "Successfully generated man pages in".green(),
+        output.display().to_string().green()
+    );
+}
@@ -0,0 +1,603 @@
+use crate::commands::create::BASE_CONTRACT_REPOSITORY;
+use crate::commands::deploy_script::{find_project_dir, foundry_dir};
+use crate::helpers::helpers::{confirm, get_spinner};
+use crate::helpers::process::{run_captured, LONG_TIMEOUT};
+use colored::Colorize;
+use reqwest::blocking::Client;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::process::Command;
+use std::time::Duration;
+
+/// @notice Path of the base contract library inside the foundry project
+const BASE_CONTRACT_LIB: &str = "lib/coprocessor-base-contract";
+
+/// @notice Function to run a command in the foundry project and capture its output
+/// @param program The program to run, forge or git
+/// @param args The arguments to pass to the program
+/// @param work_dir The path to the foundry project for the dapp
+/// @return A result containing the stdout of the command, or its stderr if it failed
+pub fn run_in_project(program: &str, args: &[&str], work_dir: &Path) -> Result<String, String> {
+    let output = run_captured(
+        Command::new(program).args(args).current_dir(work_dir),
+        LONG_TIMEOUT,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// @notice Function to parse a `vMAJOR.MINOR.PATCH` tag so versions can be ordered
+/// @param tag The tag to parse
+/// @return an Option containing the numbers of the version, None if the tag is not a version
+fn parse_version(tag: &str) -> Option<Vec<u64>> {
+    tag.trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse::<u64>().ok())
+        .collect()
+}
+
+/// @notice Function to detect the version of the base contract installed in a foundry project
+/// @notice The tag checked out by forge is used, or the tag recorded in foundry.lock by recent forge versions
+/// @param work_dir The path to the foundry project for the dapp
+/// @return an Option containing the installed tag, None if it could not be detected
+pub fn installed_base_contract_version(work_dir: &Path) -> Option<String> {
+    let lib_dir = work_dir.join(BASE_CONTRACT_LIB);
+    if !lib_dir.is_dir() {
+        return None;
+    }
+
+    // Vendored copies have no git metadata of their own, git would describe the project instead
+    if lib_dir.join(".git").exists() {
+        if let Ok(tag) = run_in_project("git", &["describe", "--tags", "--exact-match"], &lib_dir) {
+            return Some(tag);
+        }
+    }
+
+    let foundry_lock: serde_json::Value = fs::read_to_string(work_dir.join("foundry.lock"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())?;
+    foundry_lock[BASE_CONTRACT_LIB]["tag"]["name"]
+        .as_str()
+        .map(String::from)
+}
+
+/// @notice Function to list the released versions of the base contract
+/// @return A result containing the version tags from oldest to newest, or the error git reported
+fn available_base_contract_versions() -> Result<Vec<String>, String> {
+    let current_dir = std::env::current_dir().expect("Failed to get current directory");
+    let tags = run_in_project(
+        "git",
+        &["ls-remote", "--tags", "--refs", BASE_CONTRACT_REPOSITORY],
+        &current_dir,
+    )?;
+
+    let mut versions: Vec<(Vec<u64>, String)> = tags
+        .lines()
+        .filter_map(|line| line.split("refs/tags/").nth(1))
+        .filter_map(|tag| parse_version(tag).map(|version| (version, tag.to_string())))
+        .collect();
+    versions.sort();
+    Ok(versions.into_iter().map(|(_, tag)| tag).collect())
+}
+
+/// @notice Function to print the release notes of the base contract versions between two versions
+/// @param from The installed version, if None every release up to `to` is listed
+/// @param to The version to upgrade to
+fn print_changelog(from: Option<&str>, to: &str) {
+    let compare_url = match from {
+        Some(from) => format!("{}/compare/{}...{}", BASE_CONTRACT_REPOSITORY, from, to),
+        None => format!("{}/releases", BASE_CONTRACT_REPOSITORY),
+    };
+    let releases_url = format!(
+        "https://api.github.com/repos/{}/releases",
+        BASE_CONTRACT_REPOSITORY.trim_start_matches("https://github.com/")
+    );
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("cartesi-coprocessor")
+        .build()
+        .expect("Failed to build HTTP client");
+    let releases: Vec<serde_json::Value> = client
+        .get(releases_url)
+        .send()
+        .ok()
+        .filter(|response| response.status().is_success())
+        .and_then(|response| response.json().ok())
+        .unwrap_or_default();
+
+    let lower = from.and_then(parse_version);
+    let upper = parse_version(to);
+    let mut delta: Vec<(Vec<u64>, &serde_json::Value)> = releases
+        .iter()
+        .filter_map(|release| {
+            let version = parse_version(release["tag_name"].as_str()?)?;
+            let newer = lower.as_ref().is_none_or(|lower| version > *lower);
+            let in_range = upper.as_ref().is_none_or(|upper| version <= *upper);
+            (newer && in_range).then_some((version, release))
+        })
+        .collect();
+    delta.sort_by(|a, b| b.0.cmp(&a.0));
+
+    if delta.is_empty() {
+        println!("{} {}", "Changes:".bold(), compare_url);
+        return;
+    }
+    for (_, release) in delta {
+        println!(
+            "{} {}",
+            release["tag_name"].as_str().unwrap_or_default().bold(),
+            release["name"].as_str().unwrap_or_default()
+        );
+        let notes = release["body"].as_str().unwrap_or_default().trim();
+        if !notes.is_empty() {
+            println!("{}", notes);
+        }
+        println!();
+    }
+    println!("{} {}", "Full diff:".bold(), compare_url);
+}
+
+/// @notice Files of the foundry project that forge rewrites when the base contract is removed or installed
+const BASE_CONTRACT_FILES: &[&str] = &["foundry.lock", "remappings.txt"];
+
+/// @notice Function to copy a folder recursively
+/// @param from The folder to copy from
+/// @param to The folder to copy into, created if missing
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let entries =
+        fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, to.join(entry.file_name()))
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// @notice Function to find the git folder of the base contract submodule, which forge deletes on removal
+/// @param lib_dir The folder of the base contract library
+/// @return an Option containing the folder the `.git` file of the submodule points to
+fn submodule_git_dir(lib_dir: &Path) -> Option<PathBuf> {
+    let git_file = fs::read_to_string(lib_dir.join(".git")).ok()?;
+    let git_dir = git_file.trim().strip_prefix("gitdir:")?.trim();
+    Some(lib_dir.join(git_dir))
+}
+
+/// @notice Function to back up the installed base contract before forge removes it,
+/// @notice with the submodule git folder, its commit in the index and the files forge rewrites
+/// @param work_dir The path to the foundry project for the dapp
+/// @return A result containing the backup folder, None if no base contract is installed
+fn backup_base_contract(work_dir: &Path) -> Result<Option<PathBuf>, String> {
+    let lib_dir = work_dir.join(BASE_CONTRACT_LIB);
+    if !lib_dir.exists() {
+        return Ok(None);
+    }
+
+    let backup = env::temp_dir().join(format!("coprocessor-base-contract-{}", process::id()));
+    let _ = fs::remove_dir_all(&backup);
+    copy_dir(&lib_dir, &backup.join("lib"))?;
+    if let Some(git_dir) = submodule_git_dir(&lib_dir).filter(|git_dir| git_dir.is_dir()) {
+        copy_dir(&git_dir, &backup.join("modules"))?;
+    }
+    for file in BASE_CONTRACT_FILES {
+        let path = work_dir.join(file);
+        if path.is_file() {
+            fs::copy(&path, backup.join(file))
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+        }
+    }
+
+    // Keep the commit of the submodule and the .gitmodules of the repository, which can be above
+    // the foundry project, so the gitlink removed by forge can be staged again
+    if let (Ok(stage), Ok(top_level)) = (
+        run_in_project(
+            "git",
+            &["ls-files", "--stage", "--full-name", BASE_CONTRACT_LIB],
+            work_dir,
+        ),
+        run_in_project("git", &["rev-parse", "--show-toplevel"], work_dir),
+    ) {
+        if let Some((commit, path)) = stage
+            .strip_prefix("160000 ")
+            .and_then(|entry| entry.split_once(' '))
+            .and_then(|(commit, rest)| Some((commit, rest.split_once('\t')?.1)))
+        {
+            fs::write(
+                backup.join("gitlink"),
+                format!("160000,{},{}", commit, path),
+            )
+            .map_err(|e| format!("Failed to write the backup: {}", e))?;
+            let gitmodules = Path::new(&top_level).join(".gitmodules");
+            if gitmodules.is_file() {
+                fs::copy(&gitmodules, backup.join("gitmodules"))
+                    .map_err(|e| format!("Failed to copy {}: {}", gitmodules.display(), e))?;
+            }
+        }
+    }
+    Ok(Some(backup))
+}
+
+/// @notice Function to put a backed up base contract back in place of a failed or rejected install
+/// @param work_dir The path to the foundry project for the dapp
+/// @param backup The folder created by backup_base_contract
+/// @return A result containing nothing, or the step that failed
+fn restore_base_contract(work_dir: &Path, backup: &Path) -> Result<(), String> {
+    let lib_dir = work_dir.join(BASE_CONTRACT_LIB);
+    if lib_dir.exists() {
+        fs::remove_dir_all(&lib_dir)
+            .map_err(|e| format!("Failed to remove {}: {}", lib_dir.display(), e))?;
+    }
+    copy_dir(&backup.join("lib"), &lib_dir)?;
+    if backup.join("modules").is_dir() {
+        if let Some(git_dir) = submodule_git_dir(&lib_dir) {
+            let _ = fs::remove_dir_all(&git_dir);
+            copy_dir(&backup.join("modules"), &git_dir)?;
+        }
+    }
+
+    // Files forge created for the failed install are removed, the others are put back
+    for file in BASE_CONTRACT_FILES {
+        let path = work_dir.join(file);
+        if backup.join(file).is_file() {
+            fs::copy(backup.join(file), &path)
+                .map_err(|e| format!("Failed to restore {}: {}", path.display(), e))?;
+        } else if path.is_file() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+
+    if let Ok(gitlink) = fs::read_to_string(backup.join("gitlink")) {
+        let top_level = PathBuf::from(run_in_project(
+            "git",
+            &["rev-parse", "--show-toplevel"],
+            work_dir,
+        )?);
+        let path = gitlink.splitn(3, ',').last().unwrap_or_default();
+        if backup.join("gitmodules").is_file() {
+            fs::copy(backup.join("gitmodules"), top_level.join(".gitmodules"))
+                .map_err(|e| format!("Failed to restore .gitmodules: {}", e))?;
+            run_in_project("git", &["add", ".gitmodules"], &top_level)?;
+        }
+        run_in_project(
+            "git",
+            &["rm", "--cached", "-r", "-q", "--ignore-unmatch", path],
+            &top_level,
+        )?;
+        run_in_project(
+            "git",
+            &["update-index", "--add", "--cacheinfo", &gitlink],
+            &top_level,
+        )?;
+    }
+    Ok(())
+}
+
+/// @notice Function to roll back to the backed up base contract and remove the backup,
+/// @notice the backup is kept and its location printed if it cannot be restored
+/// @param work_dir The path to the foundry project for the dapp
+/// @param backup The folder created by backup_base_contract, None if no base contract was installed
+/// @return true if the previous base contract is in place again
+fn rollback_base_contract(work_dir: &Path, backup: Option<PathBuf>) -> bool {
+    let Some(backup) = backup else {
+        return false;
+    };
+    match restore_base_contract(work_dir, &backup) {
+        Ok(()) => {
+            let _ = fs::remove_dir_all(&backup);
+            true
+        }
+        Err(e) => {
+            eprintln!(
+                "❌ {} {}",
+                "Failed to restore the previous coprocessor-base-contract:".red(),
+                e.red()
+            );
+            println!(
+                "{} {}",
+                "The previous version is kept in".yellow(),
+                backup.display().to_string().yellow()
+            );
+            false
+        }
+    }
+}
+
+/// @notice Function to remove the installed base contract and install another version with forge
+/// @param work_dir The path to the foundry project for the dapp
+/// @param version The tag to install
+/// @return true if the version was installed
+fn replace_base_contract(work_dir: &Path, version: &str) -> bool {
+    if work_dir.join(BASE_CONTRACT_LIB).exists() {
+        if let Err(e) = run_in_project("forge", &["remove", "--force", BASE_CONTRACT_LIB], work_dir)
+        {
+            eprintln!(
+                "❌ {}",
+                "Failed to remove the installed base contract.".red()
+            );
+            eprintln!("{} {}", "FORGE::ERROR::".red(), e.red());
+            return false;
+        }
+    }
+
+    match run_in_project(
+        "forge",
+        &[
+            "install",
+            &format!("{}@{}", BASE_CONTRACT_REPOSITORY, version),
+            "--no-commit",
+        ],
+        work_dir,
+    ) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!(
+                "❌ {} {}",
+                "Failed to install coprocessor-base-contract".red(),
+                version.red()
+            );
+            eprintln!("{} {}", "FORGE::ERROR::".red(), e.red());
+            false
+        }
+    }
+}
+
+/// @notice Function to install a version of the base contract with forge, replacing the installed one
+/// @notice The installed version is backed up first and restored if the install fails
+/// @param work_dir The path to the foundry project for the dapp
+/// @param version The tag to install
+/// @param backup The folder created by backup_base_contract, None if no base contract is installed
+/// @return true if the version was installed, the backup is left for the caller to remove or roll back to
+fn install_over_backup(work_dir: &Path, version: &str, backup: &Option<PathBuf>) -> bool {
+    if replace_base_contract(work_dir, version) {
+        return true;
+    }
+    if rollback_base_contract(work_dir, backup.clone()) {
+        println!(
+            "{}",
+            "Restored the previously installed coprocessor-base-contract.".yellow()
+        );
+    }
+    false
+}
+
+/// @notice Function to back up the installed base contract, reporting a failure
+/// @param work_dir The path to the foundry project for the dapp
+/// @return an Option containing the result of backup_base_contract, None if the backup failed
+fn backup_installed_base_contract(work_dir: &Path) -> Option<Option<PathBuf>> {
+    match backup_base_contract(work_dir) {
+        Ok(backup) => Some(backup),
+        Err(e) => {
+            eprintln!(
+                "❌ {} {}",
+                "Failed to back up the installed base contract:".red(),
+                e.red()
+            );
+            None
+        }
+    }
+}
+
+/// @notice Function to install a version of the base contract with forge, replacing the installed one
+/// @notice The installed version is backed up first and restored if the install fails
+/// @param work_dir The path to the foundry project for the dapp
+/// @param version The tag to install
+/// @return true if the version was installed
+pub fn install_base_contract_version(work_dir: &Path, version: &str) -> bool {
+    let Some(backup) = backup_installed_base_contract(work_dir) else {
+        return false;
+    };
+    let installed = install_over_backup(work_dir, version, &backup);
+    if let Some(backup) = backup.filter(|_| installed) {
+        let _ = fs::remove_dir_all(backup);
+    }
+    installed
+}
+
+/// @notice Entry point function to upgrade the base contract of the project to a newer version,
+/// @notice rolling back to the installed version if the project no longer compiles
+/// @param version The tag to upgrade to, defaults to the latest release
+/// @param check Only show the installed and latest versions with the changes between them
+/// @param yes Upgrade without asking for confirmation
+pub fn contracts_upgrade(version: Option<String>, check: bool, yes: bool) {
+    let Some(work_dir) = find_project_dir().map(|project_dir| foundry_dir(&project_dir)) else {
+        eprintln!(
+            "❌ {}",
+            "No contracts directory found, please run this command in the directory of your Cartesi program."
+                .red()
+        );
+        return;
+    };
+
+    let installed = installed_base_contract_version(&work_dir);
+    let spinner = get_spinner();
+    spinner.set_message("Fetching coprocessor-base-contract releases...");
+    let versions = available_base_contract_versions();
+    spinner.finish_and_clear();
+
+    let versions = match versions {
+        Ok(versions) => versions,
+        Err(e) => {
+            eprintln!(
+                "❌ {}",
+                "Failed to list coprocessor-base-contract releases.".red()
+            );
+            println!("{} {}", "GIT::RESPONSE::".red(), e.red());
+            return;
+        }
+    };
+    let Some(latest) = versions.last() else {
+        eprintln!(
+            "❌ {}",
+            "No coprocessor-base-contract releases found.".red()
+        );
+        return;
+    };
+
+    let target = version.unwrap_or_else(|| latest.clone());
+    if !versions.contains(&target) {
+        eprintln!(
+            "❌ {} '{}'. Available versions: {}",
+            "Unknown coprocessor-base-contract version".red(),
+            target.red(),
+            versions.join(", ").green()
+        );
+        return;
+    }
+
+    let data = vec![
+        (
+            "Installed",
+            installed.clone().unwrap_or_else(|| String::from("unknown")),
+        ),
+        ("Latest", latest.clone()),
+    ];
+
+    // Calculate the width of the first column
+    let max_width = data.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, value) in data {
+        println!("{:<width$}  {}", name, value, width = max_width);
+    }
+    println!();
+
+    if installed.as_deref() == Some(target.as_str()) {
+        println!(
+            "✅ {} {}",
+            "coprocessor-base-contract is already at".green(),
+            target.green()
+        );
+        return;
+    }
+    print_changelog(installed.as_deref(), &target);
+
+    if check || (!yes && !confirm(&format!("Upgrade coprocessor-base-contract to {}?", target))) {
+        return;
+    }
+
+    let Some(backup) = backup_installed_base_contract(&work_dir) else {
+        return;
+    };
+    if !install_over_backup(&work_dir, &target, &backup) {
+        return;
+    }
+    match run_in_project("forge", &["build"], &work_dir) {
+        Ok(_) => {
+            if let Some(backup) = backup {
+                let _ = fs::remove_dir_all(backup);
+            }
+            println!(
+                "✅ {} {}",
+                "Successfully upgraded coprocessor-base-contract to".green(),
+                target.green()
+            )
+        }
+        Err(e) => {
+            eprintln!(
+                "❌ {} {}",
+                "The project does not compile with coprocessor-base-contract".red(),
+                target.red()
+            );
+            eprintln!("{} {}", "FORGE::ERROR::".red(), e.red());
+
+            if rollback_base_contract(&work_dir, backup) {
+                println!(
+                    "{} {}",
+                    "Rolled back coprocessor-base-contract to".yellow(),
+                    installed
+                        .unwrap_or_else(|| String::from("the previous version"))
+                        .yellow()
+                )
+            } else {
+                println!(
+                    "{}",
+                    "Please update your contracts for the new base contract, or install the previous version again."
+                        .yellow()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vendored_base_contract_is_not_described_with_the_project_tag() {
+        let work_dir = env::temp_dir().join(format!("base-contract-version-{}", process::id()));
+        fs::create_dir_all(work_dir.join(BASE_CONTRACT_LIB)).unwrap();
+        let git = |args: &[&str]| run_in_project("git", args, &work_dir).unwrap();
+        git(&["init", "-q"]);
+        git(&[
+            "-c",
+            "user.name=a",
+            "-c",
+            "user.email=a@a",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "init",
+        ]);
+        git(&["tag", "v9.9.9"]);
+        assert_eq!(installed_base_contract_version(&work_dir), None);
+
+        fs::write(
+            work_dir.join("foundry.lock"),
+            r#"{"lib/coprocessor-base-contract": {"tag": {"name": "v2.2.1"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            installed_base_contract_version(&work_dir).as_deref(),
+            Some("v2.2.1")
+        );
+
+        fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[test]
+    fn restores_a_backed_up_base_contract() {
+        let work_dir = env::temp_dir().join(format!("base-contract-backup-{}", process::id()));
+        let lib_dir = work_dir.join(BASE_CONTRACT_LIB);
+        fs::create_dir_all(lib_dir.join("src")).unwrap();
+        fs::write(lib_dir.join("src/CoprocessorAdapter.sol"), "v1").unwrap();
+        fs::write(work_dir.join("remappings.txt"), "v1").unwrap();
+        assert!(backup_base_contract(&work_dir.join("missing"))
+            .unwrap()
+            .is_none());
+        let backup = backup_base_contract(&work_dir).unwrap().unwrap();
+
+        // A failed install leaves a partial library and a lock file behind
+        fs::remove_dir_all(&lib_dir).unwrap();
+        fs::create_dir_all(lib_dir.join("partial")).unwrap();
+        fs::write(work_dir.join("remappings.txt"), "v2").unwrap();
+        fs::write(work_dir.join("foundry.lock"), "{}").unwrap();
+
+        restore_base_contract(&work_dir, &backup).unwrap();
+        let restored = |path: &str| fs::read_to_string(work_dir.join(path)).unwrap();
+        assert_eq!(
+            restored("lib/coprocessor-base-contract/src/CoprocessorAdapter.sol"),
+            "v1"
+        );
+        assert_eq!(restored("remappings.txt"), "v1");
+        assert!(!lib_dir.join("partial").exists());
+        assert!(!work_dir.join("foundry.lock").exists());
+
+        fs::remove_dir_all(&work_dir).unwrap();
+        fs::remove_dir_all(&backup).unwrap();
+    }
+}
@@ -1,12 +1,152 @@
 use colored::Colorize;
+use include_dir::{include_dir, Dir};
 use std::env;
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use std::{thread, time};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::commands::codegen::{check_schema, generate_from_schema, schema_contract_name};
+use crate::commands::deploy_script::{generate_deploy_script, DEFAULT_CONTRACT_NAME};
+use crate::helpers::config::get_network_profile;
+use crate::helpers::helpers::{add_npm_home_dir_to_path, get_spinner};
+use crate::helpers::process::{report_process_error, run_captured, run_streamed, LONG_TIMEOUT};
+
+/// @notice Templates available on the co-processor branch of the cartesi application templates, with a short description
+pub const TEMPLATES: &[(&str, &str)] = &[
+    ("cpp", "C++ program using the high level rollup http api"),
+    (
+        "cpp-low-level",
+        "C++ program using the low level rollup api",
+    ),
+    ("go", "Go program"),
+    ("java", "Java program"),
+    ("javascript", "JavaScript program running on Node.js"),
+    ("lua", "Lua program"),
+    ("python", "Python program"),
+    ("ruby", "Ruby program"),
+    ("rust", "Rust program"),
+    ("typescript", "TypeScript program running on Node.js"),
+];
+
+/// @notice Version of the coprocessor base contract installed in new projects, from contract-template/base-contract-version, see build.rs
+pub const BASE_CONTRACT_VERSION: &str = env!("BASE_CONTRACT_VERSION");
+
+/// @notice Repository of the coprocessor base contract, installed with forge
+pub const BASE_CONTRACT_REPOSITORY: &str =
+    "https://github.com/Mugen-Builders/coprocessor-base-contract";
+
+/// @notice Program templates embedded in the cli, used to create projects with the --offline flag
+static PROGRAM_TEMPLATES: Dir = include_dir!("$CARGO_MANIFEST_DIR/templates/programs");
+
+/// @notice Solidity libraries vendored from the contract-template submodules when the cli is built, see build.rs
+static VENDORED_LIBRARIES: Dir = include_dir!("$OUT_DIR/vendor");
+
+/// @notice Common short names of templates, mapped to the template they stand for
+const TEMPLATE_ALIASES: &[(&str, &str)] = &[
+    ("c++", "cpp"),
+    ("golang", "go"),
+    ("js", "javascript"),
+    ("node", "javascript"),
+    ("py", "python"),
+    ("rs", "rust"),
+    ("ts", "typescript"),
+];
+
+/// @notice Function to compute the edit distance between two strings, used to suggest templates for typos
+/// @param a The first string
+/// @param b The second string
+/// @return the number of single character insertions, deletions or substitutions turning a into b
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
 
-use crate::helpers::helpers::add_npm_home_dir_to_path;
+/// @notice Function to validate a template name, resolving aliases such as `js` or `py`
+/// @param template The template passed to the create command
+/// @return A result containing the template name, or the templates closest to the one passed
+fn validate_template(template: &str) -> Result<String, Vec<String>> {
+    let template = template.trim().to_lowercase();
+
+    if TEMPLATES.iter().any(|(name, _)| *name == template) {
+        return Ok(template);
+    }
+    if let Some((_, name)) = TEMPLATE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == template)
+    {
+        return Ok(name.to_string());
+    }
+
+    // Suggest the closest templates within two edits, and the templates starting with the value passed
+    let distances: Vec<(usize, &str)> = TEMPLATES
+        .iter()
+        .map(|(name, _)| (edit_distance(&template, name), *name))
+        .collect();
+    let closest = distances
+        .iter()
+        .map(|(distance, _)| *distance)
+        .min()
+        .unwrap_or(0);
+    let mut suggestions: Vec<(usize, &str)> = distances
+        .into_iter()
+        .filter(|(distance, name)| {
+            (*distance == closest && *distance <= 2) || name.starts_with(template.as_str())
+        })
+        .collect();
+    suggestions.sort();
+    Err(suggestions
+        .into_iter()
+        .map(|(_, name)| name.to_string())
+        .collect())
+}
+
+/// @notice Function to check whether a template is embedded in the cli, so it can be created with --offline
+/// @param template The template name
+/// @return true if the program template is embedded
+fn is_available_offline(template: &str) -> bool {
+    PROGRAM_TEMPLATES.get_dir(template).is_some()
+}
+
+/// @notice Function to print the templates available to the create command, marking those available with --offline
+pub fn list_templates() {
+    // Calculate the width of the first two columns
+    let max_width = TEMPLATES
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let description_width = TEMPLATES
+        .iter()
+        .map(|(_, description)| description.len())
+        .max()
+        .unwrap_or(0);
+
+    for (name, description) in TEMPLATES {
+        if is_available_offline(name) {
+            println!(
+                "{:<width$}  {:<description_width$}  {}",
+                name,
+                description,
+                "available offline".green(),
+                width = max_width,
+                description_width = description_width
+            );
+        } else {
+            println!("{:<width$}  {}", name, description, width = max_width);
+        }
+    }
+}
 
 /// @notice Function to create a solidity contract template inheriting the base contract
 /// @param work_dir The path to the foundry project for the dapp
@@ -22,204 +162,614 @@ fn create_contract_template(work_dir: &PathBuf) {
 
 /// @notice Function to install the base contract as a library in the solidity working directory
 /// @param work_dir The path to the foundry project for the dapp
-fn install_base_contract(work_dir: &PathBuf) {
+/// @param base_contract_version The tag of the base contract to install
+fn install_base_contract(work_dir: &PathBuf, base_contract_version: &str) {
     let work_dir = work_dir.join("contracts");
 
-    let mut child = Command::new("forge")
-        .arg("install")
-        .arg("https://github.com/Mugen-Builders/coprocessor-base-contract@v2.2.1")
-        .arg("--no-commit")
-        .current_dir(work_dir.clone())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'forge install' command");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    let start = time::Instant::now();
-
-    // Handle initial output and extract keyphrase
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            if let Ok(line) = line {
-                println!("{} {}", "FORGE:: ".green(), line.green());
-            }
-        }
-        return;
-    });
-
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            if let Ok(line) = line {
-                eprintln!("{} {}", "FORGE::".yellow(), line.yellow());
-            } else if let Err(e) = line {
-                eprintln!("{} {}", "FORGE::ERROR::".red(), e);
-            }
-        }
-    });
-
-    // Wait for email verification or timeout
-    while start.elapsed().as_secs() < 50 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                println!("✅ {}", "Successfully initialized base contract.".green());
-                create_contract_template(&work_dir);
-                break;
-            } else {
-                eprintln!("error installing base contract.");
-                break;
-            }
+    let forge_status = run_streamed(
+        Command::new("forge")
+            .arg("install")
+            .arg(format!(
+                "{}@{}",
+                BASE_CONTRACT_REPOSITORY, base_contract_version
+            ))
+            .arg("--no-commit")
+            .current_dir(work_dir.clone()),
+        "FORGE",
+        LONG_TIMEOUT,
+    );
+
+    match forge_status {
+        Ok(output) if output.status.success() => {
+            println!("✅ {}", "Successfully initialized base contract.".green());
+            create_contract_template(&work_dir);
         }
-
-        // Poll every 2 seconds
-        thread::sleep(time::Duration::from_secs(2));
+        Ok(_) => eprintln!("error installing base contract."),
+        Err(e) => report_process_error("FORGE", &e),
     }
-
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the base contract installation process.");
 }
 
 /// @notice Function to create a new foundry project
 /// @param project_name The name of the project the name of choice for the project to be created
-fn bootstrap_foundry(project_name: &str) {
+/// @param base_contract_version The tag of the base contract to install
+fn bootstrap_foundry(project_name: &str, base_contract_version: &str) {
     // Create the Foundry project directory
     let current_dir = env::current_dir().expect("Failed to get current directory");
     let work_dir = current_dir.join(project_name);
 
-    let mut child = Command::new("forge")
-        .arg("init")
-        .arg("contracts")
-        .arg("--no-commit")
-        .current_dir(work_dir.clone())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'forge init' command");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    let start = time::Instant::now();
-
-    // Handle initial output and extract keyphrase
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            if let Ok(line) = line {
-                println!("{} {}", "FORGE:: ".green(), line.green());
-            }
-        }
-        return;
-    });
-
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            if let Ok(line) = line {
-                eprintln!("{} {}", "FORGE::".yellow(), line.yellow());
-            } else if let Err(e) = line {
-                eprintln!("{} {}", "FORGE::ERROR::".red(), e);
-            }
+    let forge_status = run_streamed(
+        Command::new("forge")
+            .arg("init")
+            .arg("contracts")
+            .arg("--no-commit")
+            .current_dir(work_dir.clone()),
+        "FORGE",
+        LONG_TIMEOUT,
+    );
+
+    match forge_status {
+        Ok(output) if output.status.success() => {
+            println!("✅ {}", "Successfully initialized foundry project.".green());
+            install_base_contract(&work_dir, base_contract_version);
         }
-    });
-
-    // Wait for email verification or timeout
-    while start.elapsed().as_secs() < 50 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                println!("✅ {}", "Successfully initialized foundry project.".green());
-                install_base_contract(&work_dir);
-                break;
-            } else {
-                eprintln!("error initializing a new forge project.");
-                break;
-            }
+        Ok(_) => eprintln!("error initializing a new forge project."),
+        Err(e) => report_process_error("FORGE", &e),
+    }
+}
+
+/// @notice Function to create a new cartesi project template specially for co-processor integrations.
+/// @param dapp_name The name of the project the name of choice for the project to be created
+/// @param template The programming language of choice, you'll be building in
+/// @param base_contract_version The tag of the base contract to install
+fn create_template(dapp_name: String, template: String, base_contract_version: String) {
+    add_npm_home_dir_to_path().unwrap();
+
+    let cartesi_status = run_streamed(
+        Command::new("cartesi")
+            .arg("create")
+            .arg(dapp_name.clone())
+            .arg(format!("--template={}", template))
+            .arg("--branch")
+            .arg("wip/coprocessor"),
+        "CARTESI",
+        LONG_TIMEOUT,
+    );
+
+    match cartesi_status {
+        Ok(output) if output.status.success() => {
+            println!(
+                "✅ {}",
+                "CARTESI:: Successfully created dapp template.".green()
+            );
+            bootstrap_foundry(&dapp_name, &base_contract_version);
         }
+        Ok(_) => eprintln!("Template creation process failed."),
+        Err(e) => report_process_error("CARTESI", &e),
+    }
+}
 
-        // Poll every 2 seconds
-        thread::sleep(time::Duration::from_secs(2));
+/// @notice Function to write a directory embedded in the cli to the filesystem
+/// @param dir The embedded directory
+/// @param target The path to write the contents of the directory to
+fn write_embedded_dir(dir: &Dir, target: &Path) -> io::Result<()> {
+    fs::create_dir_all(target)?;
+    for file in dir.files() {
+        if let Some(name) = file.path().file_name() {
+            fs::write(target.join(name), file.contents())?;
+        }
     }
+    for sub_dir in dir.dirs() {
+        if let Some(name) = sub_dir.path().file_name() {
+            write_embedded_dir(sub_dir, &target.join(name))?;
+        }
+    }
+    Ok(())
+}
 
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the foundry project initialization process.");
+/// @notice Function to create the foundry project of a dapp from the files embedded in the cli
+/// @param work_dir The path to the foundry project for the dapp
+fn create_offline_contracts(work_dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(work_dir.join("src"))?;
+    fs::write(
+        work_dir.join("foundry.toml"),
+        include_str!("../../contract-template/foundry.toml"),
+    )?;
+    fs::write(
+        work_dir.join(".gitignore"),
+        include_str!("../../contract-template/.gitignore"),
+    )?;
+
+    for library in VENDORED_LIBRARIES.dirs() {
+        if let Some(name) = library.path().file_name() {
+            write_embedded_dir(library, &work_dir.join("lib").join(name))?;
+        }
+    }
+    Ok(())
 }
 
-/// @notice Function to create a new cartesi project template specially for co-processor integrations.
+/// @notice Function to create a new co-processor project from the templates embedded in the cli, without network access
 /// @param dapp_name The name of the project the name of choice for the project to be created
 /// @param template The programming language of choice, you'll be building in
-fn create_template(dapp_name: String, template: String) {
-    add_npm_home_dir_to_path().unwrap();
+fn create_offline(dapp_name: String, template: String) {
+    let Some(program_template) = PROGRAM_TEMPLATES.get_dir(&template) else {
+        let available: Vec<String> = PROGRAM_TEMPLATES
+            .dirs()
+            .filter_map(|dir| dir.path().file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        eprintln!(
+            "❌ {} '{}'.",
+            "No embedded template for".red(),
+            template.red()
+        );
+        println!(
+            "Templates available offline: {}",
+            available.join(", ").green()
+        );
+        return;
+    };
 
+    // Libraries whose submodule was not checked out when the cli was built are embedded empty
+    let missing: Vec<String> = VENDORED_LIBRARIES
+        .dirs()
+        .filter(|library| library.entries().is_empty())
+        .filter_map(|library| library.path().file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect();
+    if !missing.is_empty() {
+        eprintln!(
+            "❌ {} {}",
+            "This build of the cli does not include:".red(),
+            missing.join(", ").red()
+        );
+        println!(
+            "{}",
+            "Please rebuild the cli after running `git submodule update --init --recursive`, or create the project without --offline."
+                .yellow()
+        );
+        return;
+    }
 
-    let mut child = Command::new("cartesi")
-        .arg("create")
-        .arg(dapp_name.clone())
-        .arg(format!("--template={}", template))
-        .arg("--branch")
-        .arg("wip/coprocessor")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'cartesi create command'");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    let start = time::Instant::now();
-
-    // Handle output in separate threads
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            if let Ok(line) = line {
-                println!("{} {}", "CARTESI:: ".green(), line.green());
+    let dapp_dir = env::current_dir()
+        .expect("Failed to get current directory")
+        .join(&dapp_name);
+    if dapp_dir.exists() {
+        eprintln!(
+            "❌ {} {}",
+            "A directory already exists at".red(),
+            dapp_dir.display().to_string().red()
+        );
+        return;
+    }
+
+    if let Err(e) = write_embedded_dir(program_template, &dapp_dir) {
+        eprintln!("Error creating dapp template: {}", e);
+        return;
+    }
+    println!("✅ {}", "Successfully created dapp template.".green());
+
+    let work_dir = dapp_dir.join("contracts");
+    if let Err(e) = create_offline_contracts(&work_dir) {
+        eprintln!("Error creating foundry project: {}", e);
+        return;
+    }
+    println!(
+        "✅ {} {}",
+        "Successfully initialized foundry project with base contract".green(),
+        BASE_CONTRACT_VERSION.green()
+    );
+    create_contract_template(&work_dir);
+}
+
+/// @notice Function to get the variables a template repository can reference as `{{name}}` in file contents and file names
+/// @param dapp_name The name of the project to be created
+/// @param network The network the project targets, used for the network specific variables
+/// @return the names of the variables and their values
+fn template_variables(dapp_name: &str, network: &str) -> Vec<(&'static str, String)> {
+    let profile = get_network_profile(network);
+
+    vec![
+        ("dapp_name", dapp_name.to_string()),
+        ("network", network.to_lowercase()),
+        (
+            "chain_id",
+            profile
+                .as_ref()
+                .map(|profile| profile.chain_id.to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "task_issuer",
+            profile
+                .as_ref()
+                .and_then(|profile| profile.task_issuer.clone())
+                .unwrap_or_default(),
+        ),
+        (
+            "payment_token",
+            profile
+                .as_ref()
+                .and_then(|profile| profile.payment_token.clone())
+                .unwrap_or_default(),
+        ),
+        ("base_contract_version", BASE_CONTRACT_VERSION.to_string()),
+    ]
+}
+
+/// @notice Function to replace the `{{name}}` placeholders of the template variables in a string
+/// @param value The string to substitute the variables in
+/// @param variables The names of the variables and their values
+/// @return the string with the placeholders replaced, other `{{...}}` expressions are kept as is
+fn substitute_variables(value: &str, variables: &[(&str, String)]) -> String {
+    let mut rendered = String::with_capacity(value.len());
+    let mut rest = value;
+
+    // Values are inserted in a single pass, so placeholders inside a value are never substituted
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let replacement = placeholder[2..].find("}}").and_then(|end| {
+            let name = &placeholder[2..2 + end];
+            variables
+                .iter()
+                .find(|(variable, _)| *variable == name)
+                .map(|(_, replacement)| (replacement, end + 4))
+        });
+
+        match replacement {
+            Some((replacement, length)) => {
+                rendered.push_str(replacement);
+                rest = &placeholder[length..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &placeholder[1..];
             }
         }
-    });
+    }
+    rendered.push_str(rest);
+    rendered
+}
 
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            if let Ok(line) = line {
-                eprintln!("{} {}", "CARTESI::NOTE::".yellow(), line.yellow());
-            } else if let Err(e) = line {
-                eprintln!("{} {}", "CARTESI::ERROR::".red(), e);
+/// @notice Function to substitute the template variables in the files and file names of a directory, recursively
+/// @notice Files that are not valid UTF-8 are copied as they are
+/// @param dir The directory to render
+/// @param variables The names of the variables and their values
+fn render_template_dir(dir: &Path, variables: &[(&str, String)]) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            render_template_dir(&path, variables)?;
+        } else if let Ok(contents) = fs::read_to_string(&path) {
+            let rendered = substitute_variables(&contents, variables);
+            if rendered != contents {
+                fs::write(&path, rendered)?;
             }
         }
-    });
-
-    while start.elapsed().as_secs() < 300 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                println!(
-                    "✅ {}",
-                    "CARTESI:: Successfully created dapp template.".green()
-                );
-                bootstrap_foundry(&dapp_name);
-                return;
-            } else {
-                eprintln!("Template creation process failed.");
-                return;
-            }
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let rendered_name = substitute_variables(&name, variables);
+        if rendered_name != name {
+            fs::rename(&path, dir.join(rendered_name))?;
         }
+    }
+    Ok(())
+}
+
+/// @notice Function to copy a local template directory, skipping git metadata
+/// @param from The template directory
+/// @param to The path to copy the template to
+fn copy_template_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+
+        let path = entry.path();
+        if path.is_dir() {
+            copy_template_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+/// @notice Function to run a git command for a template repository
+/// @param args The arguments to pass to git
+/// @param path The directory to run git in
+/// @return A result containing nothing, or the error git reported
+fn run_template_git(args: &[&str], path: &Path) -> Result<(), String> {
+    let git_status = run_captured(
+        Command::new("git").args(args).current_dir(path),
+        LONG_TIMEOUT,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if git_status.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&git_status.stderr)
+            .trim()
+            .to_string())
+    }
+}
+
+/// @notice Function to fetch a template repository into the project directory, without its git history
+/// @param template_repo The git url or local path of the template repository
+/// @param git_ref The tag, branch or commit of the template to use, if None the default branch or the local working tree is used
+/// @param dapp_dir The path of the project to be created
+/// @return A result containing nothing, or a description of the failure
+fn fetch_template_repo(
+    template_repo: &str,
+    git_ref: Option<&str>,
+    dapp_dir: &Path,
+) -> Result<(), String> {
+    // A local template without a ref is copied as is, so uncommitted changes can be tried out
+    if git_ref.is_none() && Path::new(template_repo).is_dir() {
+        return copy_template_dir(Path::new(template_repo), dapp_dir).map_err(|e| e.to_string());
+    }
+
+    let current_dir = env::current_dir().expect("Failed to get current directory");
+    run_template_git(
+        &["clone", template_repo, &dapp_dir.to_string_lossy()],
+        &current_dir,
+    )?;
+    if let Some(git_ref) = git_ref {
+        run_template_git(&["checkout", "--quiet", git_ref], dapp_dir)?;
+    }
+    fs::remove_dir_all(dapp_dir.join(".git")).map_err(|e| e.to_string())
+}
 
-        // Poll every 5 seconds
-        thread::sleep(time::Duration::from_secs(5));
+/// @notice Entry point function to create a new project from a custom template repository
+/// @param dapp_name The name of the project the name of choice for the project to be created
+/// @param template_repo The git url or local path of the template repository
+/// @param git_ref The tag, branch or commit of the template to use
+/// @param network The network whose details are substituted in the template, e.g. devnet, testnet or mainnet
+pub fn create_from_template_repo(
+    dapp_name: String,
+    template_repo: String,
+    git_ref: Option<String>,
+    network: String,
+) {
+    if get_network_profile(&network).is_none() {
+        eprintln!("❌ {} '{}'.", "Unknown network".red(), network.red());
+        return;
+    }
+
+    let dapp_dir = env::current_dir()
+        .expect("Failed to get current directory")
+        .join(&dapp_name);
+    if dapp_dir.exists() {
+        eprintln!(
+            "❌ {} {}",
+            "A directory already exists at".red(),
+            dapp_dir.display().to_string().red()
+        );
+        return;
     }
 
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the login process.");
-    eprintln!("Template creation process timed out. Please verify the email within the specified timeout.");
+    let spinner = get_spinner();
+    spinner.set_message(format!("Fetching template from {}...", template_repo));
+    let fetched = fetch_template_repo(&template_repo, git_ref.as_deref(), &dapp_dir);
+    spinner.finish_and_clear();
+
+    if let Err(e) = fetched {
+        eprintln!("❌ {}", "Failed to fetch the template repository.".red());
+        println!("{} {}", "GIT::RESPONSE::".red(), e.red());
+        let _ = fs::remove_dir_all(&dapp_dir);
+        return;
+    }
+    println!(
+        "✅ {} {}",
+        "Successfully fetched template from".green(),
+        template_repo.green()
+    );
+
+    let variables = template_variables(&dapp_name, &network);
+    match render_template_dir(&dapp_dir, &variables) {
+        Ok(_) => println!(
+            "✅ {} {}",
+            "Successfully created".green(),
+            dapp_name.green()
+        ),
+        Err(e) => eprintln!("Error substituting template variables: {}", e),
+    }
+}
+
+/// @notice Function to parse the template argument of the create command, so unknown templates are reported before any tool is checked
+/// @param template The template passed to the create command
+/// @return A result containing the template name, or an error with the closest and the available templates
+pub fn parse_template(template: &str) -> Result<String, String> {
+    validate_template(template).map_err(|suggestions| {
+        let mut message = format!("unknown template '{}'.", template);
+        if !suggestions.is_empty() {
+            message.push_str(&format!(" Did you mean {}?", suggestions.join(" or ")));
+        }
+        let templates: Vec<&str> = TEMPLATES.iter().map(|(name, _)| *name).collect();
+        message.push_str(&format!(
+            " Available templates: {}, see --list-templates",
+            templates.join(", ")
+        ));
+        message
+    })
 }
 
 /// @notice Entry point function to chain all the different functions required to create a new dapp template
 /// @param dapp_name The name of the project the name of choice for the project to be created
 /// @param template The programming language of choice, you'll be building in
-pub fn create(dapp_name: String, template: String) {
-    create_template(dapp_name, template);
+/// @param offline Create the project from the templates embedded in the cli, without network access
+/// @param schema The path to a schema of the program's payloads, to generate the adapter contract and encoding helpers from
+/// @param network The network whose task issuer the deploy script of the project uses
+/// @param base_contract_version The tag of the base contract to install, defaults to the version the cli was tested with
+pub fn create(
+    dapp_name: String,
+    template: String,
+    offline: bool,
+    schema: Option<String>,
+    network: String,
+    base_contract_version: Option<String>,
+) {
+    if schema
+        .as_ref()
+        .is_some_and(|schema| !check_schema(Path::new(schema)))
+    {
+        return;
+    }
+    if get_network_profile(&network).is_none() {
+        eprintln!("❌ {} '{}'.", "Unknown network".red(), network.red());
+        return;
+    }
+
+    let base_contract_version =
+        base_contract_version.unwrap_or_else(|| BASE_CONTRACT_VERSION.to_string());
+    if offline && base_contract_version != BASE_CONTRACT_VERSION {
+        eprintln!(
+            "❌ {} {}, {}",
+            "This build of the cli only embeds coprocessor-base-contract".red(),
+            BASE_CONTRACT_VERSION.red(),
+            "create the project without --offline to install another version.".yellow()
+        );
+        return;
+    }
+
+    if offline {
+        create_offline(dapp_name.clone(), template.clone());
+    } else {
+        create_template(dapp_name.clone(), template.clone(), base_contract_version);
+    }
+
+    let dapp_dir = env::current_dir()
+        .expect("Failed to get current directory")
+        .join(&dapp_name);
+    if !dapp_dir.join("contracts/src").is_dir() {
+        return;
+    }
+
+    // Generate the adapter once the foundry project exists, it replaces MyContract.sol unless the schema names another contract
+    let mut contract_name = String::from(DEFAULT_CONTRACT_NAME);
+    if let Some(schema) = schema {
+        if generate_from_schema(&dapp_dir, Path::new(&schema), Some(&template), true) {
+            contract_name = schema_contract_name(Path::new(&schema)).unwrap_or(contract_name);
+        }
+    }
+    generate_deploy_script(&dapp_dir, &network, &contract_name, false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> Vec<(&'static str, String)> {
+        vec![
+            ("project_name", String::from("my-dapp")),
+            ("network", String::from("devnet")),
+        ]
+    }
+
+    #[test]
+    fn embedded_templates_are_listed() {
+        for template in PROGRAM_TEMPLATES.dirs() {
+            let name = template.path().to_string_lossy();
+            assert!(
+                TEMPLATES.iter().any(|(listed, _)| *listed == name),
+                "{}",
+                name
+            );
+        }
+        assert!(is_available_offline("python"));
+        assert!(!is_available_offline("rust"));
+    }
+
+    #[test]
+    fn substitutes_every_placeholder() {
+        assert_eq!(
+            substitute_variables(
+                "{{project_name}} on {{network}}, {{project_name}}",
+                &variables()
+            ),
+            "my-dapp on devnet, my-dapp"
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_and_unclosed_expressions() {
+        assert_eq!(
+            substitute_variables("${{ secrets.TOKEN }} {{other}} {{network", &variables()),
+            "${{ secrets.TOKEN }} {{other}} {{network"
+        );
+        assert_eq!(
+            substitute_variables("{{{network}}}", &variables()),
+            "{devnet}"
+        );
+    }
+
+    #[test]
+    fn does_not_substitute_inside_values() {
+        let variables = vec![
+            ("project_name", String::from("{{network}}")),
+            ("network", String::from("devnet")),
+        ];
+        assert_eq!(
+            substitute_variables("{{project_name}}/{{network}}", &variables),
+            "{{network}}/devnet"
+        );
+    }
+
+    #[test]
+    fn renders_file_contents_and_names() {
+        let dir = env::temp_dir().join(format!("render-template-{}", std::process::id()));
+        fs::create_dir_all(dir.join("{{project_name}}")).unwrap();
+        fs::write(
+            dir.join("{{project_name}}/{{network}}.txt"),
+            "name = \"{{project_name}}\"",
+        )
+        .unwrap();
+
+        render_template_dir(&dir, &variables()).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("my-dapp/devnet.txt")).unwrap(),
+            "name = \"my-dapp\""
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn counts_single_character_edits() {
+        assert_eq!(edit_distance("rust", "rust"), 0);
+        assert_eq!(edit_distance("pyton", "python"), 1);
+        assert_eq!(edit_distance("rsut", "rust"), 2);
+        assert_eq!(edit_distance("", "go"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn accepts_templates_and_aliases() {
+        assert_eq!(validate_template("rust").unwrap(), "rust");
+        assert_eq!(validate_template(" Python ").unwrap(), "python");
+        assert_eq!(validate_template("js").unwrap(), "javascript");
+        assert_eq!(validate_template("c++").unwrap(), "cpp");
+    }
+
+    #[test]
+    fn suggests_the_closest_templates() {
+        assert_eq!(validate_template("pyton").unwrap_err(), vec!["python"]);
+        assert_eq!(
+            validate_template("cp").unwrap_err(),
+            vec!["cpp", "cpp-low-level"]
+        );
+        assert!(validate_template("haskell").unwrap_err().is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_templates_with_the_available_ones() {
+        let error = parse_template("pyton").unwrap_err();
+        assert!(error.contains("Did you mean python?"));
+        assert!(error.contains("typescript"));
+        assert_eq!(parse_template("ts").unwrap(), "typescript");
+    }
 }
@@ -1,31 +1,40 @@
+use crate::commands::devnet::active_devnet_config;
+use crate::helpers::artifacts::{compile_contracts, creation_bytecode, encode_constructor_args};
+use crate::helpers::process::{report_process_error, run_captured, LONG_TIMEOUT};
+use crate::helpers::rpc::{
+    address_from_private_key, format_ether, format_gwei, parse_quantity, rpc_call,
+};
 use chrono::Local;
 use colored::Colorize;
 use serde_json::json;
 use std::env;
 use std::fs;
-use std::fs::File;
-use std::io::Write;
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// @notice Function to deploy a smart contract with constructor arguments using Forge
 /// @param private_key The private of thye account to deploy with
 /// @param rpc The rpc of the chain to deploy to
 /// @param constructor_args Constructor arguments for the smart contract
 /// @param contract_name The name of the smart contract
+/// @param network The network deployed to, saved alongside the deployment record
+/// @return an Option containing the path to the saved deployment record
 pub fn deploy_contract(
     private_key: String,
     rpc: String,
     constructor_args: Option<Vec<String>>,
     contract_name: String,
-) {
+    network: String,
+) -> Option<PathBuf> {
     match constructor_args {
         Some(args) => {
             let forge_status = {
                 let mut command = Command::new("forge");
                 command
                     .arg("create")
-                    .arg(contract_name)
+                    .arg(contract_name.clone())
                     .arg("--rpc-url")
                     .arg(rpc.clone())
                     .arg("--private-key")
@@ -34,34 +43,38 @@ pub fn deploy_contract(
                     .arg("--constructor-args");
 
                 // Add the constructor arguments dynamically
-                for arg in args {
+                for arg in args.clone() {
                     command.arg(arg);
                 }
 
-                command
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped())
-                    .spawn()
-                    .expect("Failed to execute forge deploy command")
-                    .wait_with_output()
-                    .expect("Failed to wait for forge command to finish")
+                match run_captured(&mut command, LONG_TIMEOUT) {
+                    Ok(forge_status) => forge_status,
+                    Err(e) => {
+                        report_process_error("FORGE", &e);
+                        return None;
+                    }
+                }
             };
 
             if forge_status.status.success() {
                 let stdout = String::from_utf8_lossy(&forge_status.stdout);
                 println!("{} {}", "FORGE::RESPONSE::".green(), stdout.green());
-                register_deployment(stdout.to_string(), rpc);
+                register_deployment(stdout.to_string(), rpc, contract_name, network, args)
             } else {
                 eprintln!("❌ Failed to deploy contract with Forge.");
                 let stderr = String::from_utf8_lossy(&forge_status.stderr);
-                if stderr.contains("error sending request for url (http://127.0.0.1:8545/)") {
+                if stderr.contains(&format!(
+                    "error sending request for url ({}/)",
+                    active_devnet_config().anvil_rpc()
+                )) {
                     println!("Please ensure you have a devnet environment. Run the stop and start devnet commands.");
                 } else {
                     eprintln!("Error: {}", stderr);
                 }
+                None
             }
         }
-        None => deploy_without_args(private_key, rpc, contract_name),
+        None => deploy_without_args(private_key, rpc, contract_name, network),
     }
 }
 
@@ -69,45 +82,160 @@ pub fn deploy_contract(
 /// @param private_key The private of thye account to deploy with
 /// @param rpc The rpc of the chain to deploy to
 /// @param contract_name The name of the smart contract
-fn deploy_without_args(private_key: String, rpc: String, contract_name: String) {
-    let forge_status = Command::new("forge")
-        .arg("create")
-        .arg(contract_name)
-        .arg("--rpc-url")
-        .arg(rpc.clone())
-        .arg("--private-key")
-        .arg(private_key)
-        .arg("--broadcast")
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute forge deploy command")
-        .wait_with_output()
-        .expect("Failed to wait for forge command to finish");
+/// @param network The network deployed to, saved alongside the deployment record
+/// @return an Option containing the path to the saved deployment record
+fn deploy_without_args(
+    private_key: String,
+    rpc: String,
+    contract_name: String,
+    network: String,
+) -> Option<PathBuf> {
+    let forge_status = match run_captured(
+        Command::new("forge")
+            .arg("create")
+            .arg(contract_name.clone())
+            .arg("--rpc-url")
+            .arg(rpc.clone())
+            .arg("--private-key")
+            .arg(private_key)
+            .arg("--broadcast"),
+        LONG_TIMEOUT,
+    ) {
+        Ok(forge_status) => forge_status,
+        Err(e) => {
+            report_process_error("FORGE", &e);
+            return None;
+        }
+    };
 
     if forge_status.status.success() {
         let stdout = String::from_utf8_lossy(&forge_status.stdout);
         println!("{} {}", "FORGE::RESPONSE::".green(), stdout.green());
-        register_deployment(stdout.to_string(), rpc);
+        register_deployment(stdout.to_string(), rpc, contract_name, network, Vec::new())
     } else {
         eprintln!("❌ Failed to deploy contract with Forge.");
         let stderr = String::from_utf8_lossy(&forge_status.stderr);
-        if stderr.contains("error sending request for url (http://127.0.0.1:8545/)") {
+        if stderr.contains(&format!(
+            "error sending request for url ({}/)",
+            active_devnet_config().anvil_rpc()
+        )) {
             println!("Please ensure you have a devnet environment. Run the stop and start devnet commands.");
         } else {
             eprintln!("Error: {}", stderr);
         }
+        None
     }
 }
 
+/// @notice Function to simulate a deployment with `eth_estimateGas` and print the gas and fee estimate
+/// @param private_key The private of the account to deploy with
+/// @param rpc The rpc of the chain to deploy to
+/// @param constructor_args Constructor arguments for the smart contract
+/// @param contract_name The name of the smart contract
+/// @return true if the deployment could be estimated else false
+pub fn estimate_deployment(
+    private_key: &str,
+    rpc: &str,
+    constructor_args: &Option<Vec<String>>,
+    contract_name: &str,
+) -> bool {
+    if !compile_contracts() {
+        return false;
+    }
+
+    let Some(bytecode) = creation_bytecode(contract_name) else {
+        eprintln!(
+            "❌ {} {}",
+            "Could not find the compiled bytecode for".red(),
+            contract_name.red()
+        );
+        return false;
+    };
+
+    let mut data = bytecode;
+    if let Some(args) = constructor_args {
+        match encode_constructor_args(contract_name, args) {
+            Some(encoded_args) => data.push_str(encoded_args.trim_start_matches("0x")),
+            None => return false,
+        }
+    }
+
+    let Some(deployer) = address_from_private_key(private_key) else {
+        eprintln!(
+            "❌ {}",
+            "Invalid private key provided for deployment.".red()
+        );
+        return false;
+    };
+
+    let estimate = rpc_call(
+        rpc,
+        "eth_estimateGas",
+        json!([{ "from": deployer, "data": data }]),
+    )
+    .and_then(|gas| parse_quantity(&gas))
+    .and_then(|gas| {
+        let gas_price = parse_quantity(&rpc_call(rpc, "eth_gasPrice", json!([]))?)?;
+        let base_fee = rpc_call(rpc, "eth_getBlockByNumber", json!(["latest", false]))?
+            .get("baseFeePerGas")
+            .and_then(|base_fee| parse_quantity(base_fee).ok());
+        // Not every node implements this call, legacy chains have no priority fee
+        let priority_fee = rpc_call(rpc, "eth_maxPriorityFeePerGas", json!([]))
+            .and_then(|priority_fee| parse_quantity(&priority_fee))
+            .unwrap_or_default();
+        Ok((gas, gas_price, base_fee, priority_fee))
+    });
+
+    let (gas, gas_price, base_fee, priority_fee) = match estimate {
+        Ok(estimate) => estimate,
+        Err(e) => {
+            eprintln!("❌ {} {}", "Failed to estimate deployment:".red(), e.red());
+            return false;
+        }
+    };
+
+    let max_fee = base_fee.map_or(gas_price, |base_fee| base_fee * 2 + priority_fee);
+
+    let mut data = vec![
+        ("Contract", contract_name.to_string()),
+        ("Estimated gas", gas.to_string()),
+        ("Gas price", format!("{} gwei", format_gwei(gas_price))),
+    ];
+    if let Some(base_fee) = base_fee {
+        data.push(("Base fee", format!("{} gwei", format_gwei(base_fee))));
+        data.push((
+            "Priority fee",
+            format!("{} gwei", format_gwei(priority_fee)),
+        ));
+    }
+    data.push(("Estimated cost", format_ether(gas * gas_price)));
+    data.push(("Maximum cost", format_ether(gas * max_fee)));
+
+    // Calculate the width of the first column
+    let max_width = data.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, value) in data {
+        println!("{:<width$}  {}", name, value, width = max_width);
+    }
+
+    true
+}
+
 /// @notice Function to register each deployment handled by the cli
 /// @param deployment data This contains deployment logs and info from foundry after a deploymentß
 /// @param rpc The rpc of the chain deployed to
-fn register_deployment(deployment_data: String, rpc: String) {
-    let exe_dir = env::current_dir().expect("Failed to get current directory");
-
-    // Path to the cartesi-coprocessor folder relative to the CLI tool's directory
-    let copro_path = exe_dir.join("deployment_history");
+/// @param contract_name The name of the deployed smart contract
+/// @param network The network deployed to
+/// @param constructor_args The constructor arguments the contract was deployed with
+/// @return an Option containing the path to the saved deployment record
+fn register_deployment(
+    deployment_data: String,
+    rpc: String,
+    contract_name: String,
+    network: String,
+    constructor_args: Vec<String>,
+) -> Option<PathBuf> {
+    let copro_path = deployment_history_dir();
     let path = copro_path
         .to_str()
         .expect("Unable to decode path to deployment history")
@@ -121,9 +249,22 @@ fn register_deployment(deployment_data: String, rpc: String) {
         );
         fs::create_dir_all(&copro_path).expect("Failed to create directory for deployment history");
     }
-    match save_deployment_info(&deployment_data, &path, rpc) {
-        Ok(_) => println!("✅ {}", "Deployment info saved successfully.".green()),
-        Err(err) => eprintln!("❌ Error saving deployment info: {}", err),
+    match save_deployment_info(
+        &deployment_data,
+        &path,
+        rpc,
+        contract_name,
+        network,
+        constructor_args,
+    ) {
+        Ok(record_path) => {
+            println!("✅ {}", "Deployment info saved successfully.".green());
+            Some(record_path)
+        }
+        Err(err) => {
+            eprintln!("❌ Error saving deployment info: {}", err);
+            None
+        }
     }
 }
 
@@ -131,8 +272,18 @@ fn register_deployment(deployment_data: String, rpc: String) {
 /// @param log data This contains deployment logs and info from foundry after a deployment
 /// @param path This is the path to the folser where the logs are stored
 /// @param rpc The rpc of the chain deployed to
-/// @return An option containing the status of the process
-fn save_deployment_info(log: &str, path: &str, rpc: String) -> std::io::Result<()> {
+/// @param contract_name The name of the deployed smart contract
+/// @param network The network deployed to
+/// @param constructor_args The constructor arguments the contract was deployed with
+/// @return A result containing the path to the saved deployment record
+fn save_deployment_info(
+    log: &str,
+    path: &str,
+    rpc: String,
+    contract_name: String,
+    network: String,
+    constructor_args: Vec<String>,
+) -> std::io::Result<PathBuf> {
     // Extract relevant information using simple string parsing
     let deployer = log
         .lines()
@@ -152,9 +303,10 @@ fn save_deployment_info(log: &str, path: &str, rpc: String) -> std::io::Result<(
         .map(|line| line.replace("Transaction hash: ", "").trim().to_string())
         .unwrap_or_default();
 
-    // Get the current date and time for the file title
+    // Get the current date and time for the file title, down to the nanosecond so the contracts of a
+    // manifest deployed within the same second get a record each
     let now = Local::now();
-    let timestamp = now.format("%Y-%m-%d_%H-%M-%S").to_string();
+    let timestamp = now.format("%Y-%m-%d_%H-%M-%S_%9f").to_string();
 
     // Create the JSON object
     let deployment_info = json!({
@@ -162,14 +314,149 @@ fn save_deployment_info(log: &str, path: &str, rpc: String) -> std::io::Result<(
         "deployed_to": deployed_to,
         "rpc_url": rpc,
         "transaction_hash": transaction_hash,
+        "contract_name": contract_name,
+        "network": network.to_lowercase(),
+        "constructor_args": constructor_args,
+        "deployed_at": now.to_rfc3339(),
     });
 
-    // Define the file path and name
-    let file_path = Path::new(path).join(format!("deployment_{}.json", timestamp));
+    // Define the file path and name, never replacing an existing record
+    let mut suffix = 0;
+    let (file_path, mut file) = loop {
+        let name = match suffix {
+            0 => format!("deployment_{}.json", timestamp),
+            _ => format!("deployment_{}_{}.json", timestamp, suffix),
+        };
+        let file_path = Path::new(path).join(name);
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file_path)
+        {
+            Ok(file) => break (file_path, file),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => suffix += 1,
+            Err(e) => return Err(e),
+        }
+    };
 
     // Write the JSON to a file
-    let mut file = File::create(file_path)?;
     file.write_all(deployment_info.to_string().as_bytes())?;
 
-    Ok(())
+    Ok(file_path)
+}
+
+/// @notice Function to get the path to the folder where deployment records are stored
+/// @return the path to the deployment_history folder in the current directory
+pub fn deployment_history_dir() -> PathBuf {
+    let exe_dir = env::current_dir().expect("Failed to get current directory");
+
+    // Path to the cartesi-coprocessor folder relative to the CLI tool's directory
+    exe_dir.join("deployment_history")
+}
+
+/// @notice Function to load every deployment record saved by the cli, oldest first
+/// @return a vector of the record paths and their parsed contents
+pub fn load_deployment_records() -> Vec<(PathBuf, serde_json::Value)> {
+    let Ok(entries) = fs::read_dir(deployment_history_dir()) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    // Record names embed their timestamp, so sorting by name sorts by age
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            let record = serde_json::from_str(&content).ok()?;
+            Some((path, record))
+        })
+        .collect()
+}
+
+/// @notice Function to find the most recent deployment record matching a network and optionally an address
+/// @notice Records written by older versions of the cli carry no network, so these only match on address
+/// @param network The network the contract was deployed to
+/// @param address The address of the deployed contract, if None the latest deployment on the network is returned
+/// @return an Option containing the record path and its parsed contents
+pub fn find_deployment_record(
+    network: &str,
+    address: Option<&str>,
+) -> Option<(PathBuf, serde_json::Value)> {
+    load_deployment_records()
+        .into_iter()
+        .rev()
+        .find(|(_, record)| {
+            let record_network = record["network"].as_str();
+            let record_address = record["deployed_to"].as_str().unwrap_or_default();
+            match address {
+                Some(address) => {
+                    record_address.eq_ignore_ascii_case(address)
+                        && record_network.is_none_or(|n| n.eq_ignore_ascii_case(network))
+                }
+                None => record_network.is_some_and(|n| n.eq_ignore_ascii_case(network)),
+            }
+        })
+}
+
+/// @notice Function to set a field on a saved deployment record
+/// @param path The path to the deployment record
+/// @param key The name of the field to set
+/// @param value The value to store in the field
+/// @return A result containing the status of the process
+pub fn update_deployment_record(
+    path: &Path,
+    key: &str,
+    value: serde_json::Value,
+) -> std::io::Result<()> {
+    let content = fs::read_to_string(path)?;
+    let mut record: serde_json::Value = serde_json::from_str(&content)?;
+    record[key] = value;
+    fs::write(path, record.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_saved_together_do_not_overwrite_each_other() {
+        let dir = env::temp_dir().join(format!("deployment-records-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let save = |address: &str| {
+            save_deployment_info(
+                &format!("Deployed to: {}", address),
+                dir.to_str().unwrap(),
+                String::from("http://127.0.0.1:8545"),
+                String::from("MyContract"),
+                String::from("devnet"),
+                vec![],
+            )
+            .unwrap()
+        };
+
+        let first = save("0x01");
+        let second = save("0x02");
+        assert_ne!(first, second);
+
+        let mut names: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![first.clone(), second.clone()]);
+        let deployed_to = |path: &PathBuf| {
+            let record: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+            record["deployed_to"].as_str().unwrap().to_string()
+        };
+        assert_eq!(deployed_to(&first), "0x01");
+        assert_eq!(deployed_to(&second), "0x02");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
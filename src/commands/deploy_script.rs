@@ -0,0 +1,552 @@
+use crate::helpers::config::get_network_profile;
+use colored::Colorize;
+use serde_json::json;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// @notice Name of the lockfile the deploy script reads the machine hash from, kept next to the Cartesi program
+pub const LOCKFILE_NAME: &str = "coprocessor.lock";
+
+/// @notice Contract deployed by the deploy script when none is given
+pub const DEFAULT_CONTRACT_NAME: &str = "MyContract";
+
+/// @notice Directory the foundry project is created in when `contracts` belongs to another toolchain
+pub const FALLBACK_FOUNDRY_DIR: &str = "foundry";
+
+/// @notice Function to get the directory of a project, from its program directory or its contracts directory
+/// @notice A foundry project at the root of the program directory, e.g. after the init command, is also supported
+/// @return an Option containing the path of the program directory, None if the current directory is not part of a project
+pub fn find_project_dir() -> Option<PathBuf> {
+    let current_dir = env::current_dir().expect("Failed to get current directory");
+    if current_dir.join("contracts").is_dir() {
+        return Some(current_dir);
+    }
+    if current_dir.join("foundry.toml").exists() {
+        if let Some(parent) = current_dir.parent() {
+            if foundry_dir(parent) == current_dir {
+                return Some(parent.to_path_buf());
+            }
+        }
+        return Some(current_dir);
+    }
+    None
+}
+
+/// @notice Function to get the foundry project of a project
+/// @notice The `contracts` directory is used unless foundry.toml is at the root of the project, or `contracts` already holds a project of another toolchain, e.g. Hardhat, in which case the foundry project lives in `foundry`
+/// @param project_dir The directory of the program
+/// @return the path to the foundry project for the dapp
+pub fn foundry_dir(project_dir: &Path) -> PathBuf {
+    let contracts_dir = project_dir.join("contracts");
+    let fallback_dir = project_dir.join(FALLBACK_FOUNDRY_DIR);
+    if contracts_dir.join("foundry.toml").exists() {
+        contracts_dir
+    } else if project_dir.join("foundry.toml").exists() {
+        project_dir.to_path_buf()
+    } else if fallback_dir.join("foundry.toml").exists() {
+        fallback_dir
+    } else if fs::read_dir(&contracts_dir).is_ok_and(|mut entries| entries.next().is_some()) {
+        // forge init refuses non-empty directories, so another toolchain's sources are left alone
+        fallback_dir
+    } else {
+        contracts_dir
+    }
+}
+
+/// @notice Function to get the path of the lockfile relative to the foundry project, as forge scripts resolve it
+/// @param project_dir The directory of the program
+/// @param work_dir The path to the foundry project for the dapp
+/// @return the relative path of the lockfile
+pub fn relative_lockfile_path(project_dir: &Path, work_dir: &Path) -> String {
+    if project_dir == work_dir {
+        LOCKFILE_NAME.to_string()
+    } else {
+        format!("../{}", LOCKFILE_NAME)
+    }
+}
+
+/// @notice Function to read the lockfile of a project
+/// @param project_dir The directory of the program
+/// @return the content of the lockfile, Null if there is none
+fn read_lockfile(project_dir: &Path) -> serde_json::Value {
+    fs::read_to_string(project_dir.join(LOCKFILE_NAME))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// @notice Function to generate the deploy script of an adapter contract
+/// @param contract_name The name of the contract to deploy
+/// @param network The network the task issuer belongs to
+/// @param task_issuer The address of the co-processor task issuer on the network
+/// @param lockfile_path The path of the lockfile relative to the foundry project
+/// @return the source of the deploy script
+fn deploy_script_source(
+    contract_name: &str,
+    network: &str,
+    task_issuer: &str,
+    lockfile_path: &str,
+) -> String {
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+import "../lib/forge-std/src/Script.sol";
+import "../src/{contract_name}.sol";
+
+/// @notice Generated by `cartesi-coprocessor regen-deploy-script`, regenerate it instead of editing it
+contract Deploy is Script {{
+    /// @notice Task issuer of the co-processor on {network}, the TASK_ISSUER environment variable takes precedence
+    string constant TASK_ISSUER = "{task_issuer}";
+
+    /// @notice Lockfile holding the machine hash of the last published program, relative to the foundry project
+    string constant LOCKFILE = "{lockfile_path}";
+
+    /// @notice Simple deploy script to deploy the solidity contract contained in {contract_name}.sol
+    /// @dev The machine hash is read from the MACHINE_HASH environment variable, or from the lockfile
+    function run() external {{
+        address taskIssuer = vm.envOr("TASK_ISSUER", vm.parseAddress(TASK_ISSUER));
+        bytes32 machineHash = vm.envOr("MACHINE_HASH", bytes32(0));
+        if (machineHash == bytes32(0) && vm.exists(LOCKFILE)) {{
+            machineHash = vm.parseJsonBytes32(vm.readFile(LOCKFILE), ".machine_hash");
+        }}
+        require(
+            machineHash != bytes32(0),
+            "No machine hash, set MACHINE_HASH or run cartesi-coprocessor regen-deploy-script after publishing"
+        );
+
+        vm.startBroadcast();
+        new {contract_name}(taskIssuer, machineHash);
+        vm.stopBroadcast();
+    }}
+}}
+"#,
+        contract_name = contract_name,
+        network = network,
+        task_issuer = task_issuer,
+        lockfile_path = lockfile_path,
+    )
+}
+
+/// @notice Function to let forge scripts of the project read the lockfile, by adding it to the fs_permissions of foundry.toml
+/// @param work_dir The path to the foundry project for the dapp
+/// @param lockfile_path The path of the lockfile relative to the foundry project
+/// @param keep_existing Whether foundry.toml belongs to the user and must only be reported on, not edited
+/// @return true if scripts can read the lockfile
+pub fn allow_lockfile_read(work_dir: &Path, lockfile_path: &str, keep_existing: bool) -> bool {
+    let foundry_toml = work_dir.join("foundry.toml");
+    let Ok(content) = fs::read_to_string(&foundry_toml) else {
+        eprintln!(
+            "❌ {}",
+            "No foundry.toml found in the foundry project.".red()
+        );
+        return false;
+    };
+
+    if content.contains(&format!("\"{}\"", lockfile_path)) {
+        return true;
+    }
+    if content.contains("fs_permissions") {
+        println!(
+            "{} {}",
+            "foundry.toml already sets fs_permissions, add read access to".yellow(),
+            lockfile_path.yellow()
+        );
+        return true;
+    }
+
+    let permission = format!(
+        "fs_permissions = [{{ access = \"read\", path = \"{}\" }}]",
+        lockfile_path
+    );
+    if keep_existing {
+        println!(
+            "{} {}",
+            "Kept the existing foundry.toml, add to [profile.default] to let the deploy script read the lockfile:"
+                .yellow(),
+            permission.yellow()
+        );
+        return true;
+    }
+    let updated = match content.find("[profile.default]") {
+        Some(index) => {
+            let end = index + "[profile.default]".len();
+            format!("{}\n{}{}", &content[..end], permission, &content[end..])
+        }
+        None => format!("{}\n[profile.default]\n{}\n", content, permission),
+    };
+    match fs::write(&foundry_toml, updated) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("Error updating foundry.toml: {}", e);
+            false
+        }
+    }
+}
+
+/// @notice Function to record the deployment settings and the machine hash of the current build in the lockfile of a project
+/// @param project_dir The directory of the program
+/// @param network The network the deploy script targets
+/// @param contract_name The contract the deploy script deploys
+/// @param task_issuer The task issuer of the network
+/// @param keep_existing Whether an existing lockfile must be kept
+/// @return true if the lockfile was written or kept
+fn write_lockfile(
+    project_dir: &Path,
+    network: &str,
+    contract_name: &str,
+    task_issuer: &str,
+    keep_existing: bool,
+) -> bool {
+    if keep_existing && project_dir.join(LOCKFILE_NAME).exists() {
+        println!(
+            "{}",
+            format!(
+                "Kept the existing {}, run the regen-deploy-script command to replace it.",
+                LOCKFILE_NAME
+            )
+            .yellow()
+        );
+        return true;
+    }
+
+    // The machine hash is only known once the program has been built, the script rejects the zero hash
+    let zero_hash = format!("0x{}", "0".repeat(64));
+    let machine_hash = fs::read(project_dir.join(".cartesi/image/hash"))
+        .ok()
+        .map(|hash| {
+            hash.iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        })
+        .map(|hash| format!("0x{}", hash));
+    // Without a build, e.g. after a fresh clone, the hash recorded by the last publish is kept
+    let recorded_hash = read_lockfile(project_dir)["machine_hash"]
+        .as_str()
+        .filter(|hash| *hash != zero_hash)
+        .map(String::from);
+    let lock = json!({
+        "network": network,
+        "contract": contract_name,
+        "task_issuer": task_issuer,
+        "machine_hash": machine_hash.clone().or_else(|| recorded_hash.clone()).unwrap_or(zero_hash),
+    });
+
+    if let Err(e) = fs::write(
+        project_dir.join(LOCKFILE_NAME),
+        serde_json::to_string_pretty(&lock).unwrap_or_default() + "\n",
+    ) {
+        eprintln!("Error writing {}: {}", LOCKFILE_NAME, e);
+        return false;
+    }
+
+    match (machine_hash, recorded_hash) {
+        (Some(machine_hash), _) => println!(
+            "✅ {} {} {}",
+            "Recorded machine hash".green(),
+            machine_hash.green(),
+            format!("in {}", LOCKFILE_NAME).green()
+        ),
+        (None, Some(recorded_hash)) => println!(
+            "{} {} {}",
+            "No build found, kept the machine hash".yellow(),
+            recorded_hash.yellow(),
+            format!("recorded in {}", LOCKFILE_NAME).yellow()
+        ),
+        (None, None) => println!(
+            "{}",
+            "No machine hash found yet, run the regen-deploy-script command after building or publishing the program."
+                .yellow()
+        ),
+    }
+    true
+}
+
+/// @notice Function to write the deploy script of a project for a network, and its lockfile
+/// @param project_dir The directory of the program, containing the `contracts` foundry project
+/// @param network The network whose task issuer the script deploys with
+/// @param contract_name The name of the contract to deploy
+/// @param keep_existing Whether foundry.toml and an existing lockfile belong to the user and must be left untouched
+/// @return true if the script and the lockfile were written
+pub fn generate_deploy_script(
+    project_dir: &Path,
+    network: &str,
+    contract_name: &str,
+    keep_existing: bool,
+) -> bool {
+    let Some(profile) = get_network_profile(network) else {
+        eprintln!("❌ {} '{}'.", "Unknown network".red(), network.red());
+        return false;
+    };
+    let Some(task_issuer) = profile.task_issuer else {
+        eprintln!(
+            "❌ {} {}, {}",
+            "No task issuer known for".red(),
+            profile.name.red(),
+            format!(
+                "set task_issuer in [networks.{}] of coprocessor.toml.",
+                profile.name
+            )
+            .yellow()
+        );
+        return false;
+    };
+
+    let work_dir = foundry_dir(project_dir);
+    let lockfile_path = relative_lockfile_path(project_dir, &work_dir);
+    if !allow_lockfile_read(&work_dir, &lockfile_path, keep_existing) {
+        return false;
+    }
+
+    let script_path = work_dir.join("script/Deploy.s.sol");
+    let _ = fs::create_dir_all(work_dir.join("script"));
+    match fs::write(
+        &script_path,
+        deploy_script_source(contract_name, &profile.name, &task_issuer, &lockfile_path),
+    ) {
+        Ok(_) => {
+            println!(
+                "✅ {} {} {}",
+                "Successfully generated deploy script for".green(),
+                profile.name.green(),
+                format!("({})", task_issuer).green()
+            );
+            write_lockfile(
+                project_dir,
+                &profile.name,
+                contract_name,
+                &task_issuer,
+                keep_existing,
+            )
+        }
+        Err(e) => {
+            eprintln!("Error writing deploy script: {}", e);
+            false
+        }
+    }
+}
+
+/// @notice Entry point function to regenerate the deploy script and record the machine hash of the current build in the lockfile
+/// @param network The network to deploy to, defaults to the network in the lockfile or devnet
+/// @param contract_name The contract to deploy, defaults to the contract in the lockfile or MyContract
+pub fn regen_deploy_script(network: Option<String>, contract_name: Option<String>) {
+    let Some(project_dir) = find_project_dir() else {
+        eprintln!(
+            "❌ {}",
+            "No contracts directory found, please run this command in the directory of your Cartesi program."
+                .red()
+        );
+        return;
+    };
+
+    let lock = read_lockfile(&project_dir);
+    let network = network
+        .or_else(|| lock["network"].as_str().map(String::from))
+        .unwrap_or_else(|| String::from("devnet"))
+        .to_lowercase();
+    let contract_name = contract_name
+        .or_else(|| lock["contract"].as_str().map(String::from))
+        .unwrap_or_else(|| String::from(DEFAULT_CONTRACT_NAME));
+
+    let source = foundry_dir(&project_dir)
+        .join("src")
+        .join(format!("{}.sol", contract_name));
+    if !source.exists() {
+        println!(
+            "{} {}",
+            "Warning: no source found for".yellow(),
+            source.display().to_string().yellow()
+        );
+    }
+    generate_deploy_script(&project_dir, &network, &contract_name, false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn foundry_project_is_created_beside_other_toolchains() {
+        let project_dir = env::temp_dir().join(format!("foundry-dir-{}", std::process::id()));
+        fs::create_dir_all(&project_dir).unwrap();
+        assert_eq!(foundry_dir(&project_dir), project_dir.join("contracts"));
+
+        // A Hardhat project keeps its sources in contracts without a foundry.toml
+        fs::create_dir_all(project_dir.join("contracts")).unwrap();
+        fs::write(project_dir.join("contracts/Lock.sol"), "contract Lock {}\n").unwrap();
+        assert_eq!(
+            foundry_dir(&project_dir),
+            project_dir.join(FALLBACK_FOUNDRY_DIR)
+        );
+        fs::create_dir_all(project_dir.join(FALLBACK_FOUNDRY_DIR)).unwrap();
+        fs::write(project_dir.join("foundry/foundry.toml"), "").unwrap();
+        assert_eq!(
+            foundry_dir(&project_dir),
+            project_dir.join(FALLBACK_FOUNDRY_DIR)
+        );
+
+        fs::write(project_dir.join("foundry.toml"), "").unwrap();
+        assert_eq!(foundry_dir(&project_dir), project_dir);
+
+        fs::write(project_dir.join("contracts/foundry.toml"), "").unwrap();
+        assert_eq!(foundry_dir(&project_dir), project_dir.join("contracts"));
+
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+
+    #[test]
+    fn existing_foundry_toml_and_lockfile_are_kept() {
+        let project_dir = env::temp_dir().join(format!("keep-existing-{}", std::process::id()));
+        fs::create_dir_all(&project_dir).unwrap();
+        let foundry_toml = "[profile.default]\nsrc = \"src\"\n";
+        fs::write(project_dir.join("foundry.toml"), foundry_toml).unwrap();
+        fs::write(project_dir.join(LOCKFILE_NAME), "{}\n").unwrap();
+
+        assert!(allow_lockfile_read(&project_dir, LOCKFILE_NAME, true));
+        assert!(write_lockfile(
+            &project_dir,
+            "devnet",
+            DEFAULT_CONTRACT_NAME,
+            "0x0000000000000000000000000000000000000001",
+            true
+        ));
+        assert_eq!(
+            fs::read_to_string(project_dir.join("foundry.toml")).unwrap(),
+            foundry_toml
+        );
+        assert_eq!(
+            fs::read_to_string(project_dir.join(LOCKFILE_NAME)).unwrap(),
+            "{}\n"
+        );
+
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+
+    #[test]
+    fn deploy_script_reads_the_lockfile_of_its_project() {
+        let source = deploy_script_source(
+            "Auction",
+            "holesky",
+            "0x0000000000000000000000000000000000000001",
+            "../coprocessor.lock",
+        );
+        assert!(source.contains("import \"../src/Auction.sol\";"));
+        assert!(source.contains("new Auction(taskIssuer, machineHash);"));
+        assert!(source.contains("co-processor on holesky"));
+        assert!(source.contains(
+            "string constant TASK_ISSUER = \"0x0000000000000000000000000000000000000001\";"
+        ));
+        assert!(source.contains("string constant LOCKFILE = \"../coprocessor.lock\";"));
+    }
+
+    #[test]
+    fn lockfile_path_is_relative_to_the_foundry_project() {
+        let project_dir = Path::new("/tmp/dapp");
+        assert_eq!(
+            relative_lockfile_path(project_dir, project_dir),
+            LOCKFILE_NAME
+        );
+        assert_eq!(
+            relative_lockfile_path(project_dir, &project_dir.join("contracts")),
+            "../coprocessor.lock"
+        );
+    }
+
+    #[test]
+    fn lockfile_read_is_added_to_foundry_toml() {
+        let work_dir = env::temp_dir().join(format!("lockfile-read-{}", std::process::id()));
+        fs::create_dir_all(&work_dir).unwrap();
+        let foundry_toml = work_dir.join("foundry.toml");
+        let permission = "fs_permissions = [{ access = \"read\", path = \"../coprocessor.lock\" }]";
+
+        fs::write(
+            &foundry_toml,
+            "[profile.default]\nsrc = \"src\"\n\n[rpc_endpoints]\n",
+        )
+        .unwrap();
+        assert!(allow_lockfile_read(&work_dir, "../coprocessor.lock", false));
+        assert_eq!(
+            fs::read_to_string(&foundry_toml).unwrap(),
+            format!(
+                "[profile.default]\n{}\nsrc = \"src\"\n\n[rpc_endpoints]\n",
+                permission
+            )
+        );
+        // Running it again leaves the permission as is
+        assert!(allow_lockfile_read(&work_dir, "../coprocessor.lock", false));
+        assert_eq!(
+            fs::read_to_string(&foundry_toml)
+                .unwrap()
+                .matches(permission)
+                .count(),
+            1
+        );
+
+        fs::write(&foundry_toml, "[fmt]\nline_length = 100\n").unwrap();
+        assert!(allow_lockfile_read(&work_dir, "../coprocessor.lock", false));
+        assert_eq!(
+            fs::read_to_string(&foundry_toml).unwrap(),
+            format!(
+                "[fmt]\nline_length = 100\n\n[profile.default]\n{}\n",
+                permission
+            )
+        );
+
+        let existing =
+            "[profile.default]\nfs_permissions = [{ access = \"read\", path = \"./\" }]\n";
+        fs::write(&foundry_toml, existing).unwrap();
+        assert!(allow_lockfile_read(&work_dir, "../coprocessor.lock", false));
+        assert_eq!(fs::read_to_string(&foundry_toml).unwrap(), existing);
+
+        fs::remove_file(&foundry_toml).unwrap();
+        assert!(!allow_lockfile_read(
+            &work_dir,
+            "../coprocessor.lock",
+            false
+        ));
+
+        fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[test]
+    fn recorded_machine_hash_is_kept_without_a_build() {
+        let project_dir = env::temp_dir().join(format!("lockfile-hash-{}", std::process::id()));
+        fs::create_dir_all(&project_dir).unwrap();
+        let task_issuer = "0x0000000000000000000000000000000000000001";
+        let recorded = format!("0x{}", "ab".repeat(32));
+        fs::write(
+            project_dir.join(LOCKFILE_NAME),
+            json!({ "machine_hash": recorded }).to_string(),
+        )
+        .unwrap();
+
+        assert!(write_lockfile(
+            &project_dir,
+            "holesky",
+            "Auction",
+            task_issuer,
+            false
+        ));
+        let lock = read_lockfile(&project_dir);
+        assert_eq!(lock["machine_hash"], recorded);
+        assert_eq!(lock["network"], "holesky");
+        assert_eq!(lock["contract"], "Auction");
+
+        fs::create_dir_all(project_dir.join(".cartesi/image")).unwrap();
+        fs::write(project_dir.join(".cartesi/image/hash"), [0x12u8; 32]).unwrap();
+        assert!(write_lockfile(
+            &project_dir,
+            "holesky",
+            "Auction",
+            task_issuer,
+            false
+        ));
+        assert_eq!(
+            read_lockfile(&project_dir)["machine_hash"],
+            format!("0x{}", "12".repeat(32))
+        );
+
+        fs::remove_dir_all(&project_dir).unwrap();
+    }
+}
@@ -1,64 +1,68 @@
+use crate::helpers::config::{
+    get_devnet_config, get_network_profile, get_pinned_devnet_version, DevnetConfig,
+    DEFAULT_ANVIL_PORT, DEFAULT_IPFS_PORT, DEFAULT_SOLVER_PORT,
+};
+use crate::helpers::docker::{
+    check_docker_daemon, image_exists, load_images, pull_image, save_images,
+};
 use crate::helpers::helpers::get_spinner;
+use crate::helpers::process::{
+    report_process_error, run_captured, run_process, run_streamed, LONG_TIMEOUT, NO_TIMEOUT,
+    SHORT_TIMEOUT,
+};
+use crate::helpers::rpc::{get_chain_id, has_code, parse_quantity, rpc_call};
 use colored::Colorize;
+use reqwest::blocking::Client;
+use serde_json::json;
+use std::collections::BTreeSet;
 use std::env;
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use std::{thread, time};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
 
 /// @notice Function to start a local development network set of docker containers for Cartesi-Coprocessor
-pub fn start_devnet() {
+/// @param version A tag or commit of the coprocessor repository to run, if None the project pin or the release branch is used
+pub fn start_devnet(version: Option<String>) {
+    if !check_docker_daemon() {
+        return;
+    }
+    let version = version.or_else(get_pinned_devnet_version);
     let coprocessor_path = clone_coprocessor_repo();
     match coprocessor_path {
         Some(path) => {
-            if change_branch(path.clone()) == true {
-                match update_submodules(path.clone()) {
-                    true => {
-                        if build_container(path.clone()) == true
-                            && pull_container(path.clone()) == true
-                        {
-                            let spinner = get_spinner();
-                            spinner.set_message("Starting devnet containers...");
-
-                            // Run Cartesi-Coprocessor in the background
-                            let docker_status = Command::new("docker")
-                                .arg("compose")
-                                .arg("-f")
-                                .arg("docker-compose-devnet.yaml")
-                                .arg("up")
-                                .arg("--wait")
-                                .arg("-d")
-                                .current_dir(path)
-                                .stdout(Stdio::piped())
-                                .stderr(Stdio::piped())
-                                .spawn()
-                                .expect("Failed to start Cartesi-Coprocessor devnet environment")
-                                .wait_with_output()
-                                .expect("Failed to complete git status check");
-
-                            if docker_status.status.success() {
-                                spinner.finish_and_clear();
-                                println!(
-                                    "✅ {}",
-                                    "Cartesi-Coprocessor devnet environment started.".green()
-                                )
-                            } else {
-                                spinner.finish_and_clear();
-                                eprintln!(
-                                    "{} \n{}",
-                                    "❌ Failed to start devnet containers:".red(),
-                                    String::from_utf8_lossy(&docker_status.stderr).red()
-                                );
-                                return;
-                            }
-                        }
+            let path = match &version {
+                Some(version) => match checkout_version(path, version) {
+                    Some(version_path) => version_path,
+                    None => return,
+                },
+                None => {
+                    if change_branch(path.clone()) == true {
+                        path
+                    } else {
+                        return;
                     }
-                    false => return,
-                };
-            } else {
-                return;
+                }
+            };
+            let running = active_devnet().is_some_and(|record| record["running"] == true);
+            if let Some(active_path) =
+                active_devnet_path().filter(|active| running && *active != path)
+            {
+                println!(
+                    "{} {}",
+                    "A devnet started from another version may still be running, stop it with the stop-devnet command if ports clash:".yellow(),
+                    active_path.yellow()
+                );
             }
+            match update_submodules(path.clone()) {
+                true => {
+                    if build_container(path.clone()) == true && pull_container(path.clone()) == true
+                    {
+                        start_devnet_containers(path, version, &[]);
+                    }
+                }
+                false => return,
+            };
         }
         None => {
             eprintln!("❌ Failed to clone Cartesi-Coprocessor repository.");
@@ -67,11 +71,669 @@ pub fn start_devnet() {
     }
 }
 
+/// @notice Function to start the devnet containers of a checkout in the background and record it as the active devnet
+/// @param path The path to the checkout of the coprocessor repository
+/// @param version The pinned tag or commit, None when tracking the release branch
+/// @param extra_args Extra arguments passed to `docker compose up`
+/// @return true if the containers started else false
+fn start_devnet_containers(path: String, version: Option<String>, extra_args: &[&str]) -> bool {
+    let devnet_config = get_devnet_config();
+    if !write_ports_override(Path::new(&path), &devnet_config) {
+        return false;
+    }
+
+    let spinner = get_spinner();
+    spinner.set_message("Starting devnet containers...");
+
+    // Run Cartesi-Coprocessor in the background
+    let docker_status = match run_captured(
+        Command::new("docker")
+            .arg("compose")
+            .args(compose_file_args(Path::new(&path), &devnet_config))
+            .arg("up")
+            .arg("--wait")
+            .arg("-d")
+            .args(extra_args)
+            .current_dir(path.clone()),
+        LONG_TIMEOUT,
+    ) {
+        Ok(docker_status) => docker_status,
+        Err(e) => {
+            spinner.finish_and_clear();
+            report_process_error("DOCKER", &e);
+            return false;
+        }
+    };
+
+    spinner.finish_and_clear();
+    if docker_status.status.success() {
+        record_active_devnet(&path, version, &devnet_config);
+        record_devnet_baseline();
+        println!(
+            "✅ {}",
+            "Cartesi-Coprocessor devnet environment started.".green()
+        );
+        true
+    } else {
+        eprintln!(
+            "{} \n{}",
+            "❌ Failed to start devnet containers:".red(),
+            String::from_utf8_lossy(&docker_status.stderr).red()
+        );
+        false
+    }
+}
+
+/// @notice Name of the compose file generated in the checkout to publish the devnet on the configured ports
+const PORTS_OVERRIDE_FILE: &str = "docker-compose-ports.override.yaml";
+
+/// @notice Function to get the compose file arguments of the devnet: the devnet compose file, the compose override
+/// @notice configured in `[devnet] compose_override` and the generated ports override, in that order
+/// @param path The path to the checkout of the coprocessor repository
+/// @param devnet_config The devnet config holding the compose override
+/// @return the `-f <file>` arguments to pass to docker compose
+pub fn compose_file_args(path: &Path, devnet_config: &DevnetConfig) -> Vec<String> {
+    let mut args = vec![
+        String::from("-f"),
+        String::from("docker-compose-devnet.yaml"),
+    ];
+
+    if let Some(compose_override) = &devnet_config.compose_override {
+        args.push(String::from("-f"));
+        args.push(compose_override.display().to_string());
+    }
+    if path.join(PORTS_OVERRIDE_FILE).exists() {
+        args.push(String::from("-f"));
+        args.push(String::from(PORTS_OVERRIDE_FILE));
+    }
+    args
+}
+
+/// @notice Function to read the effective devnet compose configuration, with every compose file applied
+/// @param path The path to the checkout of the coprocessor repository
+/// @param devnet_config The devnet config holding the compose override
+/// @return an Option containing the configuration as json, None if docker compose could not read it
+fn compose_config(path: &Path, devnet_config: &DevnetConfig) -> Option<serde_json::Value> {
+    let config_status = match run_captured(
+        Command::new("docker")
+            .arg("compose")
+            .args(compose_file_args(path, devnet_config))
+            .arg("config")
+            .arg("--format")
+            .arg("json")
+            .current_dir(path),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(config_status) => config_status,
+        Err(e) => {
+            report_process_error("DOCKER", &e);
+            return None;
+        }
+    };
+
+    if !config_status.status.success() {
+        let stderr = String::from_utf8_lossy(&config_status.stderr);
+        println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+        return None;
+    }
+    serde_json::from_slice(&config_status.stdout).ok()
+}
+
+/// @notice Function to generate the compose file publishing the devnet anvil node, solver and IPFS api on the configured host ports
+/// @notice The ports of every service publishing one of the default ports are replaced, the override is removed when no port is configured
+/// @param path The path to the checkout of the coprocessor repository
+/// @param devnet_config The devnet config the devnet is started with
+/// @return true if the override is up to date else false
+fn write_ports_override(path: &Path, devnet_config: &DevnetConfig) -> bool {
+    let override_path = path.join(PORTS_OVERRIDE_FILE);
+    let _ = fs::remove_file(&override_path);
+
+    if let Some(compose_override) = &devnet_config.compose_override {
+        if !compose_override.exists() {
+            eprintln!(
+                "❌ {} {}",
+                "Devnet compose override not found:".red(),
+                compose_override.display().to_string().red()
+            );
+            return false;
+        }
+    }
+    if !devnet_config.has_custom_ports() {
+        return true;
+    }
+
+    let Some(config) = compose_config(path, devnet_config) else {
+        return false;
+    };
+
+    let host_ports = [
+        (DEFAULT_ANVIL_PORT, devnet_config.anvil_port),
+        (DEFAULT_SOLVER_PORT, devnet_config.solver_port),
+        (DEFAULT_IPFS_PORT, devnet_config.ipfs_port),
+    ];
+
+    // `!override` replaces the port list of the base compose file instead of appending to it
+    let mut content = String::from("services:\n");
+    let services = config["services"].as_object().cloned().unwrap_or_default();
+    for (name, service) in services {
+        let ports = service["ports"].as_array().cloned().unwrap_or_default();
+        let remapped = ports.iter().any(|port| {
+            host_ports.iter().any(|(default, configured)| {
+                port["published"].as_str() == Some(&default.to_string()) && default != configured
+            })
+        });
+        if !remapped {
+            continue;
+        }
+
+        content.push_str(&format!("  {}:\n    ports: !override\n", name));
+        for port in ports {
+            let published = port["published"].as_str().unwrap_or_default();
+            let published = host_ports
+                .iter()
+                .find(|(default, _)| published == default.to_string())
+                .map_or(published.to_string(), |(_, configured)| {
+                    configured.to_string()
+                });
+            content.push_str(&format!("      - target: {}\n", port["target"]));
+            content.push_str(&format!("        published: \"{}\"\n", published));
+            if let Some(host_ip) = port["host_ip"].as_str() {
+                content.push_str(&format!("        host_ip: {}\n", host_ip));
+            }
+            if let Some(protocol) = port["protocol"].as_str() {
+                content.push_str(&format!("        protocol: {}\n", protocol));
+            }
+        }
+    }
+
+    match fs::write(&override_path, content) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("❌ Failed to write the devnet ports override: {:?}", e);
+            false
+        }
+    }
+}
+
+/// @notice Function to get the image a compose service runs, compose names the images it builds after the project and service
+/// @param project The name of the compose project
+/// @param name The name of the service
+/// @param service The service in the effective compose configuration
+/// @return the name of the image
+fn service_image(project: &str, name: &str, service: &serde_json::Value) -> String {
+    service["image"]
+        .as_str()
+        .map(|image| image.to_string())
+        .unwrap_or_else(|| format!("{}-{}", project, name))
+}
+
+/// @notice Function to list the images the devnet compose file needs, including the ones built locally
+/// @param path The path to the checkout of the coprocessor repository
+/// @param devnet_config The devnet config holding the compose override
+/// @return an Option containing the image names, None if docker compose could not read the compose file
+fn devnet_images(path: &Path, devnet_config: &DevnetConfig) -> Option<Vec<String>> {
+    let config = compose_config(path, devnet_config)?;
+    let project = config["name"].as_str().unwrap_or_default();
+
+    let mut images: Vec<String> = config["services"]
+        .as_object()
+        .map(|services| {
+            services
+                .iter()
+                .map(|(name, service)| service_image(project, name, service))
+                .collect()
+        })
+        .unwrap_or_default();
+    images.sort();
+    images.dedup();
+    Some(images)
+}
+
+/// @notice Function to import a bundle of docker images created with the devnet export command
+/// @param bundle The path to the `docker save` tarball
+/// @return true if the images were imported else false
+fn import_image_bundle(bundle: &str) -> bool {
+    let spinner = get_spinner();
+    spinner.set_message("Importing devnet images...");
+    let loaded = load_images(Path::new(bundle));
+    spinner.finish_and_clear();
+
+    match loaded {
+        Ok(images) => {
+            println!(
+                "✅ {} {} {}",
+                "Imported".green(),
+                images.len().to_string().green(),
+                format!("devnet images from {}", bundle).green()
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to import devnet images.");
+            println!("{} {}", "DOCKER::RESPONSE::".red(), e.to_string().red());
+            false
+        }
+    }
+}
+
+/// @notice Function to start the devnet without network access, skipping every clone, pull and build step
+/// @param version A tag or commit previously checked out, if None the main clone is used
+/// @param bundle An optional image bundle to import before starting, created with the devnet export command
+pub fn start_devnet_offline(version: Option<String>, bundle: Option<String>) {
+    if !check_docker_daemon() {
+        return;
+    }
+    let version = version.or_else(get_pinned_devnet_version);
+    let path = match version.as_deref().map(version_checkout_path) {
+        Some(Ok(path)) => path,
+        Some(Err(e)) => {
+            eprintln!("❌ {}", e.red());
+            return;
+        }
+        None => coprocessor_repo_path(),
+    };
+
+    if !path.join("docker-compose-devnet.yaml").exists() {
+        eprintln!(
+            "❌ {} {:?}{}",
+            "Cartesi-Coprocessor repository not found at".red(),
+            path,
+            ", please run start-devnet once with network access.".red()
+        );
+        return;
+    }
+
+    if let Some(bundle) = bundle {
+        if !import_image_bundle(&bundle) {
+            return;
+        }
+    }
+
+    let Some(images) = devnet_images(&path, &get_devnet_config()) else {
+        return;
+    };
+    let mut missing: Vec<String> = Vec::new();
+    for image in images {
+        match image_exists(&image) {
+            Ok(true) => {}
+            Ok(false) => missing.push(image),
+            Err(e) => {
+                eprintln!("❌ {}", e.to_string().red());
+                return;
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        eprintln!("❌ {}", "Devnet images missing locally:".red());
+        for image in missing {
+            eprintln!("   {}", image.red());
+        }
+        println!(
+            "{}",
+            "Run the devnet export command on a machine with the devnet images, then start with --offline --import <bundle>."
+                .yellow()
+        );
+        return;
+    }
+    println!("✅ {}", "All devnet images are available locally.".green());
+
+    let path = path
+        .to_str()
+        .expect("Error converting path to String")
+        .to_string();
+    start_devnet_containers(path, version, &["--pull", "never", "--no-build"]);
+}
+
+/// @notice Function to export the devnet images into a `docker save` tarball, to start the devnet on machines without network access
+/// @param output The path of the tarball to create
+pub fn devnet_export(output: String) {
+    if !check_docker_daemon() {
+        return;
+    }
+    let path = devnet_path();
+    if !path.join("docker-compose-devnet.yaml").exists() {
+        println!(
+            "❌ {}",
+            "Devnet is not installed, please run the start-devnet command first.".red()
+        );
+        return;
+    }
+
+    let Some(images) = devnet_images(&path, &active_devnet_config()) else {
+        return;
+    };
+    let mut missing: Vec<&String> = Vec::new();
+    for image in images.iter() {
+        match image_exists(image) {
+            Ok(true) => {}
+            Ok(false) => missing.push(image),
+            Err(e) => {
+                eprintln!("❌ {}", e.to_string().red());
+                return;
+            }
+        }
+    }
+    if !missing.is_empty() {
+        eprintln!(
+            "❌ {}",
+            "Devnet images missing locally, please run the start-devnet command first:".red()
+        );
+        for image in missing {
+            eprintln!("   {}", image.red());
+        }
+        return;
+    }
+
+    let spinner = get_spinner();
+    spinner.set_message("Exporting devnet images...");
+
+    let saved = save_images(&images, Path::new(&output));
+    spinner.finish_and_clear();
+
+    match saved {
+        Ok(_) => println!(
+            "✅ {} {} {}",
+            "Exported".green(),
+            images.len().to_string().green(),
+            format!("devnet images to {}", output).green()
+        ),
+        Err(e) => {
+            eprintln!("❌ Failed to export devnet images.");
+            println!("{} {}", "DOCKER::RESPONSE::".red(), e.to_string().red());
+        }
+    }
+}
+
+/// @notice Function to get the path the cartesi-coprocessor repository is cloned into on the host machine
+/// @return the path to the local coprocessor repository
+pub fn coprocessor_repo_path() -> PathBuf {
+    let home_dir = env::var("HOME").expect("Failed to get HOME directory");
+    PathBuf::from(home_dir).join(".cartesi-coprocessor-repo")
+}
+
+/// @notice Function to get the folder pinned versions of the coprocessor repository are checked out into, side by side
+/// @return the path to the folder holding one checkout per version
+pub fn coprocessor_versions_path() -> PathBuf {
+    let home_dir = env::var("HOME").expect("Failed to get HOME directory");
+    PathBuf::from(home_dir).join(".cartesi-coprocessor-versions")
+}
+
+/// @notice Function to get the folder a pinned version of the coprocessor repository is checked out into
+/// @notice Only plain ref names are accepted, so a version can't point the checkout outside the versions folder
+/// @param version A tag, branch or commit of the coprocessor repository
+/// @return A result containing the path to the checkout of the version
+fn version_checkout_path(version: &str) -> Result<PathBuf, String> {
+    let valid = !version.is_empty()
+        && !version.starts_with('-')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/' | '+'))
+        && version
+            .split('/')
+            .all(|component| !component.is_empty() && !component.starts_with('.'))
+        && !version.contains("..");
+
+    if valid {
+        Ok(coprocessor_versions_path().join(version.replace('/', "_")))
+    } else {
+        Err(format!("Invalid Cartesi-Coprocessor version '{}'", version))
+    }
+}
+
+/// @notice Function to get the path of the file recording which checkout the devnet was last started from
+/// @return the path to the active devnet record
+fn active_devnet_record_path() -> PathBuf {
+    coprocessor_versions_path().join("active.json")
+}
+
+/// @notice Function to read the record of the checkout the devnet was last started from
+/// @return an Option containing the record with the `path`, `version` and `commit` of the devnet
+pub fn active_devnet() -> Option<serde_json::Value> {
+    let content = fs::read_to_string(active_devnet_record_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// @notice Function to read a devnet config saved in the active devnet record
+/// @param config The `config` field of the record
+/// @return an Option containing the devnet config, None if the record predates it or is malformed
+fn recorded_devnet_config(config: &serde_json::Value) -> Option<DevnetConfig> {
+    let port = |key: &str| {
+        config[key]
+            .as_u64()
+            .and_then(|port| u16::try_from(port).ok())
+    };
+    Some(DevnetConfig {
+        anvil_port: port("anvil_port")?,
+        solver_port: port("solver_port")?,
+        ipfs_port: port("ipfs_port")?,
+        compose_override: config["compose_override"].as_str().map(PathBuf::from),
+    })
+}
+
+/// @notice Function to get the ports and compose override the running devnet was started with
+/// @notice The devnet may have been started from another project, or coprocessor.toml changed since, so its config is read from the record
+/// @return the recorded devnet config, or the devnet config of the current project when no devnet is running
+pub fn active_devnet_config() -> DevnetConfig {
+    active_devnet()
+        .filter(|record| record["running"].as_bool() == Some(true))
+        .and_then(|record| recorded_devnet_config(&record["config"]))
+        .unwrap_or_else(get_devnet_config)
+}
+
+/// @notice Function to get the checkout the devnet was last started from
+/// @return an Option containing the path to the checkout, None if the devnet was never started or its checkout was removed
+pub fn active_devnet_path() -> Option<String> {
+    let path = active_devnet()?["path"].as_str()?.to_string();
+    PathBuf::from(&path).exists().then_some(path)
+}
+
+/// @notice Function to record which checkout, version and devnet config the devnet was started from
+/// @param path The path to the checkout of the coprocessor repository
+/// @param version The pinned tag or commit, None when tracking the release branch
+/// @param devnet_config The ports and compose override the devnet was started with
+fn record_active_devnet(path: &str, version: Option<String>, devnet_config: &DevnetConfig) {
+    let record = json!({
+        "path": path,
+        "version": version,
+        "commit": git_output(path, &["rev-parse", "HEAD"]).unwrap_or_default(),
+        "config": {
+            "anvil_port": devnet_config.anvil_port,
+            "solver_port": devnet_config.solver_port,
+            "ipfs_port": devnet_config.ipfs_port,
+            "compose_override": devnet_config
+                .compose_override
+                .as_ref()
+                .map(|path| path.display().to_string()),
+        },
+        "started_at": chrono::Local::now().to_rfc3339(),
+        "running": true,
+    });
+
+    let saved = fs::create_dir_all(coprocessor_versions_path())
+        .and_then(|_| fs::write(active_devnet_record_path(), record.to_string()));
+    if let Err(e) = saved {
+        eprintln!("❌ Failed to record the active devnet version: {:?}", e);
+    }
+}
+
+/// @notice Function to mark the recorded devnet as stopped
+fn record_devnet_stopped() {
+    if let Some(mut record) = active_devnet() {
+        record["running"] = json!(false);
+        if let Err(e) = fs::write(active_devnet_record_path(), record.to_string()) {
+            eprintln!("❌ Failed to record the active devnet version: {:?}", e);
+        }
+    }
+}
+
+/// @notice Function to take an anvil snapshot of the freshly started devnet, which the devnet reset command reverts to
+pub fn record_devnet_baseline() {
+    let Some(mut record) = active_devnet() else {
+        return;
+    };
+
+    match rpc_call(
+        &active_devnet_config().anvil_rpc(),
+        "evm_snapshot",
+        json!([]),
+    ) {
+        Ok(snapshot_id) => {
+            record["baseline_snapshot"] = snapshot_id;
+            if let Err(e) = fs::write(active_devnet_record_path(), record.to_string()) {
+                eprintln!("❌ Failed to record the devnet baseline: {:?}", e);
+            }
+        }
+        Err(e) => eprintln!(
+            "⚠️  {} {}",
+            "Failed to snapshot the devnet chain, devnet reset will not be available:".yellow(),
+            e.yellow()
+        ),
+    }
+}
+
+/// @notice Function to forget the devnet baseline, once the anvil node restarted its snapshot ids point at nothing or at newer snapshots
+pub fn clear_devnet_baseline() {
+    let Some(mut record) = active_devnet() else {
+        return;
+    };
+
+    if let Some(fields) = record.as_object_mut() {
+        fields.remove("baseline_snapshot");
+    }
+    if let Err(e) = fs::write(active_devnet_record_path(), record.to_string()) {
+        eprintln!("❌ Failed to record the devnet baseline: {:?}", e);
+    }
+}
+
+/// @notice Function to run a git command and capture its output
+/// @param path The directory to run the command in
+/// @param args The arguments to pass to git
+/// @return A result containing the trimmed stdout of the command, or its stderr on failure
+fn git_output(path: &str, args: &[&str]) -> Result<String, String> {
+    let git_status = run_captured(
+        Command::new("git").args(args).current_dir(path),
+        LONG_TIMEOUT,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if git_status.status.success() {
+        Ok(String::from_utf8_lossy(&git_status.stdout)
+            .trim()
+            .to_string())
+    } else {
+        Err(String::from_utf8_lossy(&git_status.stderr)
+            .trim()
+            .to_string())
+    }
+}
+
+/// @notice Function to resolve a version of the co-processor to a commit of the fetched repository
+/// @notice Branches resolve to the fetched remote branch first, as a local branch of the same name may be stale
+/// @param path The path to the co-processor repository
+/// @param version A branch, tag or commit
+/// @return A result containing the commit, or the error of git
+fn resolve_version_commit(path: &str, version: &str) -> Result<String, String> {
+    git_output(
+        path,
+        &[
+            "rev-parse",
+            "--verify",
+            &format!("refs/remotes/origin/{}^{{commit}}", version),
+        ],
+    )
+    .or_else(|_| {
+        git_output(
+            path,
+            &["rev-parse", "--verify", &format!("{}^{{commit}}", version)],
+        )
+    })
+}
+
+/// @notice Function to check out a tag or commit of the coprocessor repository into its own folder,
+/// @notice so several versions can be kept side by side without re-cloning
+/// @param path The path to the main clone of the coprocessor repository
+/// @param version The tag, branch or commit to check out
+/// @return an Option containing the path to the checkout of the version
+fn checkout_version(path: String, version: &str) -> Option<String> {
+    let version_path = match version_checkout_path(version) {
+        Ok(version_path) => version_path,
+        Err(e) => {
+            eprintln!("❌ {}", e.red());
+            return None;
+        }
+    };
+
+    println!("Fetching Cartesi-Coprocessor version {}...", version);
+    if let Err(stderr) = git_output(&path, &["fetch", "--tags", "origin"]) {
+        println!("{} {}", "GIT::RESPONSE::".red(), stderr.red());
+    }
+
+    let Ok(commit) = resolve_version_commit(&path, version) else {
+        eprintln!(
+            "❌ {} {}",
+            "Unknown Cartesi-Coprocessor version:".red(),
+            version.red()
+        );
+        return None;
+    };
+
+    let version_path_str = version_path
+        .to_str()
+        .expect("Error converting path to String")
+        .to_string();
+
+    let checkout = if version_path.exists() {
+        git_output(&version_path_str, &["checkout", "--detach", &commit])
+    } else {
+        fs::create_dir_all(coprocessor_versions_path())
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|_| {
+                git_output(
+                    &path,
+                    &["worktree", "add", "--detach", &version_path_str, &commit],
+                )
+            })
+    };
+
+    match checkout {
+        Ok(_) => {
+            println!(
+                "✅ Checked out version {} ({}) at {:?}",
+                version,
+                &commit[..commit.len().min(12)],
+                version_path
+            );
+            Some(version_path_str)
+        }
+        Err(stderr) => {
+            eprintln!("❌ Failed to check out version {}.", version);
+            println!("{} {}", "GIT::RESPONSE::".red(), stderr.red());
+            None
+        }
+    }
+}
+
+/// @notice Function to list the versions of the coprocessor repository checked out side by side
+/// @return the names of the version folders
+pub fn checked_out_versions() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(coprocessor_versions_path()) else {
+        return Vec::new();
+    };
+
+    let mut versions: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    versions.sort();
+    versions
+}
+
 /// @notice Function to clone the cartesi-coprocessor repository into a specified repo on host machine
 fn clone_coprocessor_repo() -> Option<String> {
     // Get the directory path to clone the cartesi-coprocessor repository
-    let home_dir = env::var("HOME").expect("Failed to get HOME directory");
-    let copro_path = PathBuf::from(home_dir).join(".cartesi-coprocessor-repo");
+    let copro_path = coprocessor_repo_path();
 
     // Check if the folder exists
     if !copro_path.exists() {
@@ -105,16 +767,19 @@ fn clone_coprocessor_repo() -> Option<String> {
 
     // Clone the repository
     println!("Cloning Cartesi-Coprocessor repository...");
-    let clone_status = Command::new("git")
-        .arg("clone")
-        .arg("https://github.com/zippiehq/cartesi-coprocessor")
-        .arg(&copro_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute git clone command")
-        .wait_with_output()
-        .expect("Failed to complete repository cloning");
+    let clone_status = match run_captured(
+        Command::new("git")
+            .arg("clone")
+            .arg("https://github.com/zippiehq/cartesi-coprocessor")
+            .arg(&copro_path),
+        LONG_TIMEOUT,
+    ) {
+        Ok(clone_status) => clone_status,
+        Err(e) => {
+            report_process_error("GIT", &e);
+            return None;
+        }
+    };
 
     if clone_status.status.success() {
         println!(
@@ -134,15 +799,16 @@ fn clone_coprocessor_repo() -> Option<String> {
 /// @notice Function to check the git status of the coprocessor repo for cases where the local version is behind the remote branch
 /// @param path The path to the local coprocessor repository
 fn check_git_status(path: String) {
-    let status_output = Command::new("git")
-        .arg("status")
-        .current_dir(path.clone())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute git status command")
-        .wait_with_output()
-        .expect("Failed to complete git status check");
+    let status_output = match run_captured(
+        Command::new("git").arg("status").current_dir(path.clone()),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(status_output) => status_output,
+        Err(e) => {
+            report_process_error("GIT", &e);
+            return;
+        }
+    };
 
     if status_output.status.success() {
         let response = String::from_utf8_lossy(&status_output.stdout);
@@ -164,17 +830,20 @@ fn check_git_status(path: String) {
 /// @notice Function to pull latest changes from the remote repository for the coprocessor
 /// /// @param path The path to the local coprocessor repository
 fn pull_latest_changes(path: String) {
-    let pull_status = Command::new("git")
-        .arg("pull")
-        .arg("origin")
-        .arg("main")
-        .current_dir(path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute git pull command")
-        .wait_with_output()
-        .expect("Failed to complete git pull");
+    let pull_status = match run_captured(
+        Command::new("git")
+            .arg("pull")
+            .arg("origin")
+            .arg("main")
+            .current_dir(path),
+        LONG_TIMEOUT,
+    ) {
+        Ok(pull_status) => pull_status,
+        Err(e) => {
+            report_process_error("GIT", &e);
+            return;
+        }
+    };
 
     if pull_status.status.success() {
         println!(
@@ -190,71 +859,40 @@ fn pull_latest_changes(path: String) {
 /// @notice Function to update submodules contained in the coprocessor repository
 /// @param path The path to the local coprocessor repository
 fn update_submodules(path: String) -> bool {
-    let mut update_status = Command::new("git")
-        .arg("submodule")
-        .arg("update")
-        .arg("--init")
-        .arg("--recursive")
-        .current_dir(path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute git submodule update command");
-
-    let stdout = BufReader::new(
-        update_status
-            .stdout
-            .take()
-            .expect("Failed to capture stdout"),
-    );
-    let stderr = BufReader::new(
-        update_status
-            .stderr
-            .take()
-            .expect("Failed to capture stderr"),
+    let update_status = run_streamed(
+        Command::new("git")
+            .arg("submodule")
+            .arg("update")
+            .arg("--init")
+            .arg("--recursive")
+            .current_dir(path),
+        "GIT",
+        LONG_TIMEOUT,
     );
-    // Handle output in separate threads
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            if let Ok(line) = line {
-                println!("{} {}", "GIT:: ".green(), line.green());
-            }
-        }
-    });
 
-    let start = time::Instant::now();
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            if let Ok(line) = line {
-                eprintln!("{} {}", "GIT::NOTE::".yellow(), line.yellow());
-            } else if let Err(e) = line {
-                eprintln!("{} {}", "GIT::ERROR::".red(), e);
-            }
+    match update_status {
+        Ok(output) if output.status.success() => {
+            println!("✅  Successfully updated submodules.");
+            true
         }
-    });
-
-    while start.elapsed().as_secs() < 30000 {
-        if let Some(status) = update_status
-            .try_wait()
-            .expect("Failed to update submodules")
-        {
-            if status.success() {
-                println!("✅  Successfully updated submodules.");
-                return true;
-            } else {
-                eprintln!("❌ Failed to update submodules.");
-                return false;
-            }
+        Ok(_) => {
+            eprintln!("❌ Failed to update submodules.");
+            false
+        }
+        Err(e) => {
+            report_process_error("GIT", &e);
+            false
         }
-
-        thread::sleep(time::Duration::from_secs(5));
     }
-    return false;
 }
 
 /// @notice Function to Stop a currently running local dev network containers for the coprocessor
 pub fn stop_devnet() {
-    let coprocessor_path = clone_coprocessor_repo();
+    if !check_docker_daemon() {
+        return;
+    }
+    // Stop the checkout the devnet was started from, which differs from the main clone for pinned versions
+    let coprocessor_path = active_devnet_path().or_else(clone_coprocessor_repo);
 
     match coprocessor_path {
         Some(path) => {
@@ -262,22 +900,26 @@ pub fn stop_devnet() {
             spinner.set_message("Stoping devnet containers...");
 
             // Run Cartesi-Coprocessor in the background
-            let docker_status = Command::new("docker")
-                .arg("compose")
-                .arg("-f")
-                .arg("docker-compose-devnet.yaml")
-                .arg("down")
-                .arg("-v")
-                .current_dir(path)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .expect("Failed to start Cartesi-Coprocessor devnet environment")
-                .wait_with_output()
-                .expect("Failed to complete git status check");
+            let docker_status = match run_captured(
+                Command::new("docker")
+                    .arg("compose")
+                    .args(compose_file_args(Path::new(&path), &active_devnet_config()))
+                    .arg("down")
+                    .arg("-v")
+                    .current_dir(path),
+                LONG_TIMEOUT,
+            ) {
+                Ok(docker_status) => docker_status,
+                Err(e) => {
+                    spinner.finish_and_clear();
+                    report_process_error("DOCKER", &e);
+                    return;
+                }
+            };
 
             if docker_status.status.success() {
                 spinner.finish_and_clear();
+                record_devnet_stopped();
                 println!(
                     "✅ {}",
                     "Cartesi-Coprocessor devnet environment stoped.".green()
@@ -299,97 +941,103 @@ pub fn stop_devnet() {
     }
 }
 
-/// @notice Function to build containers for the coprocessor
+/// @notice Function to build the containers of the coprocessor with `docker compose build`,
+/// @notice which honors `.dockerignore` and builds with BuildKit like a plain `docker build`
 /// @param path The path to the local coprocessor repository
 fn build_container(path: String) -> bool {
     let spinner = get_spinner();
     spinner.set_message("Building devnet containers...");
 
-    let pull_status = Command::new("docker")
-        .arg("compose")
-        .arg("-f")
-        .arg("docker-compose-devnet.yaml")
-        .arg("build")
-        .current_dir(path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute build container command")
-        .wait_with_output()
-        .expect("Failed to complete build container command");
+    let build_status = match run_captured(
+        Command::new("docker")
+            .arg("compose")
+            .args(compose_file_args(Path::new(&path), &get_devnet_config()))
+            .arg("build")
+            .current_dir(path),
+        LONG_TIMEOUT,
+    ) {
+        Ok(build_status) => build_status,
+        Err(e) => {
+            spinner.finish_and_clear();
+            report_process_error("DOCKER", &e);
+            return false;
+        }
+    };
 
-    if pull_status.status.success() {
-        spinner.finish_and_clear();
+    spinner.finish_and_clear();
+    if build_status.status.success() {
         println!("✅ {}", "Successfully built Devnet containers.".green());
-        return true;
+        true
     } else {
-        spinner.finish_and_clear();
         eprintln!("❌ Failed to build containers.");
-        let stderr = String::from_utf8_lossy(&pull_status.stderr);
-        if stderr.contains("Is the docker daemon running") {
-            println!(
-                "❌ {}",
-                "Docker daemon is not running. Please start it and try again.".red()
-            );
-            return false;
-        } else {
-            println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
-            return false;
-        }
+        println!(
+            "{} {}",
+            "DOCKER::RESPONSE::".red(),
+            String::from_utf8_lossy(&build_status.stderr).red()
+        );
+        false
     }
 }
 
-/// @notice Function to pull updates to the coprocessor containers
+/// @notice Function to pull updates to the coprocessor containers through the Docker Engine API, services built locally are skipped
 /// @param path The path to the local coprocessor repository
 fn pull_container(path: String) -> bool {
+    let Some(config) = compose_config(Path::new(&path), &get_devnet_config()) else {
+        return false;
+    };
+
+    let mut images: Vec<String> = config["services"]
+        .as_object()
+        .map(|services| {
+            services
+                .values()
+                .filter(|service| service.get("build").is_none())
+                .filter_map(|service| service["image"].as_str().map(|image| image.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    images.sort();
+    images.dedup();
+
     let spinner = get_spinner();
     spinner.set_message("Pulling changes to devnet containers...");
 
-    let pull_status = Command::new("docker")
-        .arg("compose")
-        .arg("-f")
-        .arg("docker-compose-devnet.yaml")
-        .arg("pull")
-        .current_dir(path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to pull changes to dev container")
-        .wait_with_output()
-        .expect("Failed to complete pull changes command");
-
-    if pull_status.status.success() {
-        spinner.finish_and_clear();
-        println!(
-            "✅ {}",
-            "Successfully pulled changes to Devnet containers.".green()
-        );
-        return true;
-    } else {
-        spinner.finish_and_clear();
-        eprintln!("❌ Failed to pull changes to containers.");
-        let stderr = String::from_utf8_lossy(&pull_status.stderr);
-        println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
-        return false;
+    for image in images {
+        if let Err(e) = pull_image(&image, &spinner) {
+            spinner.finish_and_clear();
+            eprintln!("❌ Failed to pull changes to containers.");
+            println!("{} {}", "DOCKER::RESPONSE::".red(), e.to_string().red());
+            return false;
+        }
     }
+
+    spinner.finish_and_clear();
+    println!(
+        "✅ {}",
+        "Successfully pulled changes to Devnet containers.".green()
+    );
+    true
 }
 
 /// @notice Function to create, pull and change the coprocessor working branch to origin/release
 /// @param path The path to the cloned coprocessor repo on user's local machine
 /// @return true if the change was successful else false
 fn change_branch(path: String) -> bool {
-    let checkout_status = Command::new("git")
-        .arg("checkout")
-        .arg("-b")
-        .arg("release")
-        .arg("origin/release")
-        .current_dir(path.clone())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute git checkout command")
-        .wait_with_output()
-        .expect("Failed to complete git checkout");
+    let checkout_status = match run_captured(
+        Command::new("git")
+            .arg("checkout")
+            .arg("-b")
+            .arg("release")
+            .arg("origin/release")
+            .current_dir(path.clone()),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(checkout_status) => checkout_status,
+        Err(e) => {
+            report_process_error("GIT", &e);
+            return false;
+        }
+    };
 
     if checkout_status.status.success() {
         println!("✅ Switched to release branch 'origin/release'");
@@ -414,17 +1062,20 @@ fn change_branch(path: String) -> bool {
 /// @param path The path to the cloned coprocessor repo on user's local machine
 /// @return true if the origin/release branch is active else false
 fn check_active_branch(path: String) -> bool {
-    let checkout_status = Command::new("git")
-        .arg("rev-parse")
-        .arg("--abbrev-ref")
-        .arg("HEAD")
-        .current_dir(path.clone())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute check active branch command")
-        .wait_with_output()
-        .expect("Failed to complete check active branch command");
+    let checkout_status = match run_captured(
+        Command::new("git")
+            .arg("rev-parse")
+            .arg("--abbrev-ref")
+            .arg("HEAD")
+            .current_dir(path.clone()),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(checkout_status) => checkout_status,
+        Err(e) => {
+            report_process_error("GIT", &e);
+            return false;
+        }
+    };
 
     if checkout_status.status.success() {
         let std_out = String::from_utf8_lossy(&checkout_status.stdout);
@@ -448,16 +1099,19 @@ fn check_active_branch(path: String) -> bool {
 /// @param path The path to the cloned coprocessor repo on user's local machine
 /// @return true if the origin/release branch is active else false
 fn checkout_release_branch(path: String) -> bool {
-    let checkout_status = Command::new("git")
-        .arg("checkout")
-        .arg("release")
-        .current_dir(path.clone())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute git fetch command")
-        .wait_with_output()
-        .expect("Failed to complete git checkout");
+    let checkout_status = match run_captured(
+        Command::new("git")
+            .arg("checkout")
+            .arg("release")
+            .current_dir(path.clone()),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(checkout_status) => checkout_status,
+        Err(e) => {
+            report_process_error("GIT", &e);
+            return false;
+        }
+    };
 
     if checkout_status.status.success() {
         println!("✅ Switched to release branch 'release'");
@@ -467,3 +1121,500 @@ fn checkout_release_branch(path: String) -> bool {
         return false;
     }
 }
+
+/// @notice Function to list the state of the devnet docker compose services
+/// @param path The path to the local coprocessor repository
+/// @return an Option containing one entry per compose service, None if docker compose could not be queried
+fn devnet_services(path: &Path) -> Option<Vec<serde_json::Value>> {
+    let ps_status = match run_captured(
+        Command::new("docker")
+            .arg("compose")
+            .args(compose_file_args(path, &active_devnet_config()))
+            .arg("ps")
+            .arg("--all")
+            .arg("--format")
+            .arg("json")
+            .current_dir(path),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(ps_status) => ps_status,
+        Err(e) => {
+            report_process_error("DOCKER", &e);
+            return None;
+        }
+    };
+
+    if !ps_status.status.success() {
+        let stderr = String::from_utf8_lossy(&ps_status.stderr);
+        if stderr.contains("Is the docker daemon running") {
+            println!(
+                "❌ {}",
+                "Docker daemon is not running. Please start it and try again.".red()
+            );
+        } else {
+            println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+        }
+        return None;
+    }
+
+    // Older compose versions print a json array, newer ones print one json object per line
+    let stdout = String::from_utf8_lossy(&ps_status.stdout);
+    match serde_json::from_str::<serde_json::Value>(stdout.trim()) {
+        Ok(serde_json::Value::Array(services)) => Some(services),
+        _ => Some(
+            stdout
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect(),
+        ),
+    }
+}
+
+/// @notice Function to format the published ports of a compose service
+/// @param service A compose service entry from `docker compose ps`
+/// @return a comma separated list of `host->container` ports
+fn service_ports(service: &serde_json::Value) -> String {
+    // Ports published on both IPv4 and IPv6 are listed twice, and not always next to each other
+    let ports: BTreeSet<(u64, u64)> = service["Publishers"]
+        .as_array()
+        .map(|publishers| {
+            publishers
+                .iter()
+                .map(|publisher| {
+                    (
+                        publisher["PublishedPort"].as_u64().unwrap_or(0),
+                        publisher["TargetPort"].as_u64().unwrap_or(0),
+                    )
+                })
+                .filter(|(published, _)| *published != 0)
+                .collect()
+        })
+        .unwrap_or_default();
+    let ports: Vec<String> = ports
+        .iter()
+        .map(|(published, target)| format!("{}->{}", published, target))
+        .collect();
+
+    if ports.is_empty() {
+        String::from("--")
+    } else {
+        ports.join(", ")
+    }
+}
+
+/// @notice Function to probe the anvil node of the devnet
+/// @param rpc The url of the anvil node
+/// @return a description of the node state, and whether it is healthy
+fn probe_anvil(rpc: &str) -> (String, bool) {
+    match (
+        get_chain_id(rpc),
+        rpc_call(rpc, "eth_blockNumber", json!([])),
+    ) {
+        (Ok(chain_id), Ok(block)) => (
+            format!(
+                "chain id {}, block {}",
+                chain_id,
+                parse_quantity(&block).unwrap_or_default()
+            ),
+            true,
+        ),
+        (Err(e), _) | (_, Err(e)) => (e, false),
+    }
+}
+
+/// @notice Function to probe an http service of the devnet, any http response counts as reachable
+/// @param url The url of the service
+/// @return a description of the service state, and whether it is reachable
+fn probe_http(url: &str) -> (String, bool) {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("Failed to build HTTP client");
+
+    match client.get(url).send() {
+        Ok(response) => (format!("reachable (HTTP {})", response.status()), true),
+        Err(e) => (format!("unreachable: {}", e), false),
+    }
+}
+
+/// @notice Function to probe the IPFS node of the devnet
+/// @param api_url The url of the IPFS api
+/// @return a description of the node state, and whether it is healthy
+fn probe_ipfs(api_url: &str) -> (String, bool) {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .expect("Failed to build HTTP client");
+
+    match client.post(format!("{}/api/v0/version", api_url)).send() {
+        Ok(response) if response.status().is_success() => {
+            let body: serde_json::Value = response.json().unwrap_or_default();
+            (
+                format!("version {}", body["Version"].as_str().unwrap_or("unknown")),
+                true,
+            )
+        }
+        Ok(response) => (format!("unhealthy (HTTP {})", response.status()), false),
+        Err(e) => (format!("unreachable: {}", e), false),
+    }
+}
+
+/// @notice Function to get the checkout the devnet runs from, the last started checkout or the main clone
+/// @return the path to the checkout
+pub fn devnet_path() -> PathBuf {
+    active_devnet_path()
+        .map(PathBuf::from)
+        .unwrap_or_else(coprocessor_repo_path)
+}
+
+/// @notice Function to report the health of the devnet: the compose services, the endpoints used by the cli and the task issuer
+pub fn devnet_status() {
+    let path = devnet_path();
+    if !path.join("docker-compose-devnet.yaml").exists() {
+        println!(
+            "❌ {}",
+            "Devnet is not installed, please run the start-devnet command first.".red()
+        );
+        return;
+    }
+
+    let Some(services) = devnet_services(&path) else {
+        return;
+    };
+
+    let path_str = path.to_str().expect("Error converting path to String");
+    let version = active_devnet()
+        .and_then(|record| record["version"].as_str().map(|v| v.to_string()))
+        .unwrap_or_else(|| String::from("release branch"));
+    let commit = git_output(path_str, &["rev-parse", "--short", "HEAD"]).unwrap_or_default();
+    println!("{} {} ({})", "Version".bold(), version, commit);
+
+    let versions = checked_out_versions();
+    if !versions.is_empty() {
+        println!("{} {}", "Checked out versions".bold(), versions.join(", "));
+    }
+    println!();
+
+    println!("{}", "Services".bold());
+    if services.is_empty() {
+        println!(
+            "{}",
+            "No devnet containers found, the devnet is stopped.".yellow()
+        );
+    }
+
+    let rows: Vec<(String, String, String, String)> = services
+        .iter()
+        .map(|service| {
+            let state = match service["Health"].as_str() {
+                Some(health) if !health.is_empty() => format!(
+                    "{} ({})",
+                    service["State"].as_str().unwrap_or_default(),
+                    health
+                ),
+                _ => service["State"].as_str().unwrap_or_default().to_string(),
+            };
+            (
+                service["Service"].as_str().unwrap_or_default().to_string(),
+                state,
+                service_ports(service),
+                service["Image"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+
+    // Calculate the width of the columns
+    let name_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+    let state_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+    let ports_width = rows.iter().map(|row| row.2.len()).max().unwrap_or(0);
+
+    for (name, state, ports, image) in rows {
+        let state = if state.starts_with("running") && !state.contains("unhealthy") {
+            state.green()
+        } else {
+            state.red()
+        };
+        println!(
+            "{:<name_width$}  {:<state_width$}  {:<ports_width$}  {}",
+            name,
+            state,
+            ports,
+            image,
+            name_width = name_width,
+            state_width = state_width,
+            ports_width = ports_width
+        );
+    }
+
+    let devnet_config = active_devnet_config();
+    let anvil_rpc = devnet_config.anvil_rpc();
+    let endpoints = vec![
+        ("anvil", anvil_rpc.clone(), probe_anvil(&anvil_rpc)),
+        (
+            "solver",
+            devnet_config.solver_url(),
+            probe_http(&devnet_config.solver_url()),
+        ),
+        (
+            "ipfs",
+            devnet_config.ipfs_api_url(),
+            probe_ipfs(&devnet_config.ipfs_api_url()),
+        ),
+    ];
+
+    println!("\n{}", "Endpoints".bold());
+    let name_width = endpoints.iter().map(|e| e.0.len()).max().unwrap_or(0);
+    let url_width = endpoints.iter().map(|e| e.1.len()).max().unwrap_or(0);
+    for (name, url, (state, healthy)) in endpoints {
+        let marker = if healthy { "✅" } else { "❌" };
+        println!(
+            "{} {:<name_width$}  {:<url_width$}  {}",
+            marker,
+            name,
+            url,
+            state,
+            name_width = name_width,
+            url_width = url_width
+        );
+    }
+
+    if let Some(task_issuer) = get_network_profile("devnet").and_then(|p| p.task_issuer) {
+        let deployed = match has_code(&anvil_rpc, &task_issuer) {
+            Ok(true) => "deployed".green(),
+            Ok(false) => "not deployed".red(),
+            Err(_) => "unknown".yellow(),
+        };
+        println!("\n{}", "Task issuer".bold());
+        println!("{}  {}", task_issuer, deployed);
+    }
+}
+
+/// @notice Function to split a line of compose logs into the service it came from and the message
+/// @param line A line printed by `docker compose logs`, e.g. `solver-1  | message`
+/// @return the service name without the replica suffix, and the message
+fn split_log_line(line: &str) -> (String, String) {
+    match line.split_once(" | ") {
+        Some((container, message)) => {
+            let container = container.trim();
+            let service = match container.rsplit_once('-') {
+                Some((service, replica)) if replica.chars().all(|c| c.is_ascii_digit()) => service,
+                _ => container,
+            };
+            (service.to_string(), message.to_string())
+        }
+        None => (String::new(), line.to_string()),
+    }
+}
+
+/// @notice Function to stream the logs of the devnet containers, prefixed per service
+/// @param service Only show logs of this compose service, if None logs of every service are shown
+/// @param follow Keep streaming new log lines until interrupted
+/// @param since Only show logs newer than this timestamp or relative duration, e.g. `10m`
+/// @param grep Only show lines containing this text, e.g. a machine hash or upload id
+pub fn devnet_logs(
+    service: Option<String>,
+    follow: bool,
+    since: Option<String>,
+    grep: Option<String>,
+) {
+    let path = devnet_path();
+    if !path.join("docker-compose-devnet.yaml").exists() {
+        println!(
+            "❌ {}",
+            "Devnet is not installed, please run the start-devnet command first.".red()
+        );
+        return;
+    }
+
+    let mut command = Command::new("docker");
+    command
+        .arg("compose")
+        .args(compose_file_args(&path, &active_devnet_config()))
+        .arg("logs")
+        .arg("--no-color");
+    if follow {
+        command.arg("--follow");
+    }
+    if let Some(since) = since {
+        command.arg("--since").arg(since);
+    }
+    if let Some(service) = service {
+        command.arg(service);
+    }
+
+    command.current_dir(path);
+
+    // Hashes are printed with and without a 0x prefix, so match on the bare value
+    let grep = grep.map(|pattern| pattern.trim_start_matches("0x").to_lowercase());
+
+    let logs_status = run_process(&mut command, NO_TIMEOUT, |is_stderr, line| {
+        if is_stderr {
+            eprintln!("{} {}", "DOCKER::NOTE::".yellow(), line.yellow());
+            return;
+        }
+        if let Some(pattern) = &grep {
+            if !line.to_lowercase().contains(pattern) {
+                return;
+            }
+        }
+
+        let (service, message) = split_log_line(line);
+        if service.is_empty() {
+            println!("{}", message);
+        } else {
+            println!(
+                "{} {}",
+                format!("{}::", service.to_uppercase()).green(),
+                message
+            );
+        }
+    });
+
+    match logs_status {
+        Ok(status) if !status.success() => {
+            eprintln!("❌ {}", "Failed to read devnet logs.".red())
+        }
+        Ok(_) => {}
+        Err(e) => report_process_error("DOCKER", &e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branches_resolve_to_the_fetched_remote_branch() {
+        let dir = env::temp_dir().join(format!("devnet-version-{}", std::process::id()));
+        let origin = dir.join("origin");
+        let clone = dir.join("clone");
+        fs::create_dir_all(&origin).unwrap();
+        let git = |path: &Path, args: &[&str]| git_output(path.to_str().unwrap(), args).unwrap();
+        let commit = |path: &Path, message: &str| {
+            git(
+                path,
+                &[
+                    "-c",
+                    "user.name=a",
+                    "-c",
+                    "user.email=a@a",
+                    "commit",
+                    "-q",
+                    "--allow-empty",
+                    "-m",
+                    message,
+                ],
+            );
+            git(path, &["rev-parse", "HEAD"])
+        };
+        git(&origin, &["init", "-q", "-b", "main"]);
+        let first = commit(&origin, "first");
+        git(&origin, &["tag", "v1.0.0"]);
+        git(
+            &dir,
+            &[
+                "clone",
+                "-q",
+                origin.to_str().unwrap(),
+                clone.to_str().unwrap(),
+            ],
+        );
+
+        // The local main of the clone stays at the first commit once origin moves on
+        let second = commit(&origin, "second");
+        git(&clone, &["fetch", "-q", "--tags", "origin"]);
+        let resolve = |version: &str| resolve_version_commit(clone.to_str().unwrap(), version);
+        assert_eq!(resolve("main").unwrap(), second);
+        assert_eq!(resolve("v1.0.0").unwrap(), first);
+        assert_eq!(resolve(&first).unwrap(), first);
+        assert!(resolve("missing").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn accepts_plain_ref_names() {
+        for version in [
+            "v1.0.0",
+            "release/v1",
+            "main",
+            "4f2a9c1",
+            "v1.0.0-rc.1+build",
+        ] {
+            assert!(version_checkout_path(version).is_ok(), "{}", version);
+        }
+        assert!(version_checkout_path("release/v1")
+            .unwrap()
+            .ends_with(".cartesi-coprocessor-versions/release_v1"));
+    }
+
+    #[test]
+    fn rejects_versions_escaping_the_versions_folder() {
+        for version in [
+            "", "..", "../etc", "a/../b", "/tmp/x", "a//b", "v1/", ".hidden", "-flag", "v1 2",
+            "v1~1",
+        ] {
+            assert!(version_checkout_path(version).is_err(), "{}", version);
+        }
+    }
+
+    #[test]
+    fn reads_the_recorded_devnet_config() {
+        let config = recorded_devnet_config(&json!({
+            "anvil_port": 18545,
+            "solver_port": 13034,
+            "ipfs_port": 15001,
+            "compose_override": "/work/devnet.override.yaml",
+        }))
+        .unwrap();
+        assert_eq!(config.anvil_rpc(), "http://127.0.0.1:18545");
+        assert_eq!(config.solver_url(), "http://127.0.0.1:13034");
+        assert_eq!(config.ipfs_api_url(), "http://127.0.0.1:15001");
+        assert_eq!(
+            config.compose_override,
+            Some(PathBuf::from("/work/devnet.override.yaml"))
+        );
+    }
+
+    #[test]
+    fn ignores_records_without_a_devnet_config() {
+        assert!(recorded_devnet_config(&serde_json::Value::Null).is_none());
+        assert!(recorded_devnet_config(&json!({
+            "anvil_port": 70000,
+            "solver_port": 3034,
+            "ipfs_port": 5001,
+        }))
+        .is_none());
+    }
+
+    #[test]
+    fn splits_service_and_message() {
+        assert_eq!(
+            split_log_line("solver-1  | listening on 3034"),
+            (String::from("solver"), String::from("listening on 3034"))
+        );
+    }
+
+    #[test]
+    fn strips_only_numeric_replica_suffixes() {
+        assert_eq!(
+            split_log_line("coprocessor-operator-12 | ok").0,
+            "coprocessor-operator"
+        );
+        assert_eq!(split_log_line("anvil-node | ok").0, "anvil-node");
+        assert_eq!(split_log_line("ipfs | ok").0, "ipfs");
+    }
+
+    #[test]
+    fn keeps_separators_inside_the_message() {
+        assert_eq!(split_log_line("operator-1  | a | b").1, "a | b");
+    }
+
+    #[test]
+    fn lines_without_a_service_keep_the_whole_line() {
+        assert_eq!(
+            split_log_line("Attaching to solver-1"),
+            (String::new(), String::from("Attaching to solver-1"))
+        );
+    }
+}
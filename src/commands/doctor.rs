@@ -0,0 +1,333 @@
+use crate::helpers::docker::ping;
+use crate::helpers::helpers::add_npm_home_dir_to_path;
+use crate::helpers::process::{run_captured, SHORT_TIMEOUT};
+use colored::Colorize;
+use regex::Regex;
+use std::env;
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// @notice Free disk space below which the doctor command warns, devnet images and Cartesi machine builds take several GiB
+const MIN_FREE_DISK_SPACE: u64 = 10 * 1024 * 1024 * 1024;
+
+/// @notice An external tool the cli runs, with the command printing its version and the versions it is known to work with
+struct Tool {
+    name: &'static str,
+    program: &'static str,
+    version_args: &'static [&'static str],
+    min_version: Option<&'static str>,
+    max_version: Option<&'static str>,
+}
+
+/// @notice Tools checked by the doctor command, `max_version` is the first unsupported version
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "forge",
+        program: "forge",
+        version_args: &["--version"],
+        min_version: Some("0.2.0"),
+        max_version: Some("2.0.0"),
+    },
+    Tool {
+        name: "cast",
+        program: "cast",
+        version_args: &["--version"],
+        min_version: Some("0.2.0"),
+        max_version: Some("2.0.0"),
+    },
+    Tool {
+        name: "cartesi",
+        program: "cartesi",
+        version_args: &["--version"],
+        min_version: Some("0.14.0"),
+        max_version: Some("2.0.0"),
+    },
+    Tool {
+        name: "docker",
+        program: "docker",
+        version_args: &["--version"],
+        min_version: Some("20.10.0"),
+        max_version: None,
+    },
+    Tool {
+        name: "docker compose",
+        program: "docker",
+        version_args: &["compose", "version", "--short"],
+        min_version: Some("2.24.4"),
+        max_version: None,
+    },
+    Tool {
+        name: "git",
+        program: "git",
+        version_args: &["--version"],
+        min_version: Some("2.0.0"),
+        max_version: None,
+    },
+    Tool {
+        name: "curl",
+        program: "curl",
+        version_args: &["--version"],
+        min_version: Some("7.0.0"),
+        max_version: None,
+    },
+    Tool {
+        name: "w3",
+        program: "w3",
+        version_args: &["--version"],
+        min_version: Some("7.0.0"),
+        max_version: None,
+    },
+    // xxd reports the date of its release rather than a version
+    Tool {
+        name: "xxd",
+        program: "xxd",
+        version_args: &["-v"],
+        min_version: None,
+        max_version: None,
+    },
+];
+
+/// @notice Function to find a program in the directories of PATH
+/// @param program The name of the program to look for
+/// @return an Option containing the path to the first executable found
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| {
+            path.metadata().is_ok_and(|metadata| {
+                use std::os::unix::fs::PermissionsExt;
+                metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+            })
+        })
+}
+
+/// @notice Function to parse the first dotted version number out of a tool's version output
+/// @param output The output of the tool's version command
+/// @return an Option containing the major, minor and patch numbers, a missing patch number is read as 0
+fn parse_version(output: &str) -> Option<(u64, u64, u64)> {
+    let version = Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?").expect("Invalid version regex");
+    let captures = version.captures(output)?;
+    let number = |index: usize| {
+        captures
+            .get(index)
+            .map_or(Some(0), |value| value.as_str().parse().ok())
+    };
+
+    Some((number(1)?, number(2)?, number(3)?))
+}
+
+/// @notice Function to describe the supported versions of a tool
+/// @return the supported range, e.g. `>=0.2.0, <2.0.0`, or `any` for tools without a version
+fn supported_range(tool: &Tool) -> String {
+    match (tool.min_version, tool.max_version) {
+        (Some(min), Some(max)) => format!(">={}, <{}", min, max),
+        (Some(min), None) => format!(">={}", min),
+        (None, Some(max)) => format!("<{}", max),
+        (None, None) => String::from("any"),
+    }
+}
+
+/// @notice Function to check a tool is installed with a supported version
+/// @return the path, version and status of the tool, and whether the check passed
+fn check_tool(tool: &Tool) -> (String, String, String, bool) {
+    let Some(path) = find_in_path(tool.program) else {
+        return (
+            String::from("-"),
+            String::from("-"),
+            String::from("not installed"),
+            false,
+        );
+    };
+    let path = path.display().to_string();
+
+    let output = match run_captured(
+        Command::new(tool.program).args(tool.version_args),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(output) if output.status.success() => output,
+        Ok(_) => {
+            return (
+                path,
+                String::from("-"),
+                String::from("version command failed"),
+                false,
+            )
+        }
+        Err(e) => return (path, String::from("-"), e.to_string(), false),
+    };
+    if tool.min_version.is_none() && tool.max_version.is_none() {
+        return (path, String::from("-"), String::from("ok"), true);
+    }
+
+    let output = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let Some(version) = parse_version(&output) else {
+        return (
+            path,
+            String::from("-"),
+            String::from("unknown version"),
+            false,
+        );
+    };
+    let version_string = format!("{}.{}.{}", version.0, version.1, version.2);
+
+    let too_old = tool
+        .min_version
+        .and_then(parse_version)
+        .is_some_and(|min| version < min);
+    let too_new = tool
+        .max_version
+        .and_then(parse_version)
+        .is_some_and(|max| version >= max);
+    match (too_old, too_new) {
+        (true, _) => (path, version_string, String::from("too old"), false),
+        (_, true) => (path, version_string, String::from("too new"), false),
+        _ => (path, version_string, String::from("ok"), true),
+    }
+}
+
+/// @notice Function to get the free disk space of the filesystem holding a path
+/// @param path The path on the filesystem to check
+/// @return an Option containing the number of bytes available to unprivileged users
+fn free_disk_space(path: &Path) -> Option<u64> {
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// @notice Function to format a number of bytes in GiB
+fn format_gib(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / (1024 * 1024 * 1024) as f64)
+}
+
+/// @notice Entry point function to check the tools, Docker daemon, npm global bin path and free disk space the cli relies on
+pub fn doctor() {
+    // cartesi is looked up the same way the create and publish commands run it
+    let _ = add_npm_home_dir_to_path();
+
+    let rows: Vec<(&str, String, String, String, String, bool)> = TOOLS
+        .iter()
+        .map(|tool| {
+            let (path, version, status, ok) = check_tool(tool);
+            (tool.name, status, version, supported_range(tool), path, ok)
+        })
+        .collect();
+
+    // Calculate the width of the columns
+    let name_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+    let status_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+    let version_width = rows.iter().map(|row| row.2.len()).max().unwrap_or(0);
+    let range_width = rows.iter().map(|row| row.3.len()).max().unwrap_or(0);
+
+    println!("{}", "Tools".bold());
+    for (name, status, version, range, path, ok) in &rows {
+        let status = format!("{:<status_width$}", status, status_width = status_width);
+        println!(
+            "{} {:<name_width$}  {}  {:<version_width$}  {:<range_width$}  {}",
+            if *ok { "✅" } else { "❌" },
+            name,
+            if *ok { status.green() } else { status.red() },
+            version,
+            range,
+            path,
+            name_width = name_width,
+            version_width = version_width,
+            range_width = range_width,
+        );
+    }
+    println!();
+
+    println!("{}", "Environment".bold());
+    let mut problems = rows.iter().filter(|row| !row.5).count();
+
+    let daemon = match ping() {
+        Ok(_) => (true, String::from("reachable")),
+        Err(e) => (false, e.to_string()),
+    };
+
+    let home = env::var("HOME").unwrap_or_default();
+    let npm_bin = PathBuf::from(format!("{}/.npm/bin", home));
+    let npm_prefix = run_captured(Command::new("npm").args(["prefix", "-g"]), SHORT_TIMEOUT)
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    let npm = match (npm_bin.is_dir(), npm_prefix) {
+        (true, _) => (true, format!("{} exists", npm_bin.display())),
+        (false, Some(prefix)) if Path::new(&prefix) == npm_bin.parent().unwrap() => {
+            (true, format!("{} does not exist yet", npm_bin.display()))
+        }
+        (false, Some(prefix)) => (
+            find_in_path("cartesi").is_some(),
+            format!(
+                "{} does not exist, npm installs global packages in {}/bin",
+                npm_bin.display(),
+                prefix
+            ),
+        ),
+        (false, None) => (
+            find_in_path("cartesi").is_some(),
+            format!("{} does not exist, npm not found", npm_bin.display()),
+        ),
+    };
+
+    let current_dir = env::current_dir().expect("Failed to get current directory");
+    let disk = match free_disk_space(&current_dir) {
+        Some(free) if free >= MIN_FREE_DISK_SPACE => (
+            true,
+            format!("{} free in {}", format_gib(free), current_dir.display()),
+        ),
+        Some(free) => (
+            false,
+            format!(
+                "{} free in {}, at least {} recommended",
+                format_gib(free),
+                current_dir.display(),
+                format_gib(MIN_FREE_DISK_SPACE)
+            ),
+        ),
+        None => (false, String::from("unable to read free disk space")),
+    };
+
+    let data = vec![
+        ("Docker daemon", daemon),
+        ("npm global bin", npm),
+        ("Disk space", disk),
+    ];
+
+    // Calculate the width of the first column
+    let max_width = data.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, (ok, value)) in data {
+        if !ok {
+            problems += 1;
+        }
+        println!(
+            "{} {:<width$}  {}",
+            if ok { "✅" } else { "❌" },
+            name,
+            if ok { value.normal() } else { value.red() },
+            width = max_width
+        );
+    }
+    println!();
+
+    if problems == 0 {
+        println!("✅ {}", "Everything the CLI needs is set up.".green());
+    } else {
+        println!(
+            "{} {} {}",
+            problems.to_string().yellow(),
+            "problem(s) found, commands only need the tools they run,".yellow(),
+            "e.g. w3 is only needed to publish to testnet or mainnet.".yellow()
+        );
+    }
+}
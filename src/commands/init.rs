@@ -0,0 +1,246 @@
+use crate::commands::contracts::{
+    install_base_contract_version, installed_base_contract_version, run_in_project,
+};
+use crate::commands::create::BASE_CONTRACT_VERSION;
+use crate::commands::deploy_script::{
+    allow_lockfile_read, foundry_dir, generate_deploy_script, relative_lockfile_path,
+    DEFAULT_CONTRACT_NAME,
+};
+use crate::helpers::config::get_network_profile;
+use colored::Colorize;
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// @notice Function to find a contract of the foundry project inheriting the co-processor adapter
+/// @param work_dir The path to the foundry project for the dapp
+/// @return an Option containing the name of the first adapter contract found
+fn find_adapter_contract(work_dir: &Path) -> Option<String> {
+    let adapter = Regex::new(r"contract\s+(\w+)\s+is\s+[^{]*\bCoprocessorAdapter\b")
+        .expect("Invalid adapter regex");
+
+    let mut sources: Vec<_> = fs::read_dir(work_dir.join("src"))
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "sol"))
+        .collect();
+    sources.sort();
+
+    sources.iter().find_map(|path| {
+        let source = fs::read_to_string(path).ok()?;
+        adapter
+            .captures(&source)
+            .map(|captures| captures[1].to_string())
+    })
+}
+
+/// @notice Entry point function to add co-processor support to an existing foundry project or Cartesi program in the current directory
+/// @notice Only missing pieces are added, existing files are never overwritten
+/// @param network The network whose task issuer the deploy script uses
+/// @param contract_name The name of the adapter contract to add if the project has none
+/// @param base_contract_version The tag of the base contract to install if it is missing
+pub fn init(network: String, contract_name: Option<String>, base_contract_version: Option<String>) {
+    if get_network_profile(&network).is_none() {
+        eprintln!("❌ {} '{}'.", "Unknown network".red(), network.red());
+        return;
+    }
+
+    let project_dir = env::current_dir().expect("Failed to get current directory");
+    let work_dir = foundry_dir(&project_dir);
+    let has_foundry = work_dir.join("foundry.toml").exists();
+    let has_program =
+        project_dir.join(".cartesi").is_dir() || project_dir.join("Dockerfile").exists();
+    let has_base_contract = work_dir.join("lib/coprocessor-base-contract").is_dir();
+    let adapter = find_adapter_contract(&work_dir);
+    let has_deploy_script = work_dir.join("script/Deploy.s.sol").exists();
+
+    let found = |present: bool, detail: String| {
+        if present {
+            format!("found {}", detail)
+        } else {
+            String::from("missing")
+        }
+    };
+    let data = vec![
+        (
+            "Foundry project",
+            found(has_foundry, format!("({})", work_dir.display())),
+        ),
+        ("Cartesi program", found(has_program, String::new())),
+        (
+            "Base contract",
+            found(
+                has_base_contract,
+                installed_base_contract_version(&work_dir)
+                    .map(|version| format!("({})", version))
+                    .unwrap_or_default(),
+            ),
+        ),
+        (
+            "Adapter contract",
+            found(
+                adapter.is_some(),
+                format!("({})", adapter.clone().unwrap_or_default()),
+            ),
+        ),
+        ("Deploy script", found(has_deploy_script, String::new())),
+    ];
+
+    // Calculate the width of the first column
+    let max_width = data.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, value) in data {
+        println!("{:<width$}  {}", name, value.trim(), width = max_width);
+    }
+    println!();
+
+    let mut added = 0;
+    if !has_foundry {
+        let foundry_path = work_dir
+            .strip_prefix(&project_dir)
+            .unwrap_or(&work_dir)
+            .display()
+            .to_string();
+        if foundry_path != "contracts" {
+            println!(
+                "{} {}",
+                "contracts already holds a project of another toolchain, the foundry project is created in"
+                    .yellow(),
+                foundry_path.yellow()
+            );
+        }
+        if let Err(e) = run_in_project(
+            "forge",
+            &["init", &foundry_path, "--no-commit"],
+            &project_dir,
+        ) {
+            eprintln!("❌ {}", "Failed to initialize a foundry project.".red());
+            eprintln!("{} {}", "FORGE::ERROR::".red(), e.red());
+            return;
+        }
+        // The foundry.toml was just created, so the deploy script may be granted access to the lockfile
+        if !allow_lockfile_read(
+            &work_dir,
+            &relative_lockfile_path(&project_dir, &work_dir),
+            false,
+        ) {
+            return;
+        }
+        println!("✅ {}", "Successfully initialized foundry project.".green());
+        added += 1;
+    }
+
+    if !has_base_contract {
+        let version = base_contract_version.unwrap_or_else(|| BASE_CONTRACT_VERSION.to_string());
+        if !install_base_contract_version(&work_dir, &version) {
+            return;
+        }
+        println!(
+            "✅ {} {}",
+            "Successfully installed coprocessor-base-contract".green(),
+            version.green()
+        );
+        added += 1;
+    }
+
+    let contract_name = match adapter {
+        Some(adapter) => adapter,
+        None => {
+            let contract_name = contract_name.unwrap_or_else(|| DEFAULT_CONTRACT_NAME.to_string());
+            let contract_path = work_dir.join("src").join(format!("{}.sol", contract_name));
+            if contract_path.exists() {
+                println!(
+                    "{} {} {}",
+                    contract_path.display().to_string().yellow(),
+                    "already exists and does not inherit CoprocessorAdapter,".yellow(),
+                    "pass another --contract-name to add an adapter.".yellow()
+                );
+                return;
+            }
+
+            let contract_template = include_str!("../../contract-template/src/MyContract.sol")
+                .replace(
+                    &format!("contract {} ", DEFAULT_CONTRACT_NAME),
+                    &format!("contract {} ", contract_name),
+                );
+            let _ = fs::create_dir_all(work_dir.join("src"));
+            if let Err(e) = fs::write(&contract_path, contract_template) {
+                eprintln!("Error creating contract template: {}", e);
+                return;
+            }
+            println!(
+                "✅ {} {}",
+                "Successfully created adapter contract".green(),
+                contract_path.display().to_string().green()
+            );
+            added += 1;
+            contract_name
+        }
+    };
+
+    if has_deploy_script {
+        println!(
+            "{}",
+            "Kept the existing script/Deploy.s.sol, run the regen-deploy-script command to replace it."
+                .yellow()
+        );
+    } else if generate_deploy_script(&project_dir, &network, &contract_name, true) {
+        added += 1;
+    }
+
+    if !has_program {
+        println!(
+            "{}",
+            "No Cartesi program found, add a Dockerfile for your program before running the publish command."
+                .yellow()
+        );
+    }
+    if added == 0 {
+        println!(
+            "✅ {}",
+            "Co-processor support is already set up, nothing to add.".green()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_first_adapter_contract() {
+        let work_dir = env::temp_dir().join(format!("init-adapter-{}", std::process::id()));
+        let src = work_dir.join("src");
+        fs::create_dir_all(&src).unwrap();
+        assert_eq!(find_adapter_contract(&work_dir), None);
+
+        fs::write(src.join("Counter.sol"), "contract Counter {}\n").unwrap();
+        fs::write(
+            src.join("Notes.txt"),
+            "contract Ignored is CoprocessorAdapter {}\n",
+        )
+        .unwrap();
+        assert_eq!(find_adapter_contract(&work_dir), None);
+
+        fs::write(
+            src.join("Swap.sol"),
+            "contract Swap is Ownable,\n    CoprocessorAdapter {\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("Auction.sol"),
+            "contract Auction is CoprocessorAdapter {\n}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_adapter_contract(&work_dir),
+            Some(String::from("Auction"))
+        );
+
+        fs::remove_file(src.join("Auction.sol")).unwrap();
+        assert_eq!(find_adapter_contract(&work_dir), Some(String::from("Swap")));
+
+        fs::remove_dir_all(&work_dir).unwrap();
+    }
+}
@@ -0,0 +1,453 @@
+use crate::commands::deploy::{deploy_contract, find_deployment_record, update_deployment_record};
+use crate::helpers::artifacts::{compile_contracts, has_function};
+use crate::helpers::helpers::{confirm, find_machine_hash, get_spinner, resolve_deployment_target};
+use crate::helpers::process::{report_process_error, run_captured, LONG_TIMEOUT, SHORT_TIMEOUT};
+use chrono::Local;
+use colored::Colorize;
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+
+/// @notice Signature of the setter adapters can expose to update their machine hash in place
+const MACHINE_HASH_SETTER: &str = "setMachineHash(bytes32)";
+
+/// @notice Function to normalise a machine hash so hashes from different sources can be compared
+/// @param hash The machine hash, with or without a `0x` prefix
+/// @return the lowercase hash without a `0x` prefix
+fn normalise_hash(hash: &str) -> String {
+    hash.trim().trim_start_matches("0x").to_lowercase()
+}
+
+/// @notice Function to check whether a constructor argument looks like a bytes32 value
+/// @param arg The constructor argument
+/// @return true if the argument is a `0x` prefixed 32 byte hex string
+fn is_bytes32(arg: &str) -> bool {
+    arg.len() == 66 && arg.starts_with("0x") && arg[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// @notice Function to get the machine hash a deployment was made with from its record
+/// @notice Records without a `machine_hash` field fall back to the bytes32 constructor argument of the adapter
+/// @param record The deployment record
+/// @return an Option containing the machine hash
+fn recorded_machine_hash(record: &serde_json::Value) -> Option<String> {
+    if let Some(hash) = record["machine_hash"].as_str() {
+        return Some(normalise_hash(hash));
+    }
+
+    record["constructor_args"]
+        .as_array()?
+        .iter()
+        .filter_map(|arg| arg.as_str())
+        .find(|arg| is_bytes32(arg))
+        .map(normalise_hash)
+}
+
+/// @notice Function to get the machine hash an adapter was constructed with, which setter updates do not change
+/// @notice The hash replaced by the first setter update is used when there is one, else the recorded machine hash
+/// @param record The deployment record
+/// @return an Option containing the machine hash passed to the constructor
+fn constructor_machine_hash(record: &serde_json::Value) -> Option<String> {
+    record["machine_hash_updates"]
+        .as_array()
+        .and_then(|updates| updates.first())
+        .and_then(|update| update["from"].as_str())
+        .map(normalise_hash)
+        .or_else(|| recorded_machine_hash(record))
+}
+
+/// @notice Function to replace the machine hash in the constructor arguments of an adapter
+/// @notice A lone bytes32 argument is taken as the machine hash when none matches the constructor hash
+/// @param args The recorded constructor arguments
+/// @param constructor_hash The machine hash the adapter was constructed with, if known
+/// @param new_hash The machine hash to construct the new adapter with
+/// @return an Option containing the new constructor arguments, None if no argument holds the machine hash
+fn swap_machine_hash(
+    args: &[String],
+    constructor_hash: Option<&str>,
+    new_hash: &str,
+) -> Option<Vec<String>> {
+    let matches_hash =
+        |arg: &str| is_bytes32(arg) && constructor_hash == Some(normalise_hash(arg).as_str());
+    let position = match args.iter().position(|arg| matches_hash(arg)) {
+        Some(position) => position,
+        None => {
+            let mut bytes32 = args.iter().enumerate().filter(|(_, arg)| is_bytes32(arg));
+            match (bytes32.next(), bytes32.next()) {
+                (Some((position, _)), None) => position,
+                _ => return None,
+            }
+        }
+    };
+
+    let mut args = args.to_vec();
+    args[position] = format!("0x{}", new_hash);
+    Some(args)
+}
+
+/// @notice Function to read the machine hash an adapter was deployed with from the chain, using cast
+/// @param rpc The rpc of the chain the adapter is deployed to
+/// @param address The address of the adapter
+/// @return an Option containing the machine hash, None if the adapter could not be queried
+fn read_onchain_machine_hash(rpc: &str, address: &str) -> Option<String> {
+    let cast_status = match run_captured(
+        Command::new("cast")
+            .arg("call")
+            .arg(address)
+            .arg("machineHash()(bytes32)")
+            .arg("--rpc-url")
+            .arg(rpc),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(cast_status) => cast_status,
+        Err(e) => {
+            report_process_error("CAST", &e);
+            return None;
+        }
+    };
+
+    if cast_status.status.success() {
+        let hash = String::from_utf8_lossy(&cast_status.stdout);
+        Some(normalise_hash(&hash))
+    } else {
+        None
+    }
+}
+
+/// @notice Function to update the machine hash of an adapter in place by calling its setter
+/// @param private_key The private key of the account allowed to call the setter
+/// @param rpc The rpc of the chain the adapter is deployed to
+/// @param address The address of the adapter
+/// @param machine_hash The new machine hash
+/// @return an Option containing the transaction hash of the update
+fn call_machine_hash_setter(
+    private_key: &str,
+    rpc: &str,
+    address: &str,
+    machine_hash: &str,
+) -> Option<String> {
+    let spinner = get_spinner();
+    spinner.set_message("Updating machine hash...");
+
+    let cast_status = match run_captured(
+        Command::new("cast")
+            .arg("send")
+            .arg(address)
+            .arg(MACHINE_HASH_SETTER)
+            .arg(format!("0x{}", machine_hash))
+            .arg("--rpc-url")
+            .arg(rpc)
+            .arg("--private-key")
+            .arg(private_key)
+            .arg("--json"),
+        LONG_TIMEOUT,
+    ) {
+        Ok(cast_status) => cast_status,
+        Err(e) => {
+            spinner.finish_and_clear();
+            report_process_error("CAST", &e);
+            return None;
+        }
+    };
+
+    spinner.finish_and_clear();
+
+    if cast_status.status.success() {
+        let receipt: serde_json::Value =
+            serde_json::from_slice(&cast_status.stdout).unwrap_or_default();
+        Some(
+            receipt["transactionHash"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        )
+    } else {
+        eprintln!("❌ Failed to update the machine hash.");
+        eprintln!("Error: {}", String::from_utf8_lossy(&cast_status.stderr));
+        None
+    }
+}
+
+/// @notice Function to get the file name of a deployment record, used to link records to each other
+/// @param path The path to the deployment record
+/// @return the file name of the record
+fn record_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// @notice Function to redeploy an adapter with a new machine hash and link the old and new deployment records
+/// @param record_path The path to the deployment record of the stale adapter
+/// @param record The deployment record of the stale adapter
+/// @param private_key The private of the account to deploy with
+/// @param rpc The rpc of the chain to deploy to
+/// @param network The network to deploy to
+/// @param new_hash The current machine hash
+fn redeploy_adapter(
+    record_path: &Path,
+    record: &serde_json::Value,
+    private_key: String,
+    rpc: String,
+    network: String,
+    new_hash: &str,
+) {
+    let contract_name = record["contract_name"].as_str().unwrap_or_default();
+    let recorded_args: Vec<String> = record["constructor_args"]
+        .as_array()
+        .map(|args| {
+            args.iter()
+                .filter_map(|arg| arg.as_str())
+                .map(|arg| arg.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let Some(args) = swap_machine_hash(
+        &recorded_args,
+        constructor_machine_hash(record).as_deref(),
+        new_hash,
+    ) else {
+        eprintln!(
+            "❌ {}",
+            "Could not find the machine hash in the recorded constructor arguments, please redeploy manually."
+                .red()
+        );
+        return;
+    };
+
+    let Some(new_record_path) = deploy_contract(
+        private_key,
+        rpc,
+        Some(args),
+        contract_name.to_string(),
+        network,
+    ) else {
+        return;
+    };
+
+    let linked = update_deployment_record(&new_record_path, "machine_hash", json!(new_hash))
+        .and_then(|_| {
+            update_deployment_record(
+                &new_record_path,
+                "previous_deployment",
+                json!(record_name(record_path)),
+            )
+        })
+        .and_then(|_| {
+            update_deployment_record(
+                record_path,
+                "superseded_by",
+                json!(record_name(&new_record_path)),
+            )
+        });
+
+    match linked {
+        Ok(_) => println!(
+            "✅ {} {}",
+            "Adapter redeployed with machine hash".green(),
+            new_hash.green()
+        ),
+        Err(err) => eprintln!("❌ Error saving deployment lineage: {}", err),
+    }
+}
+
+/// @notice Entry point function to compare the machine hash of a deployed adapter with the current program build,
+/// @notice then offer to update the adapter in place or redeploy it when the hashes differ
+/// @param network The network the adapter is deployed to
+/// @param address The address of the adapter, if None the latest deployment on the network is checked
+/// @param private_key Private key used to update or redeploy the adapter. Required for "mainnet" and "testnet".
+/// @param rpc RPC endpoint the adapter is read from, updated or redeployed through. Required for "mainnet" and "testnet".
+pub fn check_machine_hash_drift(
+    network: String,
+    address: Option<String>,
+    private_key: Option<String>,
+    rpc: Option<String>,
+) {
+    let Some((record_path, record)) = find_deployment_record(&network, address.as_deref()) else {
+        println!(
+            "{} {}",
+            "No deployment record found in deployment_history for".red(),
+            address.unwrap_or(network).red()
+        );
+        return;
+    };
+
+    let Some(current_hash) = find_machine_hash() else {
+        println!(
+            "{}",
+            "No machine hash found, please build your Cartesi program first.".red()
+        );
+        return;
+    };
+
+    let Some((private_key, rpc)) = resolve_deployment_target(&network, private_key, rpc) else {
+        return;
+    };
+
+    let deployed_to = record["deployed_to"].as_str().unwrap_or_default();
+    let (deployed_hash, source) = match read_onchain_machine_hash(&rpc, deployed_to) {
+        Some(hash) => (hash, "on-chain"),
+        None => match recorded_machine_hash(&record) {
+            Some(hash) => (hash, "deployment record"),
+            None => {
+                println!(
+                    "{}",
+                    "Could not determine the machine hash the contract was deployed with.".red()
+                );
+                return;
+            }
+        },
+    };
+
+    let data = vec![
+        (
+            "Contract",
+            record["contract_name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        ),
+        ("Address", deployed_to.to_string()),
+        (
+            "Deployed machine hash",
+            format!("0x{} ({})", deployed_hash, source),
+        ),
+        ("Current machine hash", format!("0x{}", current_hash)),
+    ];
+
+    // Calculate the width of the first column
+    let max_width = data.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, value) in data {
+        println!("{:<width$}  {}", name, value, width = max_width);
+    }
+
+    if deployed_hash == current_hash {
+        println!("✅ {}", "Adapter machine hash is up to date.".green());
+        return;
+    }
+
+    println!(
+        "⚠️  {}",
+        "Machine hash drift detected, the adapter points at an outdated program.".yellow()
+    );
+
+    let contract_name = record["contract_name"].as_str().unwrap_or_default();
+    if compile_contracts()
+        && has_function(contract_name, MACHINE_HASH_SETTER)
+        && confirm(&format!("Call {} on {}?", MACHINE_HASH_SETTER, deployed_to))
+    {
+        if let Some(transaction_hash) =
+            call_machine_hash_setter(&private_key, &rpc, deployed_to, &current_hash)
+        {
+            let mut updates = record["machine_hash_updates"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            updates.push(json!({
+                "from": deployed_hash,
+                "to": current_hash,
+                "transaction_hash": transaction_hash,
+                "updated_at": Local::now().to_rfc3339(),
+            }));
+
+            let saved = update_deployment_record(&record_path, "machine_hash", json!(current_hash))
+                .and_then(|_| {
+                    update_deployment_record(&record_path, "machine_hash_updates", json!(updates))
+                });
+            match saved {
+                Ok(_) => println!("✅ {}", "Machine hash updated successfully.".green()),
+                Err(err) => eprintln!("❌ Error saving deployment info: {}", err),
+            }
+        }
+        return;
+    }
+
+    // Adapters without a setter, or whose setter was declined, can only be redeployed
+    if record["contract_name"].as_str().is_none() {
+        println!(
+            "{}",
+            "This deployment record has no contract name, please redeploy manually.".red()
+        );
+    } else if confirm(&format!(
+        "Redeploy {} with the new machine hash?",
+        contract_name
+    )) {
+        redeploy_adapter(
+            &record_path,
+            &record,
+            private_key,
+            rpc,
+            network,
+            &current_hash,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const SETTER_HASH: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+    const NEW_HASH: &str = "3333333333333333333333333333333333333333333333333333333333333333";
+    const TASK_ISSUER: &str = "0x95401dc811bb5740090279Ba06cfA8fcF6113778";
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_bytes32_arguments() {
+        assert!(is_bytes32(OLD_HASH));
+        assert!(!is_bytes32(&OLD_HASH[2..]));
+        assert!(!is_bytes32(TASK_ISSUER));
+        assert!(!is_bytes32(&format!("0x{}", "g".repeat(64))));
+        assert!(!is_bytes32(&format!("0x{}", "é".repeat(32))));
+    }
+
+    #[test]
+    fn reads_the_recorded_machine_hash() {
+        let record = json!({ "constructor_args": [TASK_ISSUER, OLD_HASH] });
+        assert_eq!(recorded_machine_hash(&record).unwrap(), &OLD_HASH[2..]);
+
+        let record = json!({ "machine_hash": "0xABCD", "constructor_args": [OLD_HASH] });
+        assert_eq!(recorded_machine_hash(&record).unwrap(), "abcd");
+        assert!(recorded_machine_hash(&json!({ "constructor_args": [TASK_ISSUER] })).is_none());
+    }
+
+    #[test]
+    fn constructor_hash_survives_setter_updates() {
+        let record = json!({
+            "machine_hash": SETTER_HASH,
+            "constructor_args": [TASK_ISSUER, OLD_HASH],
+            "machine_hash_updates": [{ "from": &OLD_HASH[2..], "to": SETTER_HASH }],
+        });
+        assert_eq!(constructor_machine_hash(&record).unwrap(), &OLD_HASH[2..]);
+    }
+
+    #[test]
+    fn swaps_the_constructor_machine_hash() {
+        let swapped = swap_machine_hash(
+            &args(&[TASK_ISSUER, OLD_HASH]),
+            Some(&OLD_HASH[2..]),
+            NEW_HASH,
+        )
+        .unwrap();
+        assert_eq!(swapped, args(&[TASK_ISSUER, &format!("0x{}", NEW_HASH)]));
+
+        // A lone bytes32 argument is the machine hash even if the known hash does not match
+        let swapped =
+            swap_machine_hash(&args(&[TASK_ISSUER, OLD_HASH]), Some(SETTER_HASH), NEW_HASH)
+                .unwrap();
+        assert_eq!(swapped[1], format!("0x{}", NEW_HASH));
+
+        // Several bytes32 arguments are only swapped on a match
+        let salt = format!("0x{}", "4".repeat(64));
+        let swapped =
+            swap_machine_hash(&args(&[&salt, OLD_HASH]), Some(&OLD_HASH[2..]), NEW_HASH).unwrap();
+        assert_eq!(swapped, args(&[&salt, &format!("0x{}", NEW_HASH)]));
+        assert!(swap_machine_hash(&args(&[&salt, OLD_HASH]), None, NEW_HASH).is_none());
+        assert!(swap_machine_hash(&args(&[TASK_ISSUER]), None, NEW_HASH).is_none());
+    }
+}
@@ -0,0 +1,448 @@
+use crate::commands::deploy::{deploy_contract, load_deployment_records, update_deployment_record};
+use crate::commands::verify::verify_deployment;
+use crate::helpers::rpc::has_code;
+use colored::Colorize;
+use regex::Regex;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// @notice A contract entry of a deployment manifest, after applying the overrides of the selected network
+#[derive(Debug, Clone)]
+struct ManifestContract {
+    name: String,
+    contract: String,
+    args: Vec<String>,
+}
+
+/// @notice Function to get the pattern matching references to earlier deployments, e.g. `${Registry.address}`
+fn reference_pattern() -> Regex {
+    Regex::new(r"\$\{([A-Za-z0-9_]+)\.([A-Za-z_]+)\}").expect("Invalid reference pattern")
+}
+
+/// @notice Function to read a list of strings from a manifest table
+/// @param table The manifest table to read from
+/// @param key The key of the list
+/// @return an Option containing the list, numbers and booleans are converted to strings
+fn get_string_list(table: &Table, key: &str) -> Option<Vec<String>> {
+    table.get(key)?.as_array().map(|values| {
+        values
+            .iter()
+            .map(|value| match value {
+                Value::String(value) => value.clone(),
+                other => other.to_string(),
+            })
+            .collect()
+    })
+}
+
+/// @notice Function to parse the contracts of a deployment manifest
+/// @notice Entries in `[networks.<network>.<name>]` override the `contract` and `args` of the contract with that name
+/// @param path The path to the manifest file
+/// @param network The network being deployed to
+/// @return A result containing the contracts in the order they are listed in the manifest
+fn parse_manifest(path: &Path, network: &str) -> Result<Vec<ManifestContract>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read manifest '{}': {}", path.display(), e))?;
+    let manifest: Table = content
+        .parse()
+        .map_err(|e| format!("Invalid manifest '{}': {}", path.display(), e))?;
+
+    let overrides = manifest
+        .get("networks")
+        .and_then(Value::as_table)
+        .and_then(|networks| networks.get(&network.to_lowercase()))
+        .and_then(Value::as_table)
+        .cloned()
+        .unwrap_or_default();
+
+    let entries = manifest
+        .get("contracts")
+        .and_then(Value::as_array)
+        .ok_or("The manifest has no [[contracts]] entries")?;
+
+    let mut contracts: Vec<ManifestContract> = Vec::new();
+    for entry in entries {
+        let entry = entry
+            .as_table()
+            .ok_or("Every [[contracts]] entry must be a table")?;
+        let name = entry
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or("Every [[contracts]] entry needs a name")?
+            .to_string();
+
+        if contracts.iter().any(|contract| contract.name == name) {
+            return Err(format!("Contract '{}' is listed more than once", name));
+        }
+
+        let network_override = overrides
+            .get(&name)
+            .and_then(Value::as_table)
+            .cloned()
+            .unwrap_or_default();
+        let get_value = |key: &str| {
+            network_override
+                .get(key)
+                .or_else(|| entry.get(key))
+                .and_then(Value::as_str)
+                .map(|value| value.to_string())
+        };
+
+        contracts.push(ManifestContract {
+            contract: get_value("contract").unwrap_or_else(|| name.clone()),
+            args: get_string_list(&network_override, "args")
+                .or_else(|| get_string_list(entry, "args"))
+                .unwrap_or_default(),
+            name,
+        });
+    }
+
+    Ok(contracts)
+}
+
+/// @notice Function to order the contracts of a manifest so every contract is deployed after the contracts it references
+/// @param contracts The contracts in manifest order
+/// @return A result containing the contracts in deployment order
+fn order_contracts(contracts: Vec<ManifestContract>) -> Result<Vec<ManifestContract>, String> {
+    let pattern = reference_pattern();
+    let mut pending: Vec<(ManifestContract, Vec<String>)> = Vec::new();
+
+    for contract in contracts.iter() {
+        let mut dependencies: Vec<String> = Vec::new();
+        for arg in contract.args.iter() {
+            for reference in pattern.captures_iter(arg) {
+                let dependency = reference[1].to_string();
+                if !contracts.iter().any(|other| other.name == dependency) {
+                    return Err(format!(
+                        "Contract '{}' references unknown contract '{}'",
+                        contract.name, dependency
+                    ));
+                }
+                if dependency == contract.name {
+                    return Err(format!("Contract '{}' references itself", contract.name));
+                }
+                dependencies.push(dependency);
+            }
+        }
+        pending.push((contract.clone(), dependencies));
+    }
+
+    let mut ordered: Vec<ManifestContract> = Vec::new();
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|(_, dependencies)| {
+            dependencies
+                .iter()
+                .all(|dependency| ordered.iter().any(|done| &done.name == dependency))
+        });
+
+        match ready {
+            Some(index) => ordered.push(pending.remove(index).0),
+            None => {
+                let names: Vec<String> = pending.into_iter().map(|(c, _)| c.name).collect();
+                return Err(format!(
+                    "Circular references between contracts: {}",
+                    names.join(", ")
+                ));
+            }
+        }
+    }
+
+    Ok(ordered)
+}
+
+/// @notice Function to replace references to earlier deployments in constructor arguments
+/// @param args The constructor arguments of a contract
+/// @param outputs The deployment records of the contracts deployed so far, by manifest name
+/// @return A result containing the resolved constructor arguments
+fn resolve_args(
+    args: &[String],
+    outputs: &HashMap<String, serde_json::Value>,
+) -> Result<Vec<String>, String> {
+    let pattern = reference_pattern();
+
+    args.iter()
+        .map(|arg| {
+            let mut error: Option<String> = None;
+            let resolved = pattern.replace_all(arg, |reference: &regex::Captures| {
+                let field = match &reference[2] {
+                    "address" => "deployed_to",
+                    field => field,
+                };
+                match outputs[&reference[1]][field].as_str() {
+                    Some(value) if !value.is_empty() => value.to_string(),
+                    _ => {
+                        error = Some(format!("Could not resolve {}", &reference[0]));
+                        String::new()
+                    }
+                }
+            });
+            match error {
+                Some(error) => Err(error),
+                None => Ok(resolved.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// @notice Function to find the deployment record of a manifest contract deployed earlier
+/// @notice Records match on network, contract and constructor arguments, so contracts deployed with the deploy command are found too,
+/// @notice records labelled by another manifest entry are left to that entry
+/// @param records The deployment records, oldest first
+/// @param contract The manifest contract to look for
+/// @param args The resolved constructor arguments of the contract
+/// @param network The network being deployed to
+/// @return an Option containing the most recent matching record
+fn find_existing_deployment<'a>(
+    records: &'a [(PathBuf, serde_json::Value)],
+    contract: &ManifestContract,
+    args: &[String],
+    network: &str,
+) -> Option<&'a serde_json::Value> {
+    records
+        .iter()
+        .rev()
+        .map(|(_, record)| record)
+        .find(|record| {
+            let label_matches = match record["label"].as_str() {
+                Some(label) => label == contract.name,
+                None => true,
+            };
+            label_matches
+                && record["network"] == json!(network.to_lowercase())
+                && record["contract_name"] == json!(contract.contract)
+                && record["constructor_args"] == json!(args)
+        })
+}
+
+/// @notice Entry point function to deploy every contract of a manifest in dependency order
+/// @notice Contracts already deployed to the network with the same constructor arguments, and still having code there, are skipped and their recorded outputs reused
+/// @param manifest_path The path to the manifest file
+/// @param private_key The private of the account to deploy with
+/// @param rpc The rpc of the chain to deploy to
+/// @param network The network to deploy to
+/// @param verify Whether to verify each newly deployed contract on the network's block explorer
+pub fn deploy_manifest(
+    manifest_path: String,
+    private_key: String,
+    rpc: String,
+    network: String,
+    verify: bool,
+) {
+    let contracts =
+        match parse_manifest(Path::new(&manifest_path), &network).and_then(order_contracts) {
+            Ok(contracts) => contracts,
+            Err(e) => {
+                eprintln!("❌ {} {}", "MANIFEST::ERROR::".red(), e.red());
+                return;
+            }
+        };
+
+    let records = load_deployment_records();
+    let mut outputs: HashMap<String, serde_json::Value> = HashMap::new();
+
+    for contract in contracts.iter() {
+        let args = match resolve_args(&contract.args, &outputs) {
+            Ok(args) => args,
+            Err(e) => {
+                eprintln!("❌ {} {}", "MANIFEST::ERROR::".red(), e.red());
+                return;
+            }
+        };
+
+        if let Some(record) = find_existing_deployment(&records, contract, &args, &network) {
+            let address = record["deployed_to"].as_str().unwrap_or_default();
+            match has_code(&rpc, address) {
+                Ok(true) => {
+                    println!(
+                        "⏭️  Skipping {}, already deployed to {}",
+                        contract.name, address
+                    );
+                    outputs.insert(contract.name.clone(), record.clone());
+                    continue;
+                }
+                Ok(false) => println!(
+                    "{} {} {}",
+                    "No code at the recorded address of".yellow(),
+                    contract.name.yellow(),
+                    "redeploying it.".yellow()
+                ),
+                Err(e) => {
+                    eprintln!("❌ {} {}", "RPC::ERROR::".red(), e.red());
+                    return;
+                }
+            }
+        }
+
+        println!("Deploying {} ({})...", contract.name, contract.contract);
+        let Some(record_path) = deploy_contract(
+            private_key.clone(),
+            rpc.clone(),
+            (!args.is_empty()).then_some(args),
+            contract.contract.clone(),
+            network.clone(),
+        ) else {
+            eprintln!(
+                "❌ {} {}",
+                "Stopping manifest deployment at".red(),
+                contract.name.red()
+            );
+            return;
+        };
+
+        if let Err(err) = update_deployment_record(&record_path, "label", json!(contract.name)) {
+            eprintln!("❌ Error saving deployment info: {}", err);
+        }
+        let record: serde_json::Value = fs::read_to_string(&record_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        outputs.insert(contract.name.clone(), record);
+
+        if verify {
+            verify_deployment(&record_path, &network);
+        }
+    }
+
+    println!("✅ {}", "All manifest contracts deployed.".green());
+
+    // Calculate the width of the first column
+    let max_width = contracts.iter().map(|c| c.name.len()).max().unwrap_or(0);
+
+    for contract in contracts {
+        println!(
+            "{:<width$}  {}",
+            contract.name,
+            outputs[&contract.name]["deployed_to"]
+                .as_str()
+                .unwrap_or_default(),
+            width = max_width
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(name: &str, args: &[&str]) -> ManifestContract {
+        ManifestContract {
+            name: name.to_string(),
+            contract: name.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    fn names(contracts: &[ManifestContract]) -> Vec<&str> {
+        contracts.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn orders_contracts_after_their_references() {
+        let ordered = order_contracts(vec![
+            contract("Adapter", &["${Registry.address}", "${Token.address}"]),
+            contract("Registry", &["${Token.address}"]),
+            contract("Token", &["1000"]),
+        ])
+        .unwrap();
+
+        assert_eq!(names(&ordered), ["Token", "Registry", "Adapter"]);
+    }
+
+    #[test]
+    fn keeps_manifest_order_without_references() {
+        let ordered = order_contracts(vec![contract("B", &[]), contract("A", &["1"])]).unwrap();
+
+        assert_eq!(names(&ordered), ["B", "A"]);
+    }
+
+    #[test]
+    fn rejects_unknown_self_and_circular_references() {
+        assert_eq!(
+            order_contracts(vec![contract("A", &["${Missing.address}"])]).unwrap_err(),
+            "Contract 'A' references unknown contract 'Missing'"
+        );
+        assert_eq!(
+            order_contracts(vec![contract("A", &["${A.address}"])]).unwrap_err(),
+            "Contract 'A' references itself"
+        );
+        assert_eq!(
+            order_contracts(vec![
+                contract("A", &["${B.address}"]),
+                contract("B", &["${A.address}"]),
+            ])
+            .unwrap_err(),
+            "Circular references between contracts: A, B"
+        );
+    }
+
+    #[test]
+    fn resolves_references_to_earlier_deployments() {
+        let outputs = HashMap::from([(
+            String::from("Registry"),
+            json!({"deployed_to": "0x01", "transaction_hash": "0xaa"}),
+        )]);
+
+        let args = vec![
+            String::from("${Registry.address}"),
+            String::from("prefix-${Registry.transaction_hash}"),
+            String::from("42"),
+        ];
+        assert_eq!(
+            resolve_args(&args, &outputs).unwrap(),
+            ["0x01", "prefix-0xaa", "42"]
+        );
+    }
+
+    #[test]
+    fn fails_on_unresolvable_references() {
+        let outputs = HashMap::from([(String::from("Registry"), json!({"deployed_to": ""}))]);
+
+        assert_eq!(
+            resolve_args(&[String::from("${Registry.address}")], &outputs).unwrap_err(),
+            "Could not resolve ${Registry.address}"
+        );
+        assert_eq!(
+            resolve_args(&[String::from("${Registry.owner}")], &outputs).unwrap_err(),
+            "Could not resolve ${Registry.owner}"
+        );
+    }
+
+    #[test]
+    fn finds_deployments_by_contract_network_and_args() {
+        let record = |value: serde_json::Value| (PathBuf::from("deployment.json"), value);
+        let records = vec![
+            // Deployed with the deploy command, so without a label
+            record(json!({
+                "deployed_to": "0x01",
+                "contract_name": "Token",
+                "network": "devnet",
+                "constructor_args": ["1000"],
+            })),
+            record(json!({
+                "deployed_to": "0x02",
+                "contract_name": "Token",
+                "network": "devnet",
+                "constructor_args": ["1000"],
+                "label": "OtherToken",
+            })),
+        ];
+        let token = contract("Token", &["1000"]);
+
+        let found = find_existing_deployment(&records, &token, &token.args, "Devnet");
+        assert_eq!(found.unwrap()["deployed_to"], "0x01");
+        assert!(find_existing_deployment(&records, &token, &token.args, "testnet").is_none());
+        assert!(
+            find_existing_deployment(&records, &token, &[String::from("5")], "devnet").is_none()
+        );
+
+        let other = ManifestContract {
+            name: String::from("OtherToken"),
+            ..token.clone()
+        };
+        let found = find_existing_deployment(&records, &other, &other.args, "devnet");
+        assert_eq!(found.unwrap()["deployed_to"], "0x02");
+    }
+}
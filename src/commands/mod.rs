@@ -1,4 +1,16 @@
+pub mod accounts;
+pub mod chain;
+pub mod codegen;
+pub mod completions;
+pub mod contracts;
 pub mod create;
 pub mod deploy;
+pub mod deploy_script;
 pub mod devnet;
+pub mod doctor;
+pub mod init;
+pub mod machine_hash;
+pub mod manifest;
 pub mod publish;
+pub mod snapshot;
+pub mod verify;
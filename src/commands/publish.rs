@@ -1,5 +1,12 @@
+use crate::commands::devnet::active_devnet_config;
+use crate::helpers::config::get_process_timeout;
+use crate::helpers::docker::run_container;
 use crate::helpers::helpers::{
-    add_npm_home_dir_to_path, check_available_space, check_if_logged_in, display_machine_hash, get_machine_hash, get_spinner, read_file, UploadResponse
+    add_npm_home_dir_to_path, check_available_space, check_if_logged_in, display_machine_hash,
+    get_machine_hash, get_spinner, read_file, UploadResponse,
+};
+use crate::helpers::process::{
+    report_process_error, run_captured, run_process, run_streamed, LONG_TIMEOUT, SHORT_TIMEOUT,
 };
 use colored::Colorize;
 use indicatif::ProgressBar;
@@ -8,117 +15,70 @@ use reqwest::blocking::{multipart, Client};
 use reqwest::StatusCode;
 use std::env;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
-use std::process::{Command, Stdio};
-use std::time::Duration;
-use std::{thread, time};
+use std::io::Read;
+use std::process::Command;
+use std::time::{self, Duration};
+
+/// @notice Time given to the user to verify their email or select a payment plan on Web3.Storage
+const WEB3_STORAGE_WAIT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// @notice Default time the carize container may run, raised with `[timeouts] carize`
+const CARIZE_TIMEOUT: Duration = Duration::from_secs(300);
 
 /// @notice Function to set the space where uploaded car files will be saved to
 /// @param space_name The name of the space of choice
 fn set_active_space(space_name: String) {
-    let mut child = Command::new("w3")
-        .arg("space")
-        .arg("use")
-        .arg(space_name.clone())
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'w3 space creation'");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    // Collect lines from stdout first
-    let lines: Vec<Result<String, std::io::Error>> = stdout.lines().collect();
-
-    // Process collected lines to extract available spaces
-    for line in lines {
-        if line.is_ok() {
+    let w3_status = run_captured(
+        Command::new("w3")
+            .arg("space")
+            .arg("use")
+            .arg(space_name.clone()),
+        SHORT_TIMEOUT,
+    );
+
+    match w3_status {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
             println!("Switched to space: {}", space_name);
-            println!("Space ID: {}", line.unwrap());
-            return;
-        } else {
+            println!("Space ID: {}", stdout.lines().next().unwrap_or_default());
+        }
+        Ok(output) => {
             eprintln!("Failed to switch to space: {}", space_name);
-            return;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            println!("{} {}", "WEB3STORAGE::".red(), stderr.red());
         }
+        Err(e) => report_process_error("W3STORAGE", &e),
     }
-
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            match line {
-                Ok(output) => {
-                    println!("{} {}", "WEB3STORAGE::".red(), output.red());
-                }
-                Err(e) => eprintln!("Error reading stdout: {}", e),
-            }
-        }
-    });
 }
 
 /// @notice Function to create a new space for car files upload
 /// @param space The name of the space of choice
 fn create_space(space: String) {
     println!("Creating a new storage space: {}", space);
-    let mut child = Command::new("w3")
-        .arg("space")
-        .arg("create")
-        .arg(&space.clone())
-        .arg("--no-recovery")
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'w3 space create'");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    let start = time::Instant::now();
-
-    // Handle initial output and extract keyphrase
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            if let Ok(line) = line {
+    let w3_status = run_process(
+        Command::new("w3")
+            .arg("space")
+            .arg("create")
+            .arg(&space)
+            .arg("--no-recovery"),
+        WEB3_STORAGE_WAIT_TIMEOUT,
+        |is_stderr, line| {
+            if !is_stderr {
                 println!("{} {}", "W3STORAGE::OUTPUT:: ".green(), line.green());
+                return;
             }
-        }
-        return;
-    });
-
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            if let Ok(line) = line {
-                eprintln!("{} {}", "W3STORAGE::NOTE::".yellow(), line.yellow());
-                if line.contains("- Waiting for payment plan to be selected") {
-                    println!("{}", "W3STORAGE::INSTRUCTION:: Login to your W3 storage dashboard and complete your payment plan selection".yellow());
-                    break;
-                }
-            } else if let Err(e) = line {
-                eprintln!("{} {}", "W3STORAGE::ERROR::".red(), e);
-            }
-        }
-    });
-
-    // Wait for email verification or timeout
-    while start.elapsed().as_secs() < 300 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                println!("✅ Successfully created your space.");
-                break;
-            } else {
-                eprintln!("space creation process timed out. Please select your payment plan within the specified timeout.");
-                break;
+            eprintln!("{} {}", "W3STORAGE::NOTE::".yellow(), line.yellow());
+            if line.contains("- Waiting for payment plan to be selected") {
+                println!("{}", "W3STORAGE::INSTRUCTION:: Login to your W3 storage dashboard and complete your payment plan selection".yellow());
             }
-        }
+        },
+    );
 
-        // Poll every 2 seconds
-        thread::sleep(time::Duration::from_secs(2));
+    match w3_status {
+        Ok(status) if status.success() => println!("✅ Successfully created your space."),
+        Ok(_) => eprintln!("space creation process failed."),
+        Err(e) => report_process_error("W3STORAGE", &e),
     }
-
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the space creation process.");
 }
 
 /// @notice Function to upload a car file to the active space
@@ -129,56 +89,33 @@ fn upload_car_file(file_path: String) -> bool {
     let spinner = get_spinner();
     spinner.set_message("Uploading CAR file...");
 
-    let mut child = Command::new("w3")
-        .arg("up")
-        .arg("--car")
-        .arg(file_path.clone())
-        .stdin(Stdio::null())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'w3 up'");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            match line {
-                Ok(output) => {
-                    println!("{} {}", "W3STORAGE::".green(), output.green());
-                }
-                Err(e) => eprintln!("Error reading stdout: {}", e),
-            }
-        }
-    });
+    let w3_status = run_streamed(
+        Command::new("w3")
+            .arg("up")
+            .arg("--car")
+            .arg(file_path.clone()),
+        "W3STORAGE",
+        LONG_TIMEOUT,
+    );
 
-    // Wait for email verification or timeout
-    let start = time::Instant::now();
-    while start.elapsed().as_secs() < 30000 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                spinner.finish_and_clear();
-                println!(
-                    "✅ {}",
-                    "Successfully uploaded file to Web3.Storage.".green()
-                );
-                return true;
-            } else {
-                spinner.finish_and_clear();
-                eprintln!("{}", "upload process failed.".red());
-                return false;
-            }
+    spinner.finish_and_clear();
+    match w3_status {
+        Ok(output) if output.status.success() => {
+            println!(
+                "✅ {}",
+                "Successfully uploaded file to Web3.Storage.".green()
+            );
+            true
+        }
+        Ok(_) => {
+            eprintln!("{}", "upload process failed.".red());
+            false
+        }
+        Err(e) => {
+            report_process_error("W3STORAGE", &e);
+            false
         }
-
-        // Poll every 5 seconds
-        thread::sleep(time::Duration::from_secs(5));
     }
-
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the login process.");
-    return false;
 }
 
 /// @notice Function to build a Cartesi project before the registration process
@@ -189,15 +126,14 @@ fn build_program() -> bool {
     spinner.set_message("Building Cartesi Program...");
     add_npm_home_dir_to_path().unwrap();
 
-    let child = Command::new("cartesi")
-        .arg("build")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'cartesi build' command")
-        .wait_with_output()
-        .expect("Failed to wait for forge command to finish");
+    let child = match run_captured(Command::new("cartesi").arg("build"), LONG_TIMEOUT) {
+        Ok(child) => child,
+        Err(e) => {
+            spinner.finish_and_clear();
+            report_process_error("CARTESI", &e);
+            return false;
+        }
+    };
 
     let have_suceess = child.status.success();
 
@@ -222,80 +158,50 @@ fn run_carize_container() -> bool {
     let current_dir = env::current_dir().expect("Failed to get current directory");
 
     println!("{}", "Running Cartesi Container...".yellow());
-    let mut child = Command::new("docker")
-        .arg("run")
-        .arg("--rm")
-        .arg("-v")
-        .arg(format!(
+    let binds = vec![
+        format!(
             "{}:/data",
             current_dir
                 .join(".cartesi/image")
                 .to_str()
                 .expect("failed to convert path to string")
-        ))
-        .arg("-v")
-        .arg(format!(
+        ),
+        format!(
             "{}:/output",
             current_dir
                 .to_str()
                 .expect("Failed to get current directory")
-        ))
-        .arg("ghcr.io/zippiehq/cartesi-carize:latest")
-        .arg("/carize.sh")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'cartesi build' command");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    let start = time::Instant::now();
-
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            match line {
-                Ok(output) => {
-                    println!("{} {}", "CARIZE::".green(), output.green());
-                }
-                Err(e) => eprintln!("Error reading stdout: {}", e),
+        ),
+    ];
+
+    let status = run_container(
+        "ghcr.io/zippiehq/cartesi-carize:latest",
+        &["/carize.sh"],
+        &binds,
+        get_process_timeout("carize").unwrap_or(CARIZE_TIMEOUT),
+        |is_stderr, output| {
+            if is_stderr {
+                println!("{} {}", "CARIZE::".green(), output.red());
+            } else {
+                println!("{} {}", "CARIZE::".green(), output.green());
             }
-        }
-    });
+        },
+    );
 
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            match line {
-                Ok(output) => {
-                    println!("{} {}", "CARIZE::".green(), output.red());
-                }
-                Err(e) => eprintln!("Error reading stdout: {}", e),
-            }
+    match status {
+        Ok(0) => {
+            println!("✅ {}", "CARIZE generated successfully.".green());
+            true
         }
-    });
-
-    // Wait for email verification or timeout
-    while start.elapsed().as_secs() < 300 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                println!("✅ {}", "CARIZE generated successfully.".green());
-                return true;
-            } else {
-                eprintln!("{}", "car file generation process failed.".red());
-                return false;
-            }
+        Ok(_) => {
+            eprintln!("{}", "car file generation process failed.".red());
+            false
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e.to_string().red());
+            false
         }
-
-        // Poll every 5 seconds
-        thread::sleep(time::Duration::from_secs(5));
     }
-
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the generatioon process.");
-    return false;
 }
 
 /// @notice Function to call the co-processor task manager to register the machine, hash, grogram cid etc.
@@ -318,19 +224,19 @@ pub fn register_program_with_coprocessor(base_url: String) {
     );
     let machine_hash = get_machine_hash();
 
-    let curl_status = Command::new("curl")
-        .arg("-X")
-        .arg("POST")
-        .arg(format!(
+    let curl_status = match run_captured(
+        Command::new("curl").arg("-X").arg("POST").arg(format!(
             "{}/ensure/{}/{}/{}",
             base_url, cid, machine_hash, size
-        ))
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute curl POST command")
-        .wait_with_output()
-        .expect("Failed to wait for curl command to finish");
+        )),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(curl_status) => curl_status,
+        Err(e) => {
+            report_process_error("CURL", &e);
+            return;
+        }
+    };
 
     if curl_status.status.success() {
         println!(
@@ -352,59 +258,28 @@ pub fn register_program_with_coprocessor(base_url: String) {
 /// @param email The email address whick is linked or to be linked to web3 storage
 /// @returns a boolean value indicating whether or not the execution was successful
 fn login(email: String) -> bool {
-    let mut child = Command::new("w3")
-        .arg("login")
-        .arg(email)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'w3 login'");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    let start = time::Instant::now();
-
-    // Handle output in separate threads
-    thread::spawn(move || {
-        for line in stdout.lines() {
-            if let Ok(line) = line {
-                println!("{} {}", "W3STORAGE:: ".green(), line.green());
-            }
-        }
-    });
+    let w3_status = run_streamed(
+        Command::new("w3").arg("login").arg(email),
+        "W3STORAGE",
+        WEB3_STORAGE_WAIT_TIMEOUT,
+    );
 
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            if let Ok(line) = line {
-                eprintln!("{} {}", "W3STORAGE::NOTE::".yellow(), line.yellow());
-            } else if let Err(e) = line {
-                eprintln!("{} {}", "W3STORAGE::ERROR::".red(), e);
-            }
+    match w3_status {
+        Ok(output) if output.status.success() => {
+            println!("✅ Successfully logged in to Web3.Storage.");
+            true
         }
-    });
-
-    // Wait for email verification or timeout
-    while start.elapsed().as_secs() < 300 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                println!("✅ Successfully logged in to Web3.Storage.");
-                return true;
-            } else {
-                eprintln!("Login process timed out. Please verify the email within the specified timeout.");
-                return false;
-            }
+        Ok(_) => {
+            eprintln!(
+                "Login process failed. Please verify the email within the specified timeout."
+            );
+            false
+        }
+        Err(e) => {
+            report_process_error("W3STORAGE", &e);
+            false
         }
-
-        // Poll every 5 seconds
-        thread::sleep(time::Duration::from_secs(5));
     }
-
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the login process.");
-    return false;
 }
 
 /// @notice Function to check of web3storage space about to be created already exists
@@ -564,19 +439,22 @@ pub fn devnet_register_program_with_coprocessor(spinner: Option<ProgressBar>, re
     );
     let machine_hash = get_machine_hash();
 
-    let curl_status = Command::new("curl")
-        .arg("-X")
-        .arg("POST")
-        .arg(format!(
-            "http://127.0.0.1:3034/ensure/{}/{}/{}",
-            cid, machine_hash, size
-        ))
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute curl POST command")
-        .wait_with_output()
-        .expect("Failed to wait for curl command to finish");
+    let curl_status = match run_captured(
+        Command::new("curl").arg("-X").arg("POST").arg(format!(
+            "{}/ensure/{}/{}/{}",
+            active_devnet_config().solver_url(),
+            cid,
+            machine_hash,
+            size
+        )),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(curl_status) => curl_status,
+        Err(e) => {
+            report_process_error("CURL", &e);
+            return;
+        }
+    };
 
     if curl_status.status.success() {
         let stdout = String::from_utf8_lossy(&curl_status.stdout);
@@ -852,7 +730,10 @@ fn devnet_upload_car_file() -> bool {
         .to_str()
         .expect("Failed to convert path to string");
 
-    let url = "http://127.0.0.1:5001/api/v0/dag/import";
+    let url = format!(
+        "{}/api/v0/dag/import",
+        active_devnet_config().ipfs_api_url()
+    );
 
     let form = multipart::Form::new()
         .file("file", car_file)
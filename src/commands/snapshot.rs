@@ -0,0 +1,555 @@
+use crate::commands::deploy::deployment_history_dir;
+use crate::commands::devnet::{
+    active_devnet, active_devnet_config, clear_devnet_baseline, compose_file_args, devnet_path,
+    record_devnet_baseline,
+};
+use crate::helpers::docker::{check_docker_daemon, list_volumes, run_container};
+use crate::helpers::helpers::get_spinner;
+use crate::helpers::process::{run_captured, LONG_TIMEOUT};
+use crate::helpers::rpc::{parse_quantity, rpc_call};
+use chrono::Local;
+use colored::Colorize;
+use serde_json::json;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// @notice Image used to archive and restore the devnet volumes
+const ARCHIVE_IMAGE: &str = "alpine";
+
+/// @notice Function to get the folder named devnet snapshots are saved into
+/// @return the path to the snapshots folder
+pub fn snapshots_path() -> PathBuf {
+    let home_dir = env::var("HOME").expect("Failed to get HOME directory");
+    PathBuf::from(home_dir).join(".cartesi-coprocessor-snapshots")
+}
+
+/// @notice Function to resolve a snapshot name to its folder, names containing a path separator are used as paths
+/// @notice so snapshots shared by a team, e.g. committed next to the project, can be restored directly
+/// @param name The name of the snapshot or a path to a snapshot folder
+/// @return the path to the snapshot folder
+fn snapshot_path(name: &str) -> PathBuf {
+    if name.contains('/') || name.contains('\\') {
+        PathBuf::from(name)
+    } else {
+        snapshots_path().join(name)
+    }
+}
+
+/// @notice Function to run a docker compose command against the devnet compose file
+/// @param path The path to the checkout the devnet runs from
+/// @param args The arguments to pass to docker compose
+/// @return A result containing the stdout of the command, or its stderr on failure
+fn devnet_compose(path: &Path, args: &[&str]) -> Result<String, String> {
+    let docker_status = run_captured(
+        Command::new("docker")
+            .arg("compose")
+            .args(compose_file_args(path, &active_devnet_config()))
+            .args(args)
+            .current_dir(path),
+        LONG_TIMEOUT,
+    )
+    .map_err(|e| e.to_string())?;
+
+    if docker_status.status.success() {
+        Ok(String::from_utf8_lossy(&docker_status.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&docker_status.stderr).to_string())
+    }
+}
+
+/// @notice Function to list the docker volumes of the devnet compose project
+/// @param path The path to the checkout the devnet runs from
+/// @return A result containing the name of each volume with its logical name in the compose file
+fn devnet_volumes(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let config: serde_json::Value =
+        serde_json::from_str(&devnet_compose(path, &["config", "--format", "json"])?)
+            .map_err(|e| format!("Invalid docker compose config: {}", e))?;
+    let project = config["name"]
+        .as_str()
+        .ok_or("The docker compose config has no project name")?;
+
+    let volumes = list_volumes(&format!("com.docker.compose.project={}", project))
+        .map_err(|e| e.to_string())?;
+    Ok(logical_volume_names(&volumes))
+}
+
+/// @notice Function to pair the devnet volumes with their logical name in the compose file
+/// @notice The logical name is the same whatever the compose project is called, so snapshots can be restored into a devnet started from another folder
+/// @param volumes The volumes as listed by the Docker Engine API
+/// @return the name of each volume with its logical name, the volume name is used for volumes without the `com.docker.compose.volume` label
+fn logical_volume_names(volumes: &[serde_json::Value]) -> Vec<(String, String)> {
+    volumes
+        .iter()
+        .filter_map(|volume| {
+            let name = volume["Name"].as_str()?.to_string();
+            let logical = volume["Labels"]["com.docker.compose.volume"]
+                .as_str()
+                .filter(|logical| !logical.is_empty())
+                .map_or_else(|| name.clone(), |logical| logical.to_string());
+            Some((name, logical))
+        })
+        .collect()
+}
+
+/// @notice Function to run a shell command in a throwaway container with a volume and the snapshot folder mounted
+/// @param volume The docker volume, mounted at `/volume`
+/// @param folder The absolute path to the snapshot folder, mounted at `/snapshot`
+/// @param script The shell command to run
+/// @return A result containing nothing, or the stderr of the container on failure
+fn run_volume_container(volume: &str, folder: &Path, script: &str) -> Result<(), String> {
+    let mut stderr = String::new();
+    let status = run_container(
+        ARCHIVE_IMAGE,
+        &["sh", "-c", script],
+        &[
+            format!("{}:/volume", volume),
+            format!("{}:/snapshot", folder.display()),
+        ],
+        LONG_TIMEOUT,
+        |is_stderr, line| {
+            if is_stderr {
+                stderr.push_str(line);
+                stderr.push('\n');
+            }
+        },
+    )
+    .map_err(|e| e.to_string())?;
+
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(stderr)
+    }
+}
+
+/// @notice Function to copy the json files of one folder into another, used for the deployment history
+/// @param from The folder to copy from
+/// @param to The folder to copy into, created if missing
+/// @return A result containing the number of files copied
+fn copy_records(from: &Path, to: &Path) -> Result<usize, String> {
+    let Ok(entries) = fs::read_dir(from) else {
+        return Ok(0);
+    };
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+
+    let mut copied = 0;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            fs::copy(&path, to.join(entry.file_name()))
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// @notice Function to replace the json files of one folder with those of another, so records newer than a snapshot do not survive its restore
+/// @param from The folder to copy from, a missing folder leaves `to` empty
+/// @param to The folder to replace the records of
+/// @return A result containing the number of files copied
+fn replace_records(from: &Path, to: &Path) -> Result<usize, String> {
+    if let Ok(entries) = fs::read_dir(to) {
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                fs::remove_file(&path)
+                    .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            }
+        }
+    }
+    copy_records(from, to)
+}
+
+/// @notice Function to restart the devnet containers and load a dumped anvil state into the fresh node
+/// @param path The path to the checkout the devnet runs from
+/// @param anvil_state The state returned by `anvil_dumpState`
+/// @return true if the devnet restarted with the state loaded else false
+fn restart_with_state(path: &Path, anvil_state: &str) -> bool {
+    // The stopped containers are reused, up only waits for them to become healthy again
+    if let Err(stderr) = devnet_compose(path, &["up", "--wait", "-d", "--no-build"]) {
+        eprintln!("❌ Failed to restart devnet containers.");
+        println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+        return false;
+    }
+
+    let anvil_rpc = active_devnet_config().anvil_rpc();
+    match rpc_call(&anvil_rpc, "anvil_loadState", json!([anvil_state])) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("❌ {} {}", "Failed to load the anvil state:".red(), e.red());
+            false
+        }
+    }
+}
+
+/// @notice Function to check the devnet is installed and running before taking or restoring a snapshot
+/// @return an Option containing the path to the checkout the devnet runs from
+fn running_devnet_path() -> Option<PathBuf> {
+    if !check_docker_daemon() {
+        return None;
+    }
+    let path = devnet_path();
+    let anvil_rpc = active_devnet_config().anvil_rpc();
+    if !path.join("docker-compose-devnet.yaml").exists() {
+        println!(
+            "❌ {}",
+            "Devnet is not installed, please run the start-devnet command first.".red()
+        );
+        return None;
+    }
+
+    if rpc_call(&anvil_rpc, "eth_blockNumber", json!([])).is_err() {
+        println!(
+            "❌ {}",
+            "Devnet is not running, please run the start-devnet command first.".red()
+        );
+        return None;
+    }
+    Some(path)
+}
+
+/// @notice Function to save the anvil chain, the devnet volumes and the deployment history into a snapshot folder
+/// @notice The devnet containers are stopped while their volumes are archived, then restarted with the same chain state
+/// @param path The path to the checkout the devnet runs from
+/// @param folder The absolute path to the folder to save the snapshot into
+/// @param name The name of the snapshot
+/// @return an Option containing the block, the number of volumes and deployment records saved and whether the devnet restarted, None if the snapshot is incomplete
+fn save_snapshot(path: &Path, folder: &Path, name: &str) -> Option<(u64, usize, usize, bool)> {
+    let volumes = match devnet_volumes(path) {
+        Ok(volumes) => volumes,
+        Err(stderr) => {
+            println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+            return None;
+        }
+    };
+
+    let anvil_rpc = active_devnet_config().anvil_rpc();
+    let spinner = get_spinner();
+    spinner.set_message("Dumping anvil state...");
+
+    let block = rpc_call(&anvil_rpc, "eth_blockNumber", json!([]))
+        .and_then(|block| parse_quantity(&block))
+        .unwrap_or_default();
+    let anvil_state = match rpc_call(&anvil_rpc, "anvil_dumpState", json!([])) {
+        Ok(serde_json::Value::String(state)) => state,
+        Ok(other) => other.to_string(),
+        Err(e) => {
+            spinner.finish_and_clear();
+            eprintln!("❌ {} {}", "Failed to dump the anvil state:".red(), e.red());
+            return None;
+        }
+    };
+    if let Err(e) = fs::write(folder.join("anvil_state.hex"), &anvil_state) {
+        spinner.finish_and_clear();
+        eprintln!("❌ Failed to save the anvil state: {:?}", e);
+        return None;
+    }
+
+    spinner.set_message("Stopping devnet containers...");
+    if let Err(stderr) = devnet_compose(path, &["stop"]) {
+        spinner.finish_and_clear();
+        eprintln!("❌ Failed to stop devnet containers.");
+        println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+        return None;
+    }
+
+    let mut archived: Vec<String> = Vec::new();
+    for (volume, logical) in volumes.iter() {
+        spinner.set_message(format!("Archiving volume {}...", logical));
+        match run_volume_container(
+            volume,
+            folder,
+            &format!("tar czf /snapshot/volumes/{}.tar.gz -C /volume .", logical),
+        ) {
+            Ok(_) => archived.push(logical.clone()),
+            Err(stderr) => {
+                eprintln!("❌ Failed to archive volume {}.", volume);
+                println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+            }
+        }
+    }
+
+    spinner.set_message("Restarting devnet containers...");
+    let restarted = restart_with_state(path, &anvil_state);
+    spinner.finish_and_clear();
+    // Snapshot ids do not survive the restart, and saving a snapshot must not move the reset baseline
+    clear_devnet_baseline();
+
+    if archived.len() != volumes.len() {
+        return None;
+    }
+
+    let records = match copy_records(
+        &deployment_history_dir(),
+        &folder.join("deployment_history"),
+    ) {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("❌ Failed to save deployment history: {}", e);
+            return None;
+        }
+    };
+
+    let devnet = active_devnet().unwrap_or_default();
+    let metadata = json!({
+        "name": name,
+        "created_at": Local::now().to_rfc3339(),
+        "devnet_version": devnet["version"],
+        "devnet_commit": devnet["commit"],
+        "block_number": block as u64,
+        "volumes": archived,
+        "deployment_records": records,
+    });
+    if let Err(e) = fs::write(
+        folder.join("snapshot.json"),
+        serde_json::to_string_pretty(&metadata).unwrap(),
+    ) {
+        eprintln!("❌ Failed to save snapshot metadata: {:?}", e);
+        return None;
+    }
+
+    Some((block as u64, archived.len(), records, restarted))
+}
+
+/// @notice Entry point function to save the anvil chain, the devnet volumes and the deployment history into a named snapshot
+/// @notice The snapshot is written to a temporary folder next to its destination and only moved into place once complete
+/// @param name The name of the snapshot or a path to the snapshot folder to create
+pub fn devnet_snapshot(name: String) {
+    let Some(path) = running_devnet_path() else {
+        return;
+    };
+
+    let folder = snapshot_path(&name);
+    if folder.exists() {
+        println!(
+            "❌ {} {}",
+            "A snapshot already exists at".red(),
+            folder.display().to_string().red()
+        );
+        return;
+    }
+    let partial = folder.with_file_name(format!(
+        ".{}.partial",
+        folder.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    // A leftover of an interrupted snapshot is never a valid snapshot
+    let _ = fs::remove_dir_all(&partial);
+    if let Err(e) = fs::create_dir_all(partial.join("volumes")) {
+        eprintln!("❌ Failed to create snapshot folder: {:?}", e);
+        return;
+    }
+    let partial = fs::canonicalize(&partial).expect("Failed to resolve snapshot folder");
+
+    let Some((block, volumes, records, restarted)) = save_snapshot(&path, &partial, &name) else {
+        let _ = fs::remove_dir_all(&partial);
+        println!("❌ {}", "Snapshot not saved, see the messages above.".red());
+        return;
+    };
+    if let Err(e) = fs::rename(&partial, &folder) {
+        let _ = fs::remove_dir_all(&partial);
+        eprintln!("❌ Failed to move the snapshot into place: {:?}", e);
+        return;
+    }
+    let folder = fs::canonicalize(&folder).expect("Failed to resolve snapshot folder");
+
+    if !restarted {
+        println!(
+            "⚠️  {} {}",
+            "Snapshot saved to".yellow(),
+            format!(
+                "{} but the devnet failed to restart, see the messages above.",
+                folder.display()
+            )
+            .yellow()
+        );
+        return;
+    }
+
+    println!(
+        "✅ {} {}",
+        "Devnet snapshot saved to".green(),
+        folder.display().to_string().green()
+    );
+    let data = vec![
+        ("Block", block.to_string()),
+        ("Volumes", volumes.to_string()),
+        ("Deployment records", records.to_string()),
+    ];
+
+    // Calculate the width of the first column
+    let max_width = data.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    for (name, value) in data {
+        println!("{:<width$}  {}", name, value, width = max_width);
+    }
+}
+
+/// @notice Entry point function to restore the anvil chain, the devnet volumes and the deployment history of a snapshot
+/// @notice The devnet must be running, its containers are stopped while the volumes are replaced then restarted
+/// @param name The name of the snapshot or a path to a snapshot folder
+pub fn devnet_restore(name: String) {
+    let folder = snapshot_path(&name);
+    let metadata: serde_json::Value = match fs::read_to_string(folder.join("snapshot.json")) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => {
+            println!(
+                "❌ {} {}",
+                "No snapshot found at".red(),
+                folder.display().to_string().red()
+            );
+            return;
+        }
+    };
+    let folder = fs::canonicalize(&folder).expect("Failed to resolve snapshot folder");
+    let anvil_state = match fs::read_to_string(folder.join("anvil_state.hex")) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("❌ Failed to read the anvil state of the snapshot: {:?}", e);
+            return;
+        }
+    };
+
+    let Some(path) = running_devnet_path() else {
+        return;
+    };
+
+    let devnet = active_devnet().unwrap_or_default();
+    if metadata["devnet_commit"] != devnet["commit"] {
+        println!(
+            "⚠️  {} {}",
+            "The snapshot was taken on another devnet version:".yellow(),
+            metadata["devnet_version"]
+                .as_str()
+                .unwrap_or("release branch")
+                .yellow()
+        );
+    }
+
+    let volumes = match devnet_volumes(&path) {
+        Ok(volumes) => volumes,
+        Err(stderr) => {
+            println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+            return;
+        }
+    };
+
+    let spinner = get_spinner();
+    spinner.set_message("Stopping devnet containers...");
+    if let Err(stderr) = devnet_compose(&path, &["stop"]) {
+        spinner.finish_and_clear();
+        eprintln!("❌ Failed to stop devnet containers.");
+        println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+        return;
+    }
+
+    let mut failed = false;
+    for logical in metadata["volumes"].as_array().cloned().unwrap_or_default() {
+        let logical = logical.as_str().unwrap_or_default();
+        let Some((volume, _)) = volumes.iter().find(|(_, existing)| existing == logical) else {
+            println!(
+                "⚠️  {} {}",
+                "Skipping volume missing from the devnet:".yellow(),
+                logical.yellow()
+            );
+            continue;
+        };
+
+        spinner.set_message(format!("Restoring volume {}...", logical));
+        if let Err(stderr) = run_volume_container(
+            volume,
+            &folder,
+            &format!(
+                "find /volume -mindepth 1 -delete && tar xzf /snapshot/volumes/{}.tar.gz -C /volume",
+                logical
+            ),
+        ) {
+            eprintln!("❌ Failed to restore volume {}.", volume);
+            println!("{} {}", "DOCKER::RESPONSE::".red(), stderr.red());
+            failed = true;
+        }
+    }
+
+    spinner.set_message("Restarting devnet containers...");
+    let restarted = restart_with_state(&path, &anvil_state);
+    spinner.finish_and_clear();
+    // The restored state becomes the baseline devnet reset reverts to
+    if restarted {
+        record_devnet_baseline();
+    } else {
+        clear_devnet_baseline();
+    }
+
+    if let Err(e) = replace_records(
+        &folder.join("deployment_history"),
+        &deployment_history_dir(),
+    ) {
+        eprintln!("❌ Failed to restore deployment history: {}", e);
+        failed = true;
+    }
+
+    if failed || !restarted {
+        println!(
+            "⚠️  {}",
+            "Snapshot restored with errors, see the messages above.".yellow()
+        );
+    } else {
+        println!(
+            "✅ {} {} {}",
+            "Devnet restored from snapshot".green(),
+            metadata["name"].as_str().unwrap_or(&name).green(),
+            format!("at block {}", metadata["block_number"]).green()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_logical_volume_names() {
+        let volumes = [
+            json!({
+                "Name": "coprocessor_devnet_ipfs-data",
+                "Labels": { "com.docker.compose.volume": "ipfs-data" },
+            }),
+            json!({ "Name": "stray", "Labels": null }),
+            json!({ "Labels": {} }),
+        ];
+        assert_eq!(
+            logical_volume_names(&volumes),
+            vec![
+                (
+                    String::from("coprocessor_devnet_ipfs-data"),
+                    String::from("ipfs-data")
+                ),
+                (String::from("stray"), String::from("stray")),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaces_records_newer_than_the_snapshot() {
+        let root = env::temp_dir().join(format!("snapshot-records-{}", std::process::id()));
+        let (from, to) = (root.join("from"), root.join("to"));
+        fs::create_dir_all(&from).unwrap();
+        fs::create_dir_all(&to).unwrap();
+        fs::write(from.join("old.json"), "{}").unwrap();
+        fs::write(to.join("old.json"), "{\"stale\":true}").unwrap();
+        fs::write(to.join("new.json"), "{}").unwrap();
+        fs::write(to.join("notes.txt"), "").unwrap();
+
+        assert_eq!(replace_records(&from, &to).unwrap(), 1);
+        assert_eq!(fs::read_to_string(to.join("old.json")).unwrap(), "{}");
+        assert!(!to.join("new.json").exists());
+        assert!(to.join("notes.txt").exists());
+
+        assert_eq!(replace_records(&root.join("missing"), &to).unwrap(), 0);
+        assert!(!to.join("old.json").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
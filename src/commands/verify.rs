@@ -0,0 +1,538 @@
+use crate::commands::deploy::{find_deployment_record, update_deployment_record};
+use crate::helpers::artifacts::{compilation_target, encode_constructor_args};
+use crate::helpers::config::{
+    get_network_profile, get_verifier_config, VerifierConfig, CONFIG_FILE_NAME,
+};
+use crate::helpers::helpers::get_spinner;
+use crate::helpers::process::{report_process_error, run_captured, LONG_TIMEOUT, SHORT_TIMEOUT};
+use chrono::Local;
+use colored::Colorize;
+use reqwest::blocking::Client;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// @notice Interval between two checks of a pending verification
+const VERIFY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// @notice Number of checks of a pending verification before giving up
+const VERIFY_POLL_ATTEMPTS: u32 = 60;
+
+/// @notice Etherscan api used when no url is configured, the chain is selected with the `chainid` parameter
+const ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// @notice Source of a deployed contract to submit to an etherscan compatible explorer api
+struct VerificationRequest {
+    chain_id: u64,
+    address: String,
+    contract_name: String,
+    compiler_version: String,
+    standard_json_input: String,
+    constructor_args: String,
+}
+
+/// @notice Function to get the constructor arguments saved in a deployment record
+/// @param record The deployment record of the contract
+/// @return the constructor arguments the contract was deployed with
+fn record_constructor_args(record: &serde_json::Value) -> Vec<String> {
+    record["constructor_args"]
+        .as_array()
+        .map(|args| {
+            args.iter()
+                .filter_map(|arg| arg.as_str().map(|arg| arg.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// @notice Function to read the `status` and `result` fields of an explorer api response
+/// @param response The response of the explorer
+/// @return A result containing whether the status is `1` and the result message
+fn read_explorer_response(
+    response: reqwest::Result<reqwest::blocking::Response>,
+) -> Result<(bool, String), String> {
+    let body: serde_json::Value = response
+        .map_err(|e| format!("Failed to reach the explorer api: {}", e))?
+        .json()
+        .map_err(|e| format!("Invalid response from the explorer api: {}", e))?;
+
+    let result = match &body["result"] {
+        serde_json::Value::String(result) => result.clone(),
+        result => result.to_string(),
+    };
+    Ok((body["status"] == "1", result))
+}
+
+/// @notice Function to submit the source of a contract to an etherscan compatible explorer api
+/// @param api_url The url of the explorer api
+/// @param api_key The api key of the explorer, if it requires one
+/// @param request The source and compiler settings of the contract
+/// @return A result containing the guid of the verification to poll, None if the explorer already has the source
+fn submit_verification(
+    api_url: &str,
+    api_key: Option<&str>,
+    request: &VerificationRequest,
+) -> Result<Option<String>, String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .expect("Failed to build HTTP client");
+
+    let response = client
+        .post(api_url)
+        .query(&[("chainid", request.chain_id.to_string())])
+        .form(&[
+            ("apikey", api_key.unwrap_or_default()),
+            ("module", "contract"),
+            ("action", "verifysourcecode"),
+            ("contractaddress", &request.address),
+            ("sourceCode", &request.standard_json_input),
+            ("codeformat", "solidity-standard-json-input"),
+            ("contractname", &request.contract_name),
+            ("compilerversion", &format!("v{}", request.compiler_version)),
+            // The misspelling is part of the etherscan api
+            ("constructorArguements", &request.constructor_args),
+        ])
+        .send();
+
+    match read_explorer_response(response)? {
+        (true, guid) => Ok(Some(guid)),
+        (false, result) if result.to_lowercase().contains("already verified") => Ok(None),
+        (false, result) => Err(result),
+    }
+}
+
+/// @notice Function to wait for an explorer to finish a verification
+/// @param api_url The url of the explorer api
+/// @param api_key The api key of the explorer, if it requires one
+/// @param chain_id The chain the contract is deployed on
+/// @param guid The guid returned when the source was submitted
+/// @param interval The time to wait between two checks
+/// @param attempts The number of checks before giving up
+/// @return A result telling whether the contract was verified, with the reason of the failure
+fn poll_verification(
+    api_url: &str,
+    api_key: Option<&str>,
+    chain_id: u64,
+    guid: &str,
+    interval: Duration,
+    attempts: u32,
+) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("Failed to build HTTP client");
+
+    for _ in 0..attempts {
+        let response = client
+            .get(api_url)
+            .query(&[
+                ("chainid", chain_id.to_string().as_str()),
+                ("apikey", api_key.unwrap_or_default()),
+                ("module", "contract"),
+                ("action", "checkverifystatus"),
+                ("guid", guid),
+            ])
+            .send();
+
+        // Pending verifications are reported with a `0` status, so only the result tells the outcome
+        let (_, result) = read_explorer_response(response)?;
+        if result.starts_with("Pass") || result.to_lowercase().contains("already verified") {
+            return Ok(());
+        }
+        if result.starts_with("Fail") {
+            return Err(result);
+        }
+        thread::sleep(interval);
+    }
+
+    Err(format!(
+        "Verification {} is still pending after {} checks",
+        guid, attempts
+    ))
+}
+
+/// @notice Function to verify a deployed contract through an etherscan compatible explorer api, used by etherscan and blockscout
+/// @notice The standard json input is produced by forge, then submitted and polled by the cli
+/// @param record The deployment record of the contract to verify
+/// @param verifier The block explorer settings for the network deployed to
+/// @param chain_id The chain the contract is deployed on
+/// @return true if the explorer verified the contract else false
+fn verify_with_explorer_api(
+    record: &serde_json::Value,
+    verifier: &VerifierConfig,
+    chain_id: u64,
+) -> bool {
+    let address = record["deployed_to"].as_str().unwrap_or_default();
+    let contract_name = record["contract_name"].as_str().unwrap_or_default();
+
+    let api_url = match (&verifier.url, verifier.verifier.as_str()) {
+        (Some(url), _) => url.clone(),
+        (None, "etherscan") => ETHERSCAN_API_URL.to_string(),
+        (None, _) => {
+            eprintln!(
+                "❌ {} {} {}",
+                "Please set the api url of".red(),
+                verifier.verifier.red(),
+                format!("in {}", CONFIG_FILE_NAME).red()
+            );
+            return false;
+        }
+    };
+
+    let Some((qualified_name, compiler_version)) = compilation_target(contract_name) else {
+        eprintln!(
+            "❌ {} {}",
+            "Could not find the compiled artifact of".red(),
+            contract_name.red()
+        );
+        return false;
+    };
+
+    let args = record_constructor_args(record);
+    let constructor_args = if args.is_empty() {
+        String::new()
+    } else {
+        match encode_constructor_args(contract_name, &args) {
+            Some(encoded_args) => encoded_args.trim_start_matches("0x").to_string(),
+            None => return false,
+        }
+    };
+
+    let standard_json_input = match run_captured(
+        Command::new("forge")
+            .arg("verify-contract")
+            .arg(address)
+            .arg(contract_name)
+            .arg("--show-standard-json-input"),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+        Ok(output) => {
+            eprintln!("❌ Failed to generate the standard json input with Forge.");
+            eprintln!("Error: {}", String::from_utf8_lossy(&output.stderr));
+            return false;
+        }
+        Err(e) => {
+            report_process_error("FORGE", &e);
+            return false;
+        }
+    };
+
+    let request = VerificationRequest {
+        chain_id,
+        address: address.to_string(),
+        contract_name: qualified_name,
+        compiler_version,
+        standard_json_input,
+        constructor_args,
+    };
+    let api_key = verifier.api_key.as_deref();
+
+    let spinner = get_spinner();
+    spinner.set_message("Submitting contract source...");
+
+    let verified = submit_verification(&api_url, api_key, &request).and_then(|guid| match guid {
+        Some(guid) => {
+            spinner.set_message("Waiting for the explorer to verify the contract...");
+            poll_verification(
+                &api_url,
+                api_key,
+                chain_id,
+                &guid,
+                VERIFY_POLL_INTERVAL,
+                VERIFY_POLL_ATTEMPTS,
+            )
+        }
+        None => Ok(()),
+    });
+
+    spinner.finish_and_clear();
+    match verified {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("❌ {}", "Failed to verify contract.".red());
+            eprintln!("{} {}", "EXPLORER::ERROR::".red(), e.red());
+            false
+        }
+    }
+}
+
+/// @notice Function to submit the source of a deployed contract to sourcify using forge
+/// @param record The deployment record of the contract to verify
+/// @param verifier The block explorer settings for the network deployed to
+/// @return true if sourcify accepted the verification else false
+fn run_forge_verify(record: &serde_json::Value, verifier: &VerifierConfig) -> bool {
+    let address = record["deployed_to"].as_str().unwrap_or_default();
+    let contract_name = record["contract_name"].as_str().unwrap_or_default();
+    let rpc = record["rpc_url"].as_str().unwrap_or_default();
+    let args = record_constructor_args(record);
+
+    let mut command = Command::new("forge");
+    command
+        .arg("verify-contract")
+        .arg(address)
+        .arg(contract_name)
+        .arg("--rpc-url")
+        .arg(rpc)
+        .arg("--verifier")
+        .arg(&verifier.verifier)
+        .arg("--watch");
+
+    if let Some(url) = &verifier.url {
+        command.arg("--verifier-url").arg(url);
+    }
+    if !args.is_empty() {
+        match encode_constructor_args(contract_name, &args) {
+            Some(encoded_args) => {
+                command.arg("--constructor-args").arg(encoded_args);
+            }
+            None => return false,
+        }
+    }
+
+    let spinner = get_spinner();
+    spinner.set_message("Verifying contract source...");
+
+    let forge_status = run_captured(&mut command, LONG_TIMEOUT);
+
+    spinner.finish_and_clear();
+    let forge_status = match forge_status {
+        Ok(forge_status) => forge_status,
+        Err(e) => {
+            report_process_error("FORGE", &e);
+            return false;
+        }
+    };
+    let stdout = String::from_utf8_lossy(&forge_status.stdout);
+
+    if forge_status.status.success() {
+        println!("{} {}", "FORGE::RESPONSE::".green(), stdout.green());
+        true
+    } else {
+        eprintln!("❌ Failed to verify contract with Forge.");
+        println!("{} {}", "FORGE::RESPONSE::".red(), stdout.red());
+        eprintln!("Error: {}", String::from_utf8_lossy(&forge_status.stderr));
+        false
+    }
+}
+
+/// @notice Function to verify the contract of a deployment record and store the verification status in it
+/// @param record_path The path to the deployment record
+/// @param network The network the contract was deployed to
+pub fn verify_deployment(record_path: &Path, network: &str) {
+    let Some(verifier) = get_verifier_config(network) else {
+        println!(
+            "{} {} {} {}",
+            "No block explorer configured for".red(),
+            network.to_lowercase().red(),
+            "please add a [networks.<network>.verifier] section to".red(),
+            CONFIG_FILE_NAME.red()
+        );
+        return;
+    };
+
+    let content = fs::read_to_string(record_path).expect("Failed to read deployment record");
+    let record: serde_json::Value =
+        serde_json::from_str(&content).expect("Failed to parse deployment record");
+
+    if record["contract_name"].as_str().is_none() {
+        println!(
+            "{}",
+            "This deployment record has no contract name, please redeploy with the current cli to verify it."
+                .red()
+        );
+        return;
+    }
+
+    let verified = match verifier.verifier.as_str() {
+        "etherscan" | "blockscout" => {
+            let Some(profile) = get_network_profile(network) else {
+                eprintln!("❌ {} '{}'.", "Unknown network".red(), network.red());
+                return;
+            };
+            verify_with_explorer_api(&record, &verifier, profile.chain_id)
+        }
+        _ => run_forge_verify(&record, &verifier),
+    };
+    let verification = json!({
+        "status": if verified { "verified" } else { "failed" },
+        "verifier": verifier.verifier,
+        "verifier_url": verifier.url,
+        "checked_at": Local::now().to_rfc3339(),
+    });
+
+    match update_deployment_record(record_path, "verification", verification) {
+        Ok(_) if verified => println!("✅ {}", "Contract verified successfully.".green()),
+        Ok(_) => {}
+        Err(err) => eprintln!("❌ Error saving verification status: {}", err),
+    }
+}
+
+/// @notice Entry point function to verify a previously deployed contract using the saved deployment record
+/// @param network The network the contract was deployed to
+/// @param address The address of the contract, if None the latest deployment on the network is verified
+pub fn verify_contract(network: String, address: Option<String>) {
+    match find_deployment_record(&network, address.as_deref()) {
+        Some((record_path, _)) => verify_deployment(&record_path, &network),
+        None => println!(
+            "{} {}",
+            "No deployment record found in deployment_history for".red(),
+            address.unwrap_or(network).red()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// Starts an explorer api answering each request with the next response, and records the requests
+    fn mock_explorer(responses: Vec<serde_json::Value>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(length) = header.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                recorded.lock().unwrap().push(format!(
+                    "{}\n{}",
+                    request_line.trim(),
+                    String::from_utf8_lossy(&body)
+                ));
+
+                let response = response.to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn request() -> VerificationRequest {
+        VerificationRequest {
+            chain_id: 17000,
+            address: String::from("0x5FbDB2315678afecb367f032d93F642f64180aa3"),
+            contract_name: String::from("src/MyContract.sol:MyContract"),
+            compiler_version: String::from("0.8.26+commit.8a97fa7a"),
+            standard_json_input: String::from(r#"{"language":"Solidity"}"#),
+            constructor_args: String::from("00ab"),
+        }
+    }
+
+    #[test]
+    fn submits_source_and_polls_until_verified() {
+        let (url, requests) = mock_explorer(vec![
+            json!({"status": "1", "message": "OK", "result": "guid-1"}),
+            json!({"status": "0", "message": "NOTOK", "result": "Pending in queue"}),
+            json!({"status": "1", "message": "OK", "result": "Pass - Verified"}),
+        ]);
+
+        let guid = submit_verification(&url, Some("key"), &request()).unwrap();
+        assert_eq!(guid.as_deref(), Some("guid-1"));
+        let verified = poll_verification(&url, Some("key"), 17000, "guid-1", Duration::ZERO, 5);
+        assert_eq!(verified, Ok(()));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("POST /api?chainid=17000 "));
+        for field in [
+            "apikey=key",
+            "action=verifysourcecode",
+            "contractaddress=0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "codeformat=solidity-standard-json-input",
+            "contractname=src%2FMyContract.sol%3AMyContract",
+            "compilerversion=v0.8.26%2Bcommit.8a97fa7a",
+            "constructorArguements=00ab",
+        ] {
+            assert!(requests[0].contains(field), "missing {}", field);
+        }
+        assert!(requests[1].starts_with("GET /api?chainid=17000&apikey=key"));
+        assert!(requests[1].contains("action=checkverifystatus&guid=guid-1"));
+    }
+
+    #[test]
+    fn already_verified_source_needs_no_polling() {
+        let (url, _) = mock_explorer(vec![json!({
+            "status": "0",
+            "message": "NOTOK",
+            "result": "Contract source code already verified"
+        })]);
+
+        assert_eq!(submit_verification(&url, None, &request()), Ok(None));
+    }
+
+    #[test]
+    fn rejected_submission_is_an_error() {
+        let (url, _) = mock_explorer(vec![json!({
+            "status": "0",
+            "message": "NOTOK",
+            "result": "Invalid API Key"
+        })]);
+
+        assert_eq!(
+            submit_verification(&url, Some("bad"), &request()),
+            Err(String::from("Invalid API Key"))
+        );
+    }
+
+    #[test]
+    fn failed_verification_is_an_error() {
+        let (url, _) = mock_explorer(vec![
+            json!({"status": "0", "message": "NOTOK", "result": "Pending in queue"}),
+            json!({"status": "0", "message": "NOTOK", "result": "Fail - Unable to verify"}),
+        ]);
+
+        assert_eq!(
+            poll_verification(&url, None, 1, "guid-1", Duration::ZERO, 5),
+            Err(String::from("Fail - Unable to verify"))
+        );
+    }
+
+    #[test]
+    fn pending_verification_gives_up_after_the_attempts() {
+        let (url, requests) = mock_explorer(vec![
+            json!({"status": "0", "message": "NOTOK", "result": "Pending in queue"});
+            3
+        ]);
+
+        let verified = poll_verification(&url, None, 1, "guid-1", Duration::ZERO, 2);
+        assert!(verified
+            .unwrap_err()
+            .contains("still pending after 2 checks"));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+}
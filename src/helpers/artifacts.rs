@@ -0,0 +1,188 @@
+use crate::helpers::process::{report_process_error, run_captured, LONG_TIMEOUT, SHORT_TIMEOUT};
+use colored::Colorize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// @notice Function to get the path to the compiled forge artifact of a contract
+/// @param contract_name The name of the contract, either `MyContract` or `src/MyContract.sol:MyContract`
+/// @return the path to the artifact in the `out` folder
+fn artifact_path(contract_name: &str) -> PathBuf {
+    let current_dir = env::current_dir().expect("Failed to get current directory");
+
+    let (file_name, name) = match contract_name.rsplit_once(':') {
+        Some((file, name)) => (
+            Path::new(file)
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_else(|| format!("{}.sol", name)),
+            name.to_string(),
+        ),
+        None => (format!("{}.sol", contract_name), contract_name.to_string()),
+    };
+
+    current_dir
+        .join("out")
+        .join(file_name)
+        .join(format!("{}.json", name))
+}
+
+/// @notice Function to read the compiled forge artifact of a contract
+/// @param contract_name The name of the contract
+/// @return an Option containing the parsed artifact, None if the contract has not been compiled
+pub fn read_artifact(contract_name: &str) -> Option<serde_json::Value> {
+    let content = fs::read_to_string(artifact_path(contract_name)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// @notice Function to get the creation bytecode of a contract from its forge artifact
+/// @param contract_name The name of the contract
+/// @return an Option containing the `0x` prefixed creation bytecode
+pub fn creation_bytecode(contract_name: &str) -> Option<String> {
+    read_artifact(contract_name)?["bytecode"]["object"]
+        .as_str()
+        .map(|bytecode| bytecode.to_string())
+}
+
+/// @notice Function to get the source file and compiler of a contract from the metadata of its forge artifact
+/// @param contract_name The name of the contract
+/// @return an Option containing the qualified name, e.g. `src/MyContract.sol:MyContract`, and the compiler version, e.g. `0.8.26+commit.8a97fa7a`
+pub fn compilation_target(contract_name: &str) -> Option<(String, String)> {
+    let artifact = read_artifact(contract_name)?;
+    let metadata = &artifact["metadata"];
+
+    let (source, name) = metadata["settings"]["compilationTarget"]
+        .as_object()?
+        .iter()
+        .next()?;
+    let compiler_version = metadata["compiler"]["version"].as_str()?;
+
+    Some((
+        format!("{}:{}", source, name.as_str()?),
+        compiler_version.to_string(),
+    ))
+}
+
+/// @notice Function to compile the contracts in the current foundry project
+/// @return true if the compilation was successful else false
+pub fn compile_contracts() -> bool {
+    let forge_status = match run_captured(Command::new("forge").arg("build"), LONG_TIMEOUT) {
+        Ok(forge_status) => forge_status,
+        Err(e) => {
+            report_process_error("FORGE", &e);
+            return false;
+        }
+    };
+
+    if !forge_status.status.success() {
+        eprintln!("❌ Failed to compile contracts with Forge.");
+        eprintln!("Error: {}", String::from_utf8_lossy(&forge_status.stderr));
+    }
+    forge_status.status.success()
+}
+
+/// @notice Function to format an abi parameter type, expanding tuples into their components
+/// @param param An abi input entry from a forge artifact
+/// @return the canonical solidity type of the parameter
+fn abi_param_type(param: &serde_json::Value) -> String {
+    let param_type = param["type"].as_str().unwrap_or_default();
+
+    match param_type.strip_prefix("tuple") {
+        Some(array_suffix) => {
+            let components: Vec<String> = param["components"]
+                .as_array()
+                .map(|components| components.iter().map(abi_param_type).collect())
+                .unwrap_or_default();
+            format!("({}){}", components.join(","), array_suffix)
+        }
+        None => param_type.to_string(),
+    }
+}
+
+/// @notice Function to get the constructor signature of a contract from its forge artifact
+/// @param contract_name The name of the contract
+/// @return an Option containing a signature like `constructor(address,bytes32)`
+fn constructor_signature(contract_name: &str) -> Option<String> {
+    let artifact = read_artifact(contract_name)?;
+
+    let constructor = artifact["abi"]
+        .as_array()?
+        .iter()
+        .find(|entry| entry["type"] == "constructor")?;
+
+    let inputs: Vec<String> = constructor["inputs"]
+        .as_array()?
+        .iter()
+        .map(abi_param_type)
+        .collect();
+
+    Some(format!("constructor({})", inputs.join(",")))
+}
+
+/// @notice Function to check whether a compiled contract exposes a function
+/// @param contract_name The name of the contract
+/// @param signature The canonical signature of the function, e.g. `setMachineHash(bytes32)`
+/// @return true if the contract abi contains the function else false
+pub fn has_function(contract_name: &str, signature: &str) -> bool {
+    let Some(artifact) = read_artifact(contract_name) else {
+        return false;
+    };
+
+    artifact["abi"].as_array().is_some_and(|abi| {
+        abi.iter()
+            .filter(|entry| entry["type"] == "function")
+            .any(|entry| {
+                let inputs: Vec<String> = entry["inputs"]
+                    .as_array()
+                    .map(|inputs| inputs.iter().map(abi_param_type).collect())
+                    .unwrap_or_default();
+                let name = entry["name"].as_str().unwrap_or_default();
+                format!("{}({})", name, inputs.join(",")) == signature
+            })
+    })
+}
+
+/// @notice Function to abi encode the constructor arguments of a deployment using cast
+/// @param contract_name The name of the deployed contract
+/// @param args The constructor arguments the contract was deployed with
+/// @return an Option containing the hex encoded constructor arguments
+pub fn encode_constructor_args(contract_name: &str, args: &[String]) -> Option<String> {
+    let Some(signature) = constructor_signature(contract_name) else {
+        eprintln!(
+            "❌ {} {}",
+            "Could not find the compiled constructor for".red(),
+            contract_name.red()
+        );
+        return None;
+    };
+
+    let cast_status = match run_captured(
+        Command::new("cast")
+            .arg("abi-encode")
+            .arg(signature)
+            .args(args),
+        SHORT_TIMEOUT,
+    ) {
+        Ok(cast_status) => cast_status,
+        Err(e) => {
+            report_process_error("CAST", &e);
+            return None;
+        }
+    };
+
+    if cast_status.status.success() {
+        Some(
+            String::from_utf8_lossy(&cast_status.stdout)
+                .trim()
+                .to_string(),
+        )
+    } else {
+        eprintln!(
+            "❌ {} {}",
+            "Failed to encode constructor arguments:".red(),
+            String::from_utf8_lossy(&cast_status.stderr).red()
+        );
+        None
+    }
+}
@@ -0,0 +1,252 @@
+use colored::Colorize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use toml::{Table, Value};
+
+/// @notice Name of the optional project configuration file
+pub const CONFIG_FILE_NAME: &str = "coprocessor.toml";
+
+/// @notice Chain details the cli expects to find behind a network's RPC endpoint
+#[derive(Debug, Clone)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub chain_id: u64,
+    pub task_issuer: Option<String>,
+    pub payment_token: Option<String>,
+}
+
+/// @notice Block explorer settings used to verify contract sources on a network
+#[derive(Debug, Clone)]
+pub struct VerifierConfig {
+    pub verifier: String,
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+}
+
+/// @notice Host ports the devnet publishes and an optional compose file applied on top of the devnet compose file
+#[derive(Debug, Clone)]
+pub struct DevnetConfig {
+    pub anvil_port: u16,
+    pub solver_port: u16,
+    pub ipfs_port: u16,
+    pub compose_override: Option<PathBuf>,
+}
+
+/// @notice Default host ports of the devnet anvil node, solver and IPFS api
+pub const DEFAULT_ANVIL_PORT: u16 = 8545;
+pub const DEFAULT_SOLVER_PORT: u16 = 3034;
+pub const DEFAULT_IPFS_PORT: u16 = 5001;
+
+impl DevnetConfig {
+    /// @notice Function to get the url of the devnet anvil node
+    pub fn anvil_rpc(&self) -> String {
+        format!("http://127.0.0.1:{}", self.anvil_port)
+    }
+
+    /// @notice Function to get the url of the devnet solver
+    pub fn solver_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.solver_port)
+    }
+
+    /// @notice Function to get the url of the devnet IPFS api
+    pub fn ipfs_api_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.ipfs_port)
+    }
+
+    /// @notice Function to check whether any devnet port differs from the ports of the devnet compose file
+    pub fn has_custom_ports(&self) -> bool {
+        self.anvil_port != DEFAULT_ANVIL_PORT
+            || self.solver_port != DEFAULT_SOLVER_PORT
+            || self.ipfs_port != DEFAULT_IPFS_PORT
+    }
+}
+
+/// @notice Function to find the project configuration file, searching the current directory and its parents
+/// @return an Option containing the path to the configuration file if one was found
+pub fn find_config_file() -> Option<PathBuf> {
+    let current_dir = env::current_dir().expect("Failed to get current directory");
+
+    current_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.exists())
+}
+
+/// @notice Function to load the project configuration file
+/// @return the parsed configuration, or an empty table if no configuration file exists
+pub fn load_project_config() -> Table {
+    let Some(path) = find_config_file() else {
+        return Table::new();
+    };
+
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Failed to read config file '{}'", path.display()));
+
+    match content.parse::<Table>() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!(
+                "{} Invalid config file '{}': {}",
+                "Error::".red(),
+                path.display(),
+                e
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// @notice Function to get the configuration section for a network
+/// @param network The name of the network, e.g. devnet, testnet or mainnet
+/// @return an Option containing the `[networks.<network>]` table if present
+pub fn get_network_config(network: &str) -> Option<Table> {
+    load_project_config()
+        .get("networks")
+        .and_then(Value::as_table)
+        .and_then(|networks| networks.get(&network.to_lowercase()))
+        .and_then(Value::as_table)
+        .cloned()
+}
+
+/// @notice Function to get the coprocessor version the project pins its devnet to
+/// @return an Option containing the tag or commit from `[devnet] version`
+pub fn get_pinned_devnet_version() -> Option<String> {
+    load_project_config()
+        .get("devnet")?
+        .get("version")?
+        .as_str()
+        .map(|version| version.to_string())
+}
+
+/// @notice Function to get the timeout configured for an external tool
+/// @param name The name of the tool command, e.g. `forge-install` or `docker-compose`
+/// @return an Option containing the timeout from `[timeouts] <name>` in seconds
+pub fn get_process_timeout(name: &str) -> Option<Duration> {
+    load_project_config()
+        .get("timeouts")?
+        .get(name)?
+        .as_integer()
+        .and_then(|seconds| u64::try_from(seconds).ok())
+        .map(Duration::from_secs)
+}
+
+/// @notice Private key of the first pre-funded anvil account, the devnet treasury and default deployer
+pub const DEVNET_PRIVATE_KEY: &str =
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// @notice Mnemonic anvil derives its pre-funded accounts from
+pub const DEVNET_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+/// @notice Names of the networks with a built in profile
+pub const NETWORK_NAMES: &[&str] = &["devnet", "testnet", "mainnet"];
+
+/// @notice Function to get the profile of a network, built in defaults can be overridden in `[networks.<network>]`
+/// @param network The name of the network, e.g. devnet, testnet or mainnet
+/// @return an Option containing the network profile, None if the network is unknown
+pub fn get_network_profile(network: &str) -> Option<NetworkProfile> {
+    let name = network.to_lowercase();
+    let (chain_id, task_issuer, payment_token) = match name.as_str() {
+        "devnet" => (
+            31337,
+            Some("0x95401dc811bb5740090279Ba06cfA8fcF6113778"),
+            Some("0xc5a5C42992dECbae36851359345FE25997F5C42d"),
+        ),
+        "testnet" => (
+            17000,
+            Some("0xff35E413F5e22A9e1Cc02F92dcb78a5076c1aaf3"),
+            None,
+        ),
+        "mainnet" => (1, None, None),
+        _ => return None,
+    };
+
+    let network_config = get_network_config(&name).unwrap_or_default();
+
+    Some(NetworkProfile {
+        chain_id: network_config
+            .get("chain_id")
+            .and_then(Value::as_integer)
+            .map_or(chain_id, |chain_id| chain_id as u64),
+        task_issuer: network_config
+            .get("task_issuer")
+            .and_then(Value::as_str)
+            .or(task_issuer)
+            .map(|address| address.to_string()),
+        payment_token: network_config
+            .get("payment_token")
+            .and_then(Value::as_str)
+            .or(payment_token)
+            .map(|address| address.to_string()),
+        name,
+    })
+}
+
+/// @notice Function to get the block explorer settings for a network
+/// @notice The `api_key_env` key names an environment variable to read the api key from, so keys don't need to be committed
+/// @param network The name of the network, e.g. devnet, testnet or mainnet
+/// @return an Option containing the verifier config from `[networks.<network>.verifier]`
+pub fn get_verifier_config(network: &str) -> Option<VerifierConfig> {
+    let network_config = get_network_config(network)?;
+    let verifier = network_config.get("verifier")?.as_table()?;
+
+    let get_string = |key: &str| {
+        verifier
+            .get(key)
+            .and_then(Value::as_str)
+            .map(|value| value.to_string())
+    };
+
+    let api_key = get_string("api_key")
+        .or_else(|| get_string("api_key_env").and_then(|var_name| env::var(var_name).ok()));
+
+    Some(VerifierConfig {
+        verifier: get_string("verifier").unwrap_or_else(|| String::from("etherscan")),
+        url: get_string("url"),
+        api_key,
+    })
+}
+
+/// @notice Function to get the devnet ports and compose override from `[devnet]`
+/// @notice A relative `compose_override` path is resolved against the folder of the configuration file
+/// @return the devnet config, with the default ports for any port not configured
+pub fn get_devnet_config() -> DevnetConfig {
+    let config = load_project_config();
+    let devnet = config
+        .get("devnet")
+        .and_then(Value::as_table)
+        .cloned()
+        .unwrap_or_default();
+
+    let get_port = |key: &str, default: u16| match devnet.get(key).and_then(Value::as_integer) {
+        Some(port) => u16::try_from(port).unwrap_or_else(|_| {
+            eprintln!(
+                "{} Invalid devnet {} '{}' in {}",
+                "Error::".red(),
+                key,
+                port,
+                CONFIG_FILE_NAME
+            );
+            std::process::exit(1);
+        }),
+        None => default,
+    };
+
+    let compose_override = devnet
+        .get("compose_override")
+        .and_then(Value::as_str)
+        .map(|path| {
+            let config_dir = find_config_file()
+                .and_then(|file| file.parent().map(|dir| dir.to_path_buf()))
+                .unwrap_or_default();
+            config_dir.join(path)
+        });
+
+    DevnetConfig {
+        anvil_port: get_port("anvil_port", DEFAULT_ANVIL_PORT),
+        solver_port: get_port("solver_port", DEFAULT_SOLVER_PORT),
+        ipfs_port: get_port("ipfs_port", DEFAULT_IPFS_PORT),
+        compose_override,
+    }
+}
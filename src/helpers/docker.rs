@@ -0,0 +1,637 @@
+use crate::helpers::helpers::get_spinner;
+use colored::Colorize;
+use indicatif::ProgressBar;
+use serde_json::json;
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Compose files are resolved on the client, and builds need BuildKit and `.dockerignore` handling, so the devnet
+// lifecycle (`up`, `down`, `ps`, `logs`, `config`) and `build` still run the `docker compose` CLI while pulls,
+// image archives, containers and volumes are handled here
+
+/// @notice Errors returned when talking to the Docker Engine API
+#[derive(Debug, Clone)]
+pub enum DockerError {
+    DaemonNotRunning(String),
+    PermissionDenied(String),
+    ImageNotFound(String),
+    Timeout(String),
+    Api(u16, String),
+    Io(String),
+}
+
+impl fmt::Display for DockerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DockerError::DaemonNotRunning(socket) => write!(
+                f,
+                "Docker daemon is not running (no daemon listening on {}). Please start it and try again.",
+                socket
+            ),
+            DockerError::PermissionDenied(socket) => write!(
+                f,
+                "Permission denied on {}, add your user to the docker group or set DOCKER_HOST to a rootless Docker or Podman socket.",
+                socket
+            ),
+            DockerError::ImageNotFound(image) => write!(f, "Docker image not found: {}", image),
+            DockerError::Timeout(action) => write!(f, "Timed out while {}", action),
+            DockerError::Api(status, message) => {
+                write!(f, "Docker API error (HTTP {}): {}", status, message)
+            }
+            DockerError::Io(message) => write!(f, "Docker socket error: {}", message),
+        }
+    }
+}
+
+/// @notice A response of the Docker Engine API, the body is streamed so progress can be reported while it arrives
+struct DockerResponse {
+    status: u16,
+    body: Box<dyn BufRead + Send>,
+}
+
+impl DockerResponse {
+    /// @notice Function to read the whole body of the response as json
+    fn json(mut self) -> Result<serde_json::Value, DockerError> {
+        let mut body = String::new();
+        self.body
+            .read_to_string(&mut body)
+            .map_err(|e| DockerError::Io(e.to_string()))?;
+        Ok(serde_json::from_str(&body).unwrap_or(serde_json::Value::String(body)))
+    }
+}
+
+/// @notice Reader decoding an http body sent with `Transfer-Encoding: chunked`
+struct ChunkedReader<R: BufRead> {
+    inner: R,
+    remaining: usize,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.done {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            let mut line = String::new();
+            // Every chunk but the first is preceded by the CRLF ending the previous chunk
+            if self.started {
+                self.inner.read_line(&mut line)?;
+                line.clear();
+            }
+            self.started = true;
+            self.inner.read_line(&mut line)?;
+
+            let size = line.trim().split(';').next().unwrap_or_default();
+            self.remaining = usize::from_str_radix(size, 16)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if self.remaining == 0 {
+                self.done = true;
+                return Ok(0);
+            }
+        }
+
+        let max = buf.len().min(self.remaining);
+        let read = self.inner.read(&mut buf[..max])?;
+        if read == 0 {
+            self.done = true;
+        }
+        self.remaining -= read;
+        Ok(read)
+    }
+}
+
+/// @notice Function to find the socket of the Docker Engine API
+/// @notice `DOCKER_HOST` and `CONTAINER_HOST` are used when set, otherwise the rootful, rootless and Docker Desktop
+/// @notice docker sockets are tried before the rootless and rootful Podman sockets
+/// @return the path to the socket, the default docker socket if none was found
+pub fn docker_socket_path() -> PathBuf {
+    for var_name in ["DOCKER_HOST", "CONTAINER_HOST"] {
+        if let Ok(host) = env::var(var_name) {
+            if let Some(path) = host.strip_prefix("unix://") {
+                return PathBuf::from(path);
+            }
+        }
+    }
+
+    let runtime_dir = env::var("XDG_RUNTIME_DIR").ok().map(PathBuf::from);
+    let home_dir = env::var("HOME").ok().map(PathBuf::from);
+    let candidates = [
+        Some(PathBuf::from("/var/run/docker.sock")),
+        runtime_dir.as_ref().map(|dir| dir.join("docker.sock")),
+        home_dir
+            .as_ref()
+            .map(|dir| dir.join(".docker/run/docker.sock")),
+        runtime_dir
+            .as_ref()
+            .map(|dir| dir.join("podman/podman.sock")),
+        Some(PathBuf::from("/run/podman/podman.sock")),
+    ];
+
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("/var/run/docker.sock"))
+}
+
+/// @notice Function to open a connection to the Docker Engine API socket
+/// @return A result containing the connected socket
+#[cfg(unix)]
+fn connect() -> Result<std::os::unix::net::UnixStream, DockerError> {
+    let socket = docker_socket_path();
+    std::os::unix::net::UnixStream::connect(&socket).map_err(|e| match e.kind() {
+        io::ErrorKind::PermissionDenied => {
+            DockerError::PermissionDenied(socket.display().to_string())
+        }
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused => {
+            DockerError::DaemonNotRunning(socket.display().to_string())
+        }
+        _ => DockerError::Io(e.to_string()),
+    })
+}
+
+/// @notice Function to open a connection to the Docker Engine API socket
+/// @return An error, only unix sockets are supported
+#[cfg(not(unix))]
+fn connect() -> Result<std::net::TcpStream, DockerError> {
+    Err(DockerError::Io(String::from(
+        "the Docker Engine API is only supported over unix sockets",
+    )))
+}
+
+/// @notice Function to send a request with a json body to the Docker Engine API
+/// @param method The http method
+/// @param path The api path including the query string, e.g. `/images/create?fromImage=alpine`
+/// @param body An optional json body
+/// @return A result containing the response, errors returned by the api are turned into a DockerError
+fn docker_request(
+    method: &str,
+    path: &str,
+    body: Option<serde_json::Value>,
+) -> Result<DockerResponse, DockerError> {
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    send_request(
+        method,
+        path,
+        "application/json",
+        body.len() as u64,
+        &mut body.as_bytes(),
+    )
+}
+
+/// @notice Function to send a request to the Docker Engine API, streaming the body from a reader
+/// @param method The http method
+/// @param path The api path including the query string
+/// @param content_type The content type of the body, e.g. `application/x-tar` for build contexts and image bundles
+/// @param content_length The length of the body in bytes
+/// @param body The reader the body is copied from
+/// @return A result containing the response, errors returned by the api are turned into a DockerError
+fn send_request(
+    method: &str,
+    path: &str,
+    content_type: &str,
+    content_length: u64,
+    body: &mut dyn Read,
+) -> Result<DockerResponse, DockerError> {
+    let mut stream = connect()?;
+
+    let head = format!(
+        "{} {} HTTP/1.1\r\nHost: docker\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        method, path, content_type, content_length
+    );
+    stream
+        .write_all(head.as_bytes())
+        .and_then(|_| io::copy(body, &mut stream))
+        .map_err(|e| DockerError::Io(e.to_string()))?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    reader
+        .read_line(&mut status_line)
+        .map_err(|e| DockerError::Io(e.to_string()))?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| DockerError::Io(format!("Invalid response: {}", status_line.trim())))?;
+
+    let mut chunked = false;
+    let mut content_length: Option<u64> = None;
+    loop {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .map_err(|e| DockerError::Io(e.to_string()))?;
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            match name.trim().to_lowercase().as_str() {
+                "transfer-encoding" => chunked = value.trim().eq_ignore_ascii_case("chunked"),
+                "content-length" => content_length = value.trim().parse().ok(),
+                _ => {}
+            }
+        }
+    }
+
+    let body: Box<dyn BufRead + Send> = if chunked {
+        Box::new(BufReader::new(ChunkedReader {
+            inner: reader,
+            remaining: 0,
+            started: false,
+            done: false,
+        }))
+    } else if let Some(length) = content_length {
+        Box::new(reader.take(length))
+    } else {
+        Box::new(reader)
+    };
+
+    let response = DockerResponse { status, body };
+    if response.status < 400 {
+        return Ok(response);
+    }
+
+    let status = response.status;
+    let message = response.json()?;
+    let message = message["message"]
+        .as_str()
+        .map(|message| message.to_string())
+        .unwrap_or_else(|| message.to_string());
+    if status == 404 && message.to_lowercase().contains("no such image") {
+        Err(DockerError::ImageNotFound(message))
+    } else {
+        Err(DockerError::Api(status, message))
+    }
+}
+
+/// @notice Function to url encode a value passed in the query string of an api path
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// @notice Function to check the Docker daemon is reachable
+/// @return A result containing nothing, or the reason the daemon could not be reached
+pub fn ping() -> Result<(), DockerError> {
+    docker_request("GET", "/_ping", None).map(|_| ())
+}
+
+/// @notice Function to check the Docker daemon is reachable and print the reason when it is not
+/// @return true if the daemon is reachable else false
+pub fn check_docker_daemon() -> bool {
+    match ping() {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("❌ {}", e.to_string().red());
+            false
+        }
+    }
+}
+
+/// @notice Function to check whether an image is available locally
+/// @param image The name of the image
+/// @return A result containing true if the image exists locally
+pub fn image_exists(image: &str) -> Result<bool, DockerError> {
+    match docker_request("GET", &format!("/images/{}/json", image), None) {
+        Ok(_) => Ok(true),
+        Err(DockerError::ImageNotFound(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// @notice Function to split an image reference into the repository and the tag the pull api expects
+/// @notice Without a tag the api pulls every tag of the repository, so `latest` is used like `docker pull` does
+/// @param image The image, e.g. `ghcr.io/org/solver:v1`, `localhost:5000/solver` or `alpine@sha256:...`
+/// @return the repository and the tag or digest of the image
+fn split_image_reference(image: &str) -> (String, String) {
+    let (name, digest) = match image.split_once('@') {
+        Some((name, digest)) => (name, Some(digest)),
+        None => (image, None),
+    };
+    // A colon before the last slash separates a registry port, not a tag
+    let (repository, tag) = match name.rsplit_once(':') {
+        Some((repository, tag)) if !tag.contains('/') => (repository, Some(tag)),
+        _ => (name, None),
+    };
+
+    (
+        repository.to_string(),
+        digest.or(tag).unwrap_or("latest").to_string(),
+    )
+}
+
+/// @notice Function to pull an image, reporting the pull progress in a spinner
+/// @param image The name of the image, including its tag or digest
+/// @param spinner The spinner to report the progress in
+/// @return A result containing nothing, or the reason the pull failed
+pub fn pull_image(image: &str, spinner: &ProgressBar) -> Result<(), DockerError> {
+    let (repository, tag) = split_image_reference(image);
+    let response = docker_request(
+        "POST",
+        &format!(
+            "/images/create?fromImage={}&tag={}",
+            encode(&repository),
+            encode(&tag)
+        ),
+        None,
+    )?;
+
+    for line in response.body.lines() {
+        let line = line.map_err(|e| DockerError::Io(e.to_string()))?;
+        let Ok(progress) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+
+        if let Some(error) = progress["error"].as_str() {
+            let lowercase = error.to_lowercase();
+            return if lowercase.contains("not found")
+                || lowercase.contains("manifest unknown")
+                || lowercase.contains("does not exist")
+                || lowercase.contains("pull access denied")
+            {
+                Err(DockerError::ImageNotFound(image.to_string()))
+            } else {
+                Err(DockerError::Api(200, error.to_string()))
+            };
+        }
+
+        let message = [
+            progress["id"].as_str(),
+            progress["status"].as_str(),
+            progress["progress"].as_str(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<&str>>()
+        .join(" ");
+        spinner.set_message(format!("Pulling {}: {}", image, message));
+    }
+
+    Ok(())
+}
+
+/// @notice Function to load the images of a `docker save` tarball
+/// @param bundle The path to the tarball
+/// @return A result containing the names of the loaded images, or the reason the load failed
+pub fn load_images(bundle: &Path) -> Result<Vec<String>, DockerError> {
+    let mut file =
+        File::open(bundle).map_err(|e| DockerError::Io(format!("{}: {}", bundle.display(), e)))?;
+    let length = file
+        .metadata()
+        .map_err(|e| DockerError::Io(e.to_string()))?
+        .len();
+    let response = send_request(
+        "POST",
+        "/images/load?quiet=1",
+        "application/x-tar",
+        length,
+        &mut file,
+    )?;
+
+    let mut loaded = Vec::new();
+    for line in response.body.lines() {
+        let line = line.map_err(|e| DockerError::Io(e.to_string()))?;
+        let Ok(progress) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+
+        if let Some(error) = progress["error"].as_str() {
+            return Err(DockerError::Api(200, error.to_string()));
+        }
+        if let Some(image) = progress["stream"]
+            .as_str()
+            .and_then(|stream| stream.trim().strip_prefix("Loaded image: "))
+        {
+            loaded.push(image.to_string());
+        }
+    }
+
+    Ok(loaded)
+}
+
+/// @notice Function to save images into a tarball that `load_images` or `docker load` can import
+/// @param images The names of the images to save
+/// @param output The path of the tarball to create
+/// @return A result containing nothing, or the reason the save failed
+pub fn save_images(images: &[String], output: &Path) -> Result<(), DockerError> {
+    let names: Vec<String> = images
+        .iter()
+        .map(|image| format!("names={}", encode(image)))
+        .collect();
+    let mut response = docker_request("GET", &format!("/images/get?{}", names.join("&")), None)?;
+
+    let mut file = File::create(output)
+        .map_err(|e| DockerError::Io(format!("{}: {}", output.display(), e)))?;
+    if let Err(e) = io::copy(&mut response.body, &mut file) {
+        drop(file);
+        let _ = std::fs::remove_file(output);
+        return Err(DockerError::Io(e.to_string()));
+    }
+    Ok(())
+}
+
+/// @notice Function to list the volumes carrying a label
+/// @param label The label filter, e.g. `com.docker.compose.project=devnet`
+/// @return A result containing the volumes as returned by the api, each with its `Name` and `Labels`
+pub fn list_volumes(label: &str) -> Result<Vec<serde_json::Value>, DockerError> {
+    let filters = json!({ "label": [label] }).to_string();
+    let volumes = docker_request(
+        "GET",
+        &format!("/volumes?filters={}", encode(&filters)),
+        None,
+    )?
+    .json()?;
+
+    Ok(volumes["Volumes"].as_array().cloned().unwrap_or_default())
+}
+
+/// @notice Function to read the multiplexed output stream of a container and send it line by line
+/// @param body The body of the container logs response
+/// @param sender The channel to send `(is_stderr, line)` pairs to
+fn forward_container_output(
+    mut body: Box<dyn BufRead + Send>,
+    sender: mpsc::Sender<(bool, String)>,
+) {
+    // Each frame starts with an 8 byte header: the stream type then the big endian size of the payload
+    let mut header = [0u8; 8];
+    while body.read_exact(&mut header).is_ok() {
+        let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let mut payload = vec![0u8; size];
+        if body.read_exact(&mut payload).is_err() {
+            break;
+        }
+
+        for line in String::from_utf8_lossy(&payload).lines() {
+            if sender.send((header[0] == 2, line.to_string())).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// @notice Function to run a container to completion and remove it, pulling its image first if needed
+/// @param image The image to run
+/// @param cmd The command to run in the container
+/// @param binds The volumes to mount, in `host path:container path` form
+/// @param timeout How long the container may run before it is killed
+/// @param on_output Called with every line the container prints, and whether it was printed to stderr
+/// @return A result containing the exit code of the container
+pub fn run_container(
+    image: &str,
+    cmd: &[&str],
+    binds: &[String],
+    timeout: Duration,
+    mut on_output: impl FnMut(bool, &str),
+) -> Result<i64, DockerError> {
+    let config = json!({
+        "Image": image,
+        "Cmd": cmd,
+        "AttachStdout": true,
+        "AttachStderr": true,
+        "Tty": false,
+        "HostConfig": { "Binds": binds },
+    });
+
+    let created = match docker_request("POST", "/containers/create", Some(config.clone())) {
+        Err(DockerError::ImageNotFound(_)) => {
+            let spinner = get_spinner();
+            spinner.set_message(format!("Pulling {}...", image));
+            let pulled = pull_image(image, &spinner);
+            spinner.finish_and_clear();
+            pulled?;
+            docker_request("POST", "/containers/create", Some(config))?
+        }
+        created => created?,
+    };
+    let id = created.json()?["Id"]
+        .as_str()
+        .map(|id| id.to_string())
+        .ok_or_else(|| DockerError::Io(String::from("The created container has no id")))?;
+
+    let result = (|| {
+        docker_request("POST", &format!("/containers/{}/start", id), None)?;
+        let logs = docker_request(
+            "GET",
+            &format!(
+                "/containers/{}/logs?follow=true&stdout=true&stderr=true",
+                id
+            ),
+            None,
+        )?;
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || forward_container_output(logs.body, sender));
+
+        // The log stream ends when the container exits
+        let start = Instant::now();
+        loop {
+            match receiver.recv_timeout(Duration::from_secs(1)) {
+                Ok((is_stderr, line)) => on_output(is_stderr, &line),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+            if start.elapsed() > timeout {
+                let _ = docker_request("POST", &format!("/containers/{}/kill", id), None);
+                return Err(DockerError::Timeout(format!("running {}", image)));
+            }
+        }
+
+        let waited = docker_request("POST", &format!("/containers/{}/wait", id), None)?.json()?;
+        Ok(waited["StatusCode"].as_i64().unwrap_or(-1))
+    })();
+
+    let _ = docker_request("DELETE", &format!("/containers/{}?force=true", id), None);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_chunked(body: &str) -> String {
+        let mut reader = ChunkedReader {
+            inner: body.as_bytes(),
+            remaining: 0,
+            started: false,
+            done: false,
+        };
+        let mut decoded = String::new();
+        reader.read_to_string(&mut decoded).unwrap();
+        decoded
+    }
+
+    #[test]
+    fn decodes_chunks_in_order() {
+        assert_eq!(
+            decode_chunked("5\r\nhello\r\n7\r\n, world\r\n0\r\n\r\n"),
+            "hello, world"
+        );
+    }
+
+    #[test]
+    fn ignores_chunk_extensions_and_reads_hex_sizes() {
+        let body = format!("1a;name=value\r\n{}\r\n0\r\n\r\n", "a".repeat(26));
+        assert_eq!(decode_chunked(&body), "a".repeat(26));
+    }
+
+    #[test]
+    fn stops_at_the_last_chunk() {
+        assert_eq!(decode_chunked("2\r\nok\r\n0\r\n\r\ntrailing"), "ok");
+    }
+
+    #[test]
+    fn rejects_invalid_chunk_sizes() {
+        let mut reader = ChunkedReader {
+            inner: "zz\r\nok\r\n".as_bytes(),
+            remaining: 0,
+            started: false,
+            done: false,
+        };
+        let mut decoded = String::new();
+        assert_eq!(
+            reader.read_to_string(&mut decoded).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn defaults_the_tag_to_latest() {
+        assert_eq!(
+            split_image_reference("alpine"),
+            (String::from("alpine"), String::from("latest"))
+        );
+        assert_eq!(
+            split_image_reference("localhost:5000/solver"),
+            (
+                String::from("localhost:5000/solver"),
+                String::from("latest")
+            )
+        );
+    }
+
+    #[test]
+    fn splits_tags_and_digests() {
+        assert_eq!(
+            split_image_reference("ghcr.io/org/solver:v1"),
+            (String::from("ghcr.io/org/solver"), String::from("v1"))
+        );
+        assert_eq!(
+            split_image_reference("localhost:5000/solver:dev"),
+            (String::from("localhost:5000/solver"), String::from("dev"))
+        );
+        assert_eq!(
+            split_image_reference("alpine:3.20@sha256:abc"),
+            (String::from("alpine"), String::from("sha256:abc"))
+        );
+    }
+}
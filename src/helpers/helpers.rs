@@ -1,19 +1,26 @@
-use crate::commands::deploy::deploy_contract;
+use crate::commands::deploy::{deploy_contract, estimate_deployment};
+use crate::commands::devnet::active_devnet_config;
+use crate::commands::manifest::deploy_manifest;
 use crate::commands::publish::{
     devnet_register, devnet_register_program_with_coprocessor, mainnet_register,
     register_program_with_coprocessor, testnet_register,
 };
+use crate::commands::verify::verify_deployment;
+use crate::helpers::config::{get_network_profile, DEVNET_PRIVATE_KEY};
+use crate::helpers::process::{report_process_error, run_captured, SHORT_TIMEOUT};
+use crate::helpers::rpc::{
+    address_from_private_key, format_ether, get_balance, get_chain_id, has_code,
+};
 use colored::Colorize;
 use enum_iterator::{all, Sequence};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::env;
 use std::error::Error;
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::Command;
 use std::time::Duration;
-use std::{thread, time};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Debug, Sequence)]
 enum DeploymentOptions {
@@ -47,17 +54,20 @@ impl UploadResponse {
     }
 }
 
-/// @notice Helper Function to check all the required dependencies are installed
+/// @notice Helper Function to check the dependencies a command runs are installed
+/// @param required_tools The names of the tools the command runs
 /// @returns a boolean value indicating whether or not all dependencies are installed
-pub fn check_dependencies_installed() -> bool {
-    let required_tools = vec!["forge", "cartesi", "docker", "curl"];
+pub fn check_dependencies_installed(required_tools: &[&str]) -> bool {
+    // cartesi is run with the npm global bin directory on PATH
+    let _ = add_npm_home_dir_to_path();
 
-    for tool in &required_tools {
+    for tool in required_tools {
         if !check_installed(tool.to_string()).unwrap_or(false) {
             eprintln!(
-                "{} is not installed. Please install {} and try again.",
+                "{} is not installed. Please install {} and try again, or run {} to check your setup.",
                 tool.bright_red(),
-                tool
+                tool,
+                "cartesi-coprocessor doctor".bold()
             );
             return false;
         }
@@ -70,9 +80,7 @@ pub fn check_dependencies_installed() -> bool {
 /// @param tool The name of the tool to check if installed
 /// @returns a Result to tell of the operation was sucessful
 pub fn check_installed(tool: String) -> Result<bool, String> {
-    let output = Command::new("which")
-        .arg(tool)
-        .output()
+    let output = run_captured(Command::new("which").arg(tool), SHORT_TIMEOUT)
         .map_err(|e| format!("Failed to execute 'which': {}", e))?;
 
     Ok(output.status.success())
@@ -99,106 +107,47 @@ pub fn read_file(path: &str, var_name: &str) -> String {
 /// @notice Function to get all available spaces
 /// @return a verc of string representing all the available spaces
 pub fn check_available_space() -> Vec<String> {
-    let mut child = Command::new("w3")
-        .arg("space")
-        .arg("ls")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'w3 space creation'");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-
-    let mut available_spaces: Vec<String> = Vec::new();
+    let output = match run_captured(Command::new("w3").arg("space").arg("ls"), SHORT_TIMEOUT) {
+        Ok(output) => output,
+        Err(e) => {
+            report_process_error("W3STORAGE", &e);
+            return Vec::new();
+        }
+    };
 
-    // Collect lines from stdout first
-    let lines: Vec<String> = stdout
+    // Process the listed spaces, the name of each space is its last column
+    String::from_utf8_lossy(&output.stdout)
         .lines()
-        .filter_map(|line| line.ok()) // Filter out errors
-        .collect();
-
-    // Process collected lines to extract available spaces
-    for line in lines {
-        let response: Vec<&str> = line.split_ascii_whitespace().collect();
-        available_spaces.push((response[response.len() - 1]).to_string());
-    }
-
-    // print the available spaces
-    // for space in available_spaces.clone() {
-    //     println!("Available space: {}", space);
-    // }
-
-    return available_spaces;
+        .filter_map(|line| line.split_ascii_whitespace().last())
+        .map(|space| space.to_string())
+        .collect()
 }
 
 /// @notice Helper Function to check if a particulr email is loged in on w3 storage
 /// @param email the email address to check if logged in
 /// @returns a boolean value indicating whether or not the execution was successfull
 pub fn check_if_logged_in(email: String) -> bool {
-    let mut child = Command::new("w3")
-        .arg("account")
-        .arg("ls")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'w3 account ls'");
-
-    let stdout = BufReader::new(child.stdout.take().expect("Failed to capture stdout"));
-    let stderr = BufReader::new(child.stderr.take().expect("Failed to capture stderr"));
-
-    let start = time::Instant::now();
-
-    let mut logged_in: Vec<String> = Vec::new();
-    let email_name: Vec<&str> = email.split("@").collect();
-
-    // Collect lines from stdout first
-    let lines: Vec<String> = stdout
-        .lines()
-        .filter_map(|line| line.ok()) // Filter out errors
-        .collect();
-
-    // Process collected lines to extract available spaces
-    for line in lines {
-        if line.starts_with("did:mailto:gmail.com:") {
-            // println!("{} {}", "--W3STORAGE:: ".green(), line[21..].green());
-            logged_in.push(line[21..].to_string());
-        }
-    }
-
-    thread::spawn(move || {
-        for line in stderr.lines() {
-            if let Ok(line) = line {
-                eprintln!("{} {}", "W3STORAGE::NOTE::".yellow(), line.yellow());
-            } else if let Err(e) = line {
-                eprintln!("{} {}", "W3STORAGE::ERROR::".red(), e);
-            }
-        }
-    });
-
-    while start.elapsed().as_secs() < 300 {
-        if let Some(status) = child.try_wait().expect("Failed to check process status") {
-            if status.success() {
-                for name in logged_in.clone() {
-                    if email_name[0].to_lowercase() == name.to_lowercase() {
-                        return true;
-                    }
-                }
-            } else {
-                return false;
-            }
+    let output = match run_captured(Command::new("w3").arg("account").arg("ls"), SHORT_TIMEOUT) {
+        Ok(output) => output,
+        Err(e) => {
+            report_process_error("W3STORAGE", &e);
+            return false;
         }
+    };
 
-        // Poll every 5 seconds
-        thread::sleep(time::Duration::from_secs(5));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stderr.lines() {
+        eprintln!("{} {}", "W3STORAGE::NOTE::".yellow(), line.yellow());
+    }
+    if !output.status.success() {
+        return false;
     }
 
-    // If timeout occurs
-    child
-        .kill()
-        .expect("Failed to terminate the login process.");
-    return false;
+    let email_name: Vec<&str> = email.split("@").collect();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("did:mailto:gmail.com:"))
+        .any(|name| email_name[0].to_lowercase() == name.to_lowercase())
 }
 
 /// @notice Function to get the machine hash
@@ -206,18 +155,11 @@ pub fn get_machine_hash() -> String {
     let current_dir = env::current_dir().expect("Failed to get current directory");
     let image_hash = current_dir.join(".cartesi/image/hash");
 
-    let output = Command::new("xxd")
-        .arg("-p")
-        .arg(image_hash)
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("Failed to execute 'xxd' command")
-        .stdout
-        .expect("Failed to capture xxd output");
+    let output = run_captured(Command::new("xxd").arg("-p").arg(image_hash), SHORT_TIMEOUT)
+        .unwrap_or_else(|e| panic!("Failed to execute 'xxd' command: {}", e));
 
-    let output = BufReader::new(output)
+    let output = String::from_utf8_lossy(&output.stdout)
         .lines()
-        .map(|line| line.unwrap_or_default())
         .collect::<Vec<_>>()
         .concat();
 
@@ -226,7 +168,7 @@ pub fn get_machine_hash() -> String {
 
 /// @notice Function to check that we have valid arguents for deployment
 pub fn check_deploymet_args(
-    network: &String,
+    network: &str,
     private_key: Option<String>,
     rpc: Option<String>,
 ) -> bool {
@@ -250,6 +192,109 @@ pub fn check_deploymet_args(
     return reject;
 }
 
+/// @notice Function to check that an RPC endpoint points at the chain expected for a network before deploying to it
+/// @notice It compares the chain id with the network profile, checks the deployer has funds and that the task issuer has code on the chain
+/// @notice Networks without a known task issuer, such as mainnet, need one configured in `coprocessor.toml`
+/// @param network The network being deployed to
+/// @param rpc The RPC endpoint being deployed through
+/// @param private_key The private key of the deployer
+/// @return true if the deployment can go ahead else false
+pub fn check_deployment_target(network: &str, rpc: &str, private_key: &str) -> bool {
+    let Some(profile) = get_network_profile(network) else {
+        return false;
+    };
+
+    let chain_id = match get_chain_id(rpc) {
+        Ok(chain_id) => chain_id,
+        Err(e) => {
+            eprintln!("❌ {} {}", "RPC::ERROR::".red(), e.red());
+            if profile.name == "devnet" {
+                println!("Please ensure you have a devnet environment. Run the stop and start devnet commands.");
+            }
+            return false;
+        }
+    };
+
+    if chain_id != profile.chain_id {
+        eprintln!(
+            "❌ {} {} {} {} {}",
+            "The RPC endpoint is on chain".red(),
+            chain_id.to_string().red(),
+            "but".red(),
+            profile.name.red(),
+            format!("expects chain {}. Aborting deployment.", profile.chain_id).red()
+        );
+        return false;
+    }
+    println!("✅ Connected to {} (chain id {})", profile.name, chain_id);
+
+    match address_from_private_key(private_key) {
+        Some(deployer) => match get_balance(rpc, &deployer) {
+            Ok(0) => {
+                eprintln!(
+                    "❌ {} {}",
+                    "Deployer account has no funds:".red(),
+                    deployer.red()
+                );
+                return false;
+            }
+            Ok(balance) => println!(
+                "✅ Deployer {} balance: {}",
+                deployer,
+                format_ether(balance)
+            ),
+            Err(e) => {
+                eprintln!("❌ {} {}", "RPC::ERROR::".red(), e.red());
+                return false;
+            }
+        },
+        None => {
+            eprintln!(
+                "❌ {}",
+                "Invalid private key provided for deployment.".red()
+            );
+            return false;
+        }
+    }
+
+    match &profile.task_issuer {
+        Some(task_issuer) => match has_code(rpc, task_issuer) {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!(
+                    "❌ {} {} {}",
+                    "Task issuer".red(),
+                    task_issuer.red(),
+                    "has no code on this chain. Aborting deployment.".red()
+                );
+                return false;
+            }
+            Err(e) => {
+                eprintln!("❌ {} {}", "RPC::ERROR::".red(), e.red());
+                return false;
+            }
+        },
+        None => {
+            eprintln!(
+                "❌ {} {}",
+                "No task issuer configured for".red(),
+                format!("{}. Aborting deployment.", profile.name).red()
+            );
+            println!(
+                "{}",
+                format!(
+                    "Set the task issuer address under [networks.{}] task_issuer in coprocessor.toml.",
+                    profile.name
+                )
+                .yellow()
+            );
+            return false;
+        }
+    }
+
+    true
+}
+
 /// @notice This function determines the deployment environment (Devnet, Testnet, or Mainnet)
 /// @notice based on the given network string. It then performs the appropriate registration
 /// @notice action for the specified environment using the provided email.
@@ -292,62 +337,121 @@ pub fn check_registration_environment(network: String, email: Option<String>) {
     }
 }
 
+/// @notice Function to resolve the deployer key and RPC endpoint for a network, then check the endpoint points at the expected chain.
+/// @param `network` A `&str` representing the network environment to deploy to. It should be one of "devnet", "testnet", or "mainnet".
+/// @param `private_key` An `Option<String>` containing the private key for deployment. Required for "mainnet" and "testnet", defaults to the first anvil account on "devnet".
+/// @param `rpc` An `Option<String>` containing the RPC endpoint for deployment. Required for "mainnet" and "testnet", ignored on "devnet" where the anvil node of the running devnet is used.
+/// @return an Option containing the private key and RPC endpoint to deploy with, None if the deployment should not go ahead
+pub fn resolve_deployment_target(
+    network: &str,
+    private_key: Option<String>,
+    rpc: Option<String>,
+) -> Option<(String, String)> {
+    let (private_key, rpc) = match network.to_lowercase().as_str() {
+        "mainnet" | "testnet" => {
+            if check_deploymet_args(network, private_key.clone(), rpc.clone()) {
+                return None;
+            }
+            (private_key.unwrap(), rpc.unwrap())
+        }
+        "devnet" => (
+            private_key.unwrap_or_else(|| String::from(DEVNET_PRIVATE_KEY)),
+            active_devnet_config().anvil_rpc(),
+        ),
+        _ => {
+            println!(
+                "{}",
+                "Invalid network environment, please select either, devnet, mainnet, or testnet"
+                    .red()
+            );
+            return None;
+        }
+    };
+
+    if !check_deployment_target(network, &rpc, &private_key) {
+        return None;
+    }
+
+    Some((private_key, rpc))
+}
+
 /// @notice This Function determines the deployment environment (Devnet, Testnet, or Mainnet) based on the given network string and deploys the contract using the provided parameters.
 /// @param `network` A `String` representing the network environment to deploy to. It should be one of "devnet", "testnet", or "mainnet".
-/// @param `private_key` An `Option<String>` containing the private key for deployment. Required for "mainnet" and "testnet".
-/// @param `rpc` An `Option<String>` containing the RPC endpoint for deployment. Required for "mainnet" and "testnet".
+/// @param `private_key` An `Option<String>` containing the private key for deployment. Required for "mainnet" and "testnet", defaults to the first anvil account on "devnet".
+/// @param `rpc` An `Option<String>` containing the RPC endpoint for deployment. Required for "mainnet" and "testnet", ignored on "devnet" where the anvil node of the running devnet is used.
 /// @param `constructor_args` An `Option<Vec<String>>` containing the constructor arguments for the contract, if any.
 /// @param `contract_name` A `String` representing the name of the contract to be deployed.
-
+/// @param `verify` A `bool` indicating whether the contract source should be verified on the network's block explorer after deploying.
+/// @param `estimate` A `bool` indicating whether to preview the gas and cost of the deployment first, non-devnet deployments then ask for confirmation.
 pub fn check_deployment_environment(
     network: String,
     private_key: Option<String>,
     rpc: Option<String>,
     constructor_args: Option<Vec<String>>,
     contract_name: String,
+    verify: bool,
+    estimate: bool,
 ) {
-    match network.to_lowercase().as_str() {
-        "mainnet" => {
-            if check_deploymet_args(&network, private_key.clone(), rpc.clone()) {
-                return;
-            } else {
-                deploy_contract(
-                    private_key.unwrap(),
-                    rpc.unwrap(),
-                    constructor_args,
-                    contract_name,
-                );
-            }
-        }
-        "testnet" => {
-            if check_deploymet_args(&network, private_key.clone(), rpc.clone()) {
-                return;
-            } else {
-                deploy_contract(
-                    private_key.unwrap(),
-                    rpc.unwrap(),
-                    constructor_args,
-                    contract_name,
-                );
-            }
-        }
-        "devnet" => {
-            deploy_contract(
-                String::from("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"),
-                String::from("http://127.0.0.1:8545"),
-                constructor_args,
-                contract_name,
-            );
+    let Some((private_key, rpc)) = resolve_deployment_target(&network, private_key, rpc) else {
+        return;
+    };
+
+    if estimate {
+        if !estimate_deployment(&private_key, &rpc, &constructor_args, &contract_name) {
+            return;
         }
-        _ => {
-            println!(
-                "{}",
-                "Invalid network environment, please select either, devnet, mainnet, or testnet"
-                    .red()
-            );
+        if network.to_lowercase() != "devnet" && !confirm("Proceed with deployment?") {
+            println!("{}", "Deployment cancelled.".yellow());
             return;
         }
     }
+
+    let record_path = deploy_contract(
+        private_key,
+        rpc,
+        constructor_args,
+        contract_name,
+        network.clone(),
+    );
+
+    if let (true, Some(record_path)) = (verify, record_path) {
+        verify_deployment(&record_path, &network);
+    }
+}
+
+/// @notice This Function determines the deployment environment (Devnet, Testnet, or Mainnet) based on the given network string and deploys every contract listed in a deployment manifest.
+/// @param `network` A `String` representing the network environment to deploy to. It should be one of "devnet", "testnet", or "mainnet".
+/// @param `private_key` An `Option<String>` containing the private key for deployment. Required for "mainnet" and "testnet", defaults to the first anvil account on "devnet".
+/// @param `rpc` An `Option<String>` containing the RPC endpoint for deployment. Required for "mainnet" and "testnet", ignored on "devnet" where the anvil node of the running devnet is used.
+/// @param `manifest` A `String` containing the path to the deployment manifest.
+/// @param `verify` A `bool` indicating whether each contract source should be verified on the network's block explorer after deploying.
+pub fn check_manifest_deployment_environment(
+    network: String,
+    private_key: Option<String>,
+    rpc: Option<String>,
+    manifest: String,
+    verify: bool,
+) {
+    let Some((private_key, rpc)) = resolve_deployment_target(&network, private_key, rpc) else {
+        return;
+    };
+
+    deploy_manifest(manifest, private_key, rpc, network, verify);
+}
+
+/// @notice Function to ask the user to confirm an action on the terminal
+/// @param prompt The question to display to the user
+/// @return true if the user answered yes else false
+pub fn confirm(prompt: &str) -> bool {
+    print!("{} [y/N] ", prompt);
+    io::stdout().flush().expect("Failed to flush stdout");
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
 /// @notice This function Creates and returns a new spinner progress bar with a custom style.
@@ -380,8 +484,29 @@ pub fn display_machine_hash() -> Option<String> {
     }
 }
 
+/// @notice This function looks for the machine hash of the Cartesi program in the current directory or any of its parents,
+/// @notice so it can also be found from the solidity contract directory of a project.
+/// @returns `Option<String>` - Returns `Some(String)` containing the hex encoded machine hash without a `0x` prefix if found, otherwise returns `None`.
+pub fn find_machine_hash() -> Option<String> {
+    let current_dir = env::current_dir().expect("Failed to get current directory");
+
+    let image_hash = current_dir
+        .ancestors()
+        .map(|dir| dir.join(".cartesi/image/hash"))
+        .find(|path| path.exists())?;
+
+    let hash = fs::read(image_hash).ok()?;
+    Some(hash.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
 /// @notice This function displays the address book for the current machine.
 pub fn address_book() {
+    let task_issuer = |network: &str| {
+        get_network_profile(network)
+            .and_then(|profile| profile.task_issuer)
+            .unwrap_or_else(|| String::from("--"))
+    };
+
     let possible_machine_hash = display_machine_hash();
     let mut _machine_hash = String::new();
 
@@ -394,17 +519,13 @@ pub fn address_book() {
 
     let data = vec![
         ("Machine Hash", _machine_hash),
-        (
-            "Devnet_task_issuer",
-            String::from("0x95401dc811bb5740090279Ba06cfA8fcF6113778"),
-        ),
-        (
-            "Testnet_task_issuer",
-            String::from("0xff35E413F5e22A9e1Cc02F92dcb78a5076c1aaf3"),
-        ),
+        ("Devnet_task_issuer", task_issuer("devnet")),
+        ("Testnet_task_issuer", task_issuer("testnet")),
         (
             "payment_token",
-            String::from("0xc5a5C42992dECbae36851359345FE25997F5C42d"),
+            get_network_profile("devnet")
+                .and_then(|profile| profile.payment_token)
+                .unwrap_or_else(|| String::from("--")),
         ),
     ];
 
@@ -468,4 +589,98 @@ pub fn add_npm_home_dir_to_path() -> Result<(), Box<dyn Error>> {
     }
 
     Err("Failed to add npm home directory to path".into())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Starts a JSON-RPC node answering eth_chainId, eth_getBalance and eth_getCode with the values given
+    fn mock_rpc(chain_id: &str, balance: &str, code: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let results = json!({
+            "eth_chainId": chain_id,
+            "eth_getBalance": balance,
+            "eth_getCode": code,
+        });
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(length) = header.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+                let method = request["method"].as_str().unwrap_or_default();
+                let response =
+                    json!({"jsonrpc": "2.0", "id": request["id"], "result": results[method]})
+                        .to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+
+        url
+    }
+
+    #[test]
+    fn accepts_a_funded_deployer_on_the_expected_chain() {
+        let rpc = mock_rpc("0x7a69", "0xde0b6b3a7640000", "0x6080");
+        assert!(check_deployment_target("devnet", &rpc, DEVNET_PRIVATE_KEY));
+    }
+
+    #[test]
+    fn rejects_an_rpc_on_another_chain() {
+        let rpc = mock_rpc("0x4268", "0xde0b6b3a7640000", "0x6080");
+        assert!(!check_deployment_target("devnet", &rpc, DEVNET_PRIVATE_KEY));
+    }
+
+    #[test]
+    fn rejects_a_deployer_without_funds() {
+        let rpc = mock_rpc("0x7a69", "0x0", "0x6080");
+        assert!(!check_deployment_target("devnet", &rpc, DEVNET_PRIVATE_KEY));
+    }
+
+    #[test]
+    fn rejects_a_task_issuer_without_code() {
+        let rpc = mock_rpc("0x4268", "0xde0b6b3a7640000", "0x");
+        assert!(!check_deployment_target(
+            "testnet",
+            &rpc,
+            DEVNET_PRIVATE_KEY
+        ));
+    }
+
+    #[test]
+    fn rejects_a_network_without_a_task_issuer() {
+        let rpc = mock_rpc("0x1", "0xde0b6b3a7640000", "0x6080");
+        assert!(!check_deployment_target(
+            "mainnet",
+            &rpc,
+            DEVNET_PRIVATE_KEY
+        ));
+    }
+}
@@ -1 +1,6 @@
+pub mod artifacts;
+pub mod config;
+pub mod docker;
 pub mod helpers;
+pub mod process;
+pub mod rpc;
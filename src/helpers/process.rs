@@ -0,0 +1,405 @@
+use crate::helpers::config::get_process_timeout;
+use colored::Colorize;
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// @notice Timeout of quick tool invocations such as git queries, cast calls or docker compose queries
+pub const SHORT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// @notice Timeout of tool invocations downloading or building things, such as git clone, forge install or docker compose build
+pub const LONG_TIMEOUT: Duration = Duration::from_secs(1800);
+
+/// @notice Timeout of tool invocations that only stop when the user stops them, such as following logs
+pub const NO_TIMEOUT: Duration = Duration::MAX;
+
+/// @notice Time a tool is given to exit after being interrupted before it is killed
+const STOP_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// @notice Time output is still read after a tool exits, background processes it started can keep its output open
+const OUTPUT_DRAIN_PERIOD: Duration = Duration::from_millis(500);
+
+/// @notice Flags whose value is replaced in log files
+const REDACTED_FLAGS: [&str; 2] = ["--private-key", "--mnemonic"];
+
+/// @notice Set when the user presses Ctrl-C while a tool is running
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// @notice Errors returned when running an external tool
+#[derive(Debug, Clone)]
+pub enum ProcessError {
+    NotFound(String),
+    Timeout(String, Duration, PathBuf),
+    Io(String),
+}
+
+impl fmt::Display for ProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessError::NotFound(program) => write!(
+                f,
+                "{} is not installed or not on your PATH. Please install {} and try again.",
+                program, program
+            ),
+            ProcessError::Timeout(name, timeout, log_file) => write!(
+                f,
+                "{} timed out after {} seconds, see {} for its output. Set `{} = <seconds>` in the [timeouts] section of coprocessor.toml to wait longer.",
+                name,
+                timeout.as_secs(),
+                log_file.display(),
+                name
+            ),
+            ProcessError::Io(message) => write!(f, "Failed to run command: {}", message),
+        }
+    }
+}
+
+/// @notice Function to get the name of a tool command, the program and its subcommand, e.g. `forge-install`
+/// @param command The command to name
+/// @return the name used for the log file and the `[timeouts]` key of the command
+fn command_name(command: &Command) -> String {
+    let program = Path::new(command.get_program())
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    match command.get_args().next().map(|arg| arg.to_string_lossy()) {
+        Some(subcommand) if !subcommand.starts_with('-') => format!("{}-{}", program, subcommand),
+        _ => program,
+    }
+}
+
+/// @notice Function to format a command as it would be typed, with secrets redacted
+/// @param command The command to format
+/// @return the command line
+fn command_line(command: &Command) -> String {
+    let mut parts = vec![command.get_program().to_string_lossy().to_string()];
+    let mut redact_next = false;
+    for arg in command.get_args() {
+        let arg = arg.to_string_lossy();
+        if redact_next {
+            parts.push(String::from("<redacted>"));
+        } else {
+            parts.push(arg.to_string());
+        }
+        redact_next = REDACTED_FLAGS.contains(&arg.as_ref());
+    }
+    parts.join(" ")
+}
+
+/// @notice Function to get the path of the log file of a tool command, holding the output of its last run
+/// @param name The name of the tool command
+/// @return the path to the log file
+pub fn log_file_path(name: &str) -> PathBuf {
+    let home_dir = env::var("HOME").expect("Failed to get HOME directory");
+    PathBuf::from(home_dir)
+        .join(".cartesi-coprocessor-logs")
+        .join(format!("{}.log", name))
+}
+
+/// @notice Function to create the log file of a tool command and write the command line to it
+/// @param path The path to the log file
+/// @param command The command being run
+/// @return an Option containing the log file, None if it could not be created
+fn open_log_file(path: &Path, command: &Command) -> Option<File> {
+    fs::create_dir_all(path.parent()?).ok()?;
+    let mut log_file = File::create(path).ok()?;
+    let current_dir = command
+        .get_current_dir()
+        .map(Path::to_path_buf)
+        .or_else(|| env::current_dir().ok())
+        .unwrap_or_default();
+    writeln!(
+        log_file,
+        "# {} in {}\n$ {}",
+        chrono::Local::now().to_rfc3339(),
+        current_dir.display(),
+        command_line(command)
+    )
+    .ok()?;
+    Some(log_file)
+}
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// @notice Function to handle Ctrl-C in the cli while a tool runs, instead of exiting and leaving the tool behind
+/// @param enabled true to catch Ctrl-C, false to restore the default behaviour
+#[cfg(unix)]
+fn catch_interrupts(enabled: bool) {
+    let handler = if enabled {
+        on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t
+    } else {
+        libc::SIG_DFL
+    };
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+    }
+}
+
+#[cfg(not(unix))]
+fn catch_interrupts(_enabled: bool) {}
+
+/// @notice Function to check whether a tool exited without reaping it, so its process group id stays reserved
+/// @param child The running tool
+/// @return true if the tool exited
+#[cfg(unix)]
+fn has_exited(child: &mut Child) -> bool {
+    // SAFETY: siginfo_t is plain data and waitid only writes to it, WNOWAIT leaves the tool waitable
+    unsafe {
+        let mut info: libc::siginfo_t = std::mem::zeroed();
+        libc::waitid(
+            libc::P_PID,
+            child.id() as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+        ) == 0
+            && info.si_pid() != 0
+    }
+}
+
+#[cfg(not(unix))]
+fn has_exited(child: &mut Child) -> bool {
+    matches!(child.try_wait(), Ok(Some(_)))
+}
+
+/// @notice Function to stop a tool, asking it to exit with SIGINT before killing it
+/// @notice The signal goes to the process group of the tool so processes it started are stopped too
+/// @param child The running tool
+fn stop_child(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: the tool was spawned as the leader of its own process group
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGINT);
+    }
+
+    let start = Instant::now();
+    while start.elapsed() < STOP_GRACE_PERIOD {
+        if has_exited(child) {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    // Processes the tool started in the background may ignore SIGINT or outlive it, the group is
+    // killed before the tool is reaped so its id cannot have been handed out to another group
+    #[cfg(unix)]
+    // SAFETY: the tool leads the process group and is not reaped yet, even if it exited it is a zombie
+    // holding the id, so the signal can only reach the tool and the processes it started
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// @notice Function to read the output of a tool line by line and send it to the runner
+/// @param output The stdout or stderr of the tool
+/// @param is_stderr Whether the output is stderr
+/// @param sender The channel to send the lines to
+fn forward_output(output: impl Read, is_stderr: bool, sender: mpsc::Sender<(bool, String)>) {
+    let mut reader = BufReader::new(output);
+    let mut line = Vec::new();
+    while let Ok(read) = reader.read_until(b'\n', &mut line) {
+        if read == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&line)
+            .trim_end_matches(['\r', '\n'])
+            .to_string();
+        if sender.send((is_stderr, text)).is_err() {
+            break;
+        }
+        line.clear();
+    }
+}
+
+/// @notice Function to run an external tool, with a timeout, Ctrl-C forwarding and its output written to a log file
+/// @notice The timeout can be overridden per tool command in the `[timeouts]` section of coprocessor.toml.
+/// @notice On Ctrl-C the tool is stopped and the cli exits.
+/// @param command The command to run, its stdin is closed and its output captured
+/// @param timeout How long the tool may run before it is stopped
+/// @param on_output Callback receiving each line of output along with whether it was written to stderr
+/// @return A result containing the exit status of the tool
+pub fn run_process(
+    command: &mut Command,
+    timeout: Duration,
+    mut on_output: impl FnMut(bool, &str),
+) -> Result<ExitStatus, ProcessError> {
+    let name = command_name(command);
+    let timeout = get_process_timeout(&name).unwrap_or(timeout);
+    let log_path = log_file_path(&name);
+    let mut log_file = open_log_file(&log_path, command);
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Ctrl-C from the terminal only reaches the cli, which forwards it to the tool
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    catch_interrupts(true);
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            catch_interrupts(false);
+            // Spawning in a missing directory is also reported as NotFound
+            let missing_dir = command.get_current_dir().filter(|dir| !dir.is_dir());
+            return Err(match (e.kind(), missing_dir) {
+                (io::ErrorKind::NotFound, Some(dir)) => {
+                    ProcessError::Io(format!("{} does not exist", dir.display()))
+                }
+                (io::ErrorKind::NotFound, None) => {
+                    ProcessError::NotFound(command.get_program().to_string_lossy().to_string())
+                }
+                _ => ProcessError::Io(e.to_string()),
+            });
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        let sender = sender.clone();
+        thread::spawn(move || forward_output(stdout, false, sender));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let sender = sender.clone();
+        thread::spawn(move || forward_output(stderr, true, sender));
+    }
+    drop(sender);
+
+    let start = Instant::now();
+    let mut exited_at: Option<Instant> = None;
+    let mut output_closed = false;
+    let result = loop {
+        if output_closed {
+            // A tool can close its output and keep running, it is still subject to the timeout
+            thread::sleep(Duration::from_millis(100));
+        } else {
+            match receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok((is_stderr, line)) => {
+                    if let Some(log_file) = log_file.as_mut() {
+                        let _ = writeln!(log_file, "{}", line);
+                    }
+                    on_output(is_stderr, &line);
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => output_closed = true,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+        }
+
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            stop_child(&mut child);
+            catch_interrupts(false);
+            if let Some(log_file) = log_file.as_mut() {
+                let _ = writeln!(log_file, "# interrupted");
+            }
+            eprintln!("\n❌ {} {}", "Interrupted, stopped".red(), name.red());
+            std::process::exit(130);
+        }
+
+        // The tool is only reaped once the loop ends, so an interrupt while its output drains still
+        // signals its own process group and not one that reused its id
+        if has_exited(&mut child) {
+            if output_closed
+                || exited_at.get_or_insert_with(Instant::now).elapsed() > OUTPUT_DRAIN_PERIOD
+            {
+                break child.wait().map_err(|e| ProcessError::Io(e.to_string()));
+            }
+        } else if start.elapsed() > timeout {
+            stop_child(&mut child);
+            break Err(ProcessError::Timeout(
+                name.clone(),
+                timeout,
+                log_path.clone(),
+            ));
+        }
+    };
+    catch_interrupts(false);
+
+    if let Some(log_file) = log_file.as_mut() {
+        let _ = match &result {
+            Ok(status) => writeln!(log_file, "# {}", status),
+            Err(e) => writeln!(log_file, "# {}", e),
+        };
+    }
+    result
+}
+
+/// @notice Function to run an external tool without printing its output
+/// @param command The command to run
+/// @param timeout How long the tool may run before it is stopped
+/// @return A result containing the exit status and output of the tool
+pub fn run_captured(command: &mut Command, timeout: Duration) -> Result<Output, ProcessError> {
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    let status = run_process(command, timeout, |is_stderr, line| {
+        let output = if is_stderr { &mut stderr } else { &mut stdout };
+        output.push_str(line);
+        output.push('\n');
+    })?;
+
+    Ok(Output {
+        status,
+        stdout: stdout.into_bytes(),
+        stderr: stderr.into_bytes(),
+    })
+}
+
+/// @notice Function to run an external tool, printing its output as it arrives
+/// @param command The command to run
+/// @param prefix The prefix of the printed lines, e.g. FORGE, stderr lines are printed as `<prefix>::NOTE::`
+/// @param timeout How long the tool may run before it is stopped
+/// @return A result containing the exit status and output of the tool
+pub fn run_streamed(
+    command: &mut Command,
+    prefix: &str,
+    timeout: Duration,
+) -> Result<Output, ProcessError> {
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    let status = run_process(command, timeout, |is_stderr, line| {
+        if is_stderr {
+            eprintln!(
+                "{} {}",
+                format!("{}::NOTE::", prefix).yellow(),
+                line.yellow()
+            );
+            stderr.push_str(line);
+            stderr.push('\n');
+        } else {
+            println!("{} {}", format!("{}::", prefix).green(), line.green());
+            stdout.push_str(line);
+            stdout.push('\n');
+        }
+    })?;
+
+    Ok(Output {
+        status,
+        stdout: stdout.into_bytes(),
+        stderr: stderr.into_bytes(),
+    })
+}
+
+/// @notice Function to print an error returned when running an external tool
+/// @param prefix The prefix of the tool, e.g. FORGE
+/// @param error The error to print
+pub fn report_process_error(prefix: &str, error: &ProcessError) {
+    eprintln!(
+        "❌ {} {}",
+        format!("{}::ERROR::", prefix).red(),
+        error.to_string().red()
+    );
+}
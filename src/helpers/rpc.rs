@@ -0,0 +1,390 @@
+use k256::ecdsa::SigningKey;
+use reqwest::blocking::Client;
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+use std::thread;
+use std::time::Duration;
+
+/// @notice Function to send a JSON-RPC request to an ethereum node
+/// @param rpc The url of the node
+/// @param method The JSON-RPC method to call
+/// @param params The parameters of the call
+/// @return A result containing the `result` field of the response or a description of the failure
+pub fn rpc_call(
+    rpc: &str,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("Failed to build HTTP client");
+
+    let response = client
+        .post(rpc)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))
+        .send()
+        .map_err(|e| format!("Failed to reach RPC endpoint {}: {}", rpc, e))?;
+
+    let body: serde_json::Value = response
+        .json()
+        .map_err(|e| format!("Invalid response from RPC endpoint {}: {}", rpc, e))?;
+
+    if let Some(error) = body.get("error") {
+        return Err(format!("{} failed: {}", method, error));
+    }
+
+    body.get("result")
+        .cloned()
+        .ok_or_else(|| format!("{} returned no result", method))
+}
+
+/// @notice Function to parse a hex quantity returned by a node
+/// @param value The JSON value holding a `0x` prefixed quantity
+/// @return A result containing the parsed number
+pub fn parse_quantity(value: &serde_json::Value) -> Result<u128, String> {
+    let quantity = value
+        .as_str()
+        .ok_or_else(|| format!("Expected a hex quantity, got {}", value))?;
+
+    u128::from_str_radix(quantity.trim_start_matches("0x"), 16)
+        .map_err(|e| format!("Invalid hex quantity '{}': {}", quantity, e))
+}
+
+/// @notice Function to get the chain id of the chain behind an RPC endpoint
+/// @param rpc The url of the node
+/// @return A result containing the chain id
+pub fn get_chain_id(rpc: &str) -> Result<u64, String> {
+    let chain_id = parse_quantity(&rpc_call(rpc, "eth_chainId", json!([]))?)?;
+    Ok(chain_id as u64)
+}
+
+/// @notice Function to get the balance of an account in wei
+/// @param rpc The url of the node
+/// @param address The address of the account
+/// @return A result containing the balance in wei
+pub fn get_balance(rpc: &str, address: &str) -> Result<u128, String> {
+    parse_quantity(&rpc_call(
+        rpc,
+        "eth_getBalance",
+        json!([address, "latest"]),
+    )?)
+}
+
+/// @notice Function to check whether an address has contract code deployed to it
+/// @param rpc The url of the node
+/// @param address The address to check
+/// @return A result containing true if the address holds code
+pub fn has_code(rpc: &str, address: &str) -> Result<bool, String> {
+    let code = rpc_call(rpc, "eth_getCode", json!([address, "latest"]))?;
+    Ok(code.as_str().is_some_and(|code| code.len() > 2))
+}
+
+/// @notice Function to format an amount of wei as ether
+/// @param wei The amount in wei
+/// @return the amount in ether with up to 6 decimal places
+pub fn format_ether(wei: u128) -> String {
+    let whole = wei / 10u128.pow(18);
+    let fraction = (wei % 10u128.pow(18)) / 10u128.pow(12);
+    format!("{}.{:06}", whole, fraction)
+}
+
+/// @notice Function to format an amount of wei as gwei
+/// @param wei The amount in wei
+/// @return the amount in gwei with up to 3 decimal places
+pub fn format_gwei(wei: u128) -> String {
+    format!(
+        "{}.{:03}",
+        wei / 10u128.pow(9),
+        (wei % 10u128.pow(9)) / 10u128.pow(6)
+    )
+}
+
+/// @notice Function to get the size of a unit in its smallest unit, e.g. 10^18 wei for ether
+/// @param decimals The number of decimals of the unit
+/// @return A result containing 10 to the power of decimals, an error if it does not fit in a u128
+fn unit_size(decimals: u32) -> Result<u128, String> {
+    10u128.checked_pow(decimals).ok_or_else(|| {
+        format!(
+            "Unsupported number of decimals {}, at most 38 are supported",
+            decimals
+        )
+    })
+}
+
+/// @notice Function to parse a decimal amount into its smallest unit, e.g. ether into wei
+/// @param amount The decimal amount, e.g. `1.5`
+/// @param decimals The number of decimals of the unit
+/// @return A result containing the amount in the smallest unit
+pub fn parse_units(amount: &str, decimals: u32) -> Result<u128, String> {
+    let unit = unit_size(decimals)?;
+    let invalid = || format!("Invalid amount '{}'", amount);
+    let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+    if fraction.len() > decimals as usize {
+        return Err(format!(
+            "Invalid amount '{}', at most {} decimals are supported",
+            amount, decimals
+        ));
+    }
+
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: u128 = if decimals == 0 {
+        0
+    } else {
+        format!("{:0<width$}", fraction, width = decimals as usize)
+            .parse()
+            .map_err(|_| invalid())?
+    };
+
+    whole
+        .checked_mul(unit)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// @notice Function to format an amount in its smallest unit as a decimal amount
+/// @param amount The amount in the smallest unit
+/// @param decimals The number of decimals of the unit
+/// @return A result containing the decimal amount without trailing zeros
+pub fn format_units(amount: u128, decimals: u32) -> Result<String, String> {
+    let unit = unit_size(decimals)?;
+    let fraction = format!("{:0>width$}", amount % unit, width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        Ok((amount / unit).to_string())
+    } else {
+        Ok(format!("{}.{}", amount / unit, fraction))
+    }
+}
+
+/// @notice Function to check an address is a `0x` prefixed 20 byte hex string
+/// @param address The address to check
+/// @return true if the address is well formed
+pub fn is_address(address: &str) -> bool {
+    address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// @notice Function to abi encode a call to an ERC20 function taking an address and an optional amount
+/// @param selector The 4 byte function selector, hex encoded without a `0x` prefix
+/// @param address The address argument
+/// @param amount The amount argument, if any
+/// @return the `0x` prefixed calldata
+fn encode_erc20_call(selector: &str, address: &str, amount: Option<u128>) -> String {
+    let mut data = format!(
+        "0x{}{:0>64}",
+        selector,
+        address.trim_start_matches("0x").to_lowercase()
+    );
+    if let Some(amount) = amount {
+        data.push_str(&format!("{:064x}", amount));
+    }
+    data
+}
+
+/// @notice Function to get the decimals of an ERC20 token
+/// @param rpc The url of the node
+/// @param token The address of the token
+/// @return A result containing the decimals of the token
+pub fn get_token_decimals(rpc: &str, token: &str) -> Result<u32, String> {
+    let decimals = rpc_call(
+        rpc,
+        "eth_call",
+        json!([{ "to": token, "data": "0x313ce567" }, "latest"]),
+    )?;
+    Ok(parse_quantity(&decimals)? as u32)
+}
+
+/// @notice Function to get the ERC20 token balance of an account
+/// @param rpc The url of the node
+/// @param token The address of the token
+/// @param address The address of the account
+/// @return A result containing the balance in the smallest unit of the token
+pub fn get_token_balance(rpc: &str, token: &str, address: &str) -> Result<u128, String> {
+    let balance = rpc_call(
+        rpc,
+        "eth_call",
+        json!([{ "to": token, "data": encode_erc20_call("70a08231", address, None) }, "latest"]),
+    )?;
+    parse_quantity(&balance)
+}
+
+/// @notice Function to send a transaction from an account unlocked on the node, e.g. a devnet anvil account,
+/// @notice and wait for it to be mined
+/// @param rpc The url of the node
+/// @param transaction The transaction object, with at least `from` and `to`
+/// @return A result containing the transaction hash
+pub fn send_unlocked_transaction(
+    rpc: &str,
+    transaction: serde_json::Value,
+) -> Result<String, String> {
+    let transaction_hash = rpc_call(rpc, "eth_sendTransaction", json!([transaction]))?
+        .as_str()
+        .map(|hash| hash.to_string())
+        .ok_or("eth_sendTransaction returned no transaction hash")?;
+
+    for _ in 0..30 {
+        let receipt = rpc_call(rpc, "eth_getTransactionReceipt", json!([transaction_hash]))?;
+        if !receipt.is_null() {
+            return if receipt["status"] == json!("0x1") {
+                Ok(transaction_hash)
+            } else {
+                Err(format!("Transaction {} reverted", transaction_hash))
+            };
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    Err(format!("Transaction {} was not mined", transaction_hash))
+}
+
+/// @notice Function to transfer ERC20 tokens from an account unlocked on the node
+/// @param rpc The url of the node
+/// @param token The address of the token
+/// @param from The address of the sender
+/// @param to The address of the recipient
+/// @param amount The amount in the smallest unit of the token
+/// @return A result containing the transaction hash
+pub fn transfer_token(
+    rpc: &str,
+    token: &str,
+    from: &str,
+    to: &str,
+    amount: u128,
+) -> Result<String, String> {
+    send_unlocked_transaction(
+        rpc,
+        json!({
+            "from": from,
+            "to": token,
+            "data": encode_erc20_call("a9059cbb", to, Some(amount)),
+        }),
+    )
+}
+
+/// @notice Function to derive the address of a private key
+/// @param private_key The private key of the account, with or without the `0x` prefix
+/// @return an Option containing the checksummed address of the account, None if the key is invalid
+pub fn address_from_private_key(private_key: &str) -> Option<String> {
+    let key = private_key.trim();
+    let key = key.strip_prefix("0x").unwrap_or(key);
+    // Slicing by byte offsets is only safe once the key is known to be ASCII
+    if key.len() != 64 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let bytes = (0..64)
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&key[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    let signing_key = SigningKey::from_slice(&bytes).ok()?;
+    let public_key = signing_key.verifying_key().to_encoded_point(false);
+    // The address is the last 20 bytes of the hash of the public key, without its 0x04 prefix
+    let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+    let address: String = hash[12..]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    Some(checksum_address(&address))
+}
+
+/// @notice Function to apply the EIP-55 mixed case checksum to an address
+/// @param address The 40 lowercase hex characters of the address
+/// @return the `0x` prefixed checksummed address
+fn checksum_address(address: &str) -> String {
+    let hash = Keccak256::digest(address.as_bytes());
+    let checksummed: String = address
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_amounts() {
+        assert_eq!(parse_units("1.5", 18).unwrap(), 1_500_000_000_000_000_000);
+        assert_eq!(parse_units(".25", 6).unwrap(), 250_000);
+        assert_eq!(parse_units(" 42 ", 0).unwrap(), 42);
+    }
+
+    #[test]
+    fn rejects_invalid_amounts() {
+        assert!(parse_units("1.0000001", 6).is_err());
+        assert!(parse_units("abc", 18).is_err());
+        assert!(parse_units("1e18", 18).is_err());
+        assert!(parse_units("340282366920938463464", 18).is_err());
+    }
+
+    #[test]
+    fn formats_amounts_without_trailing_zeros() {
+        assert_eq!(format_units(1_500_000_000_000_000_000, 18).unwrap(), "1.5");
+        assert_eq!(format_units(2_000_000, 6).unwrap(), "2");
+        assert_eq!(format_units(1, 6).unwrap(), "0.000001");
+        assert_eq!(format_units(7, 0).unwrap(), "7");
+    }
+
+    #[test]
+    fn round_trips_amounts() {
+        for amount in ["0.1", "123.456", "1000000"] {
+            let parsed = parse_units(amount, 18).unwrap();
+            assert_eq!(format_units(parsed, 18).unwrap(), amount);
+        }
+    }
+
+    #[test]
+    fn rejects_more_decimals_than_a_u128_holds() {
+        assert!(parse_units("1", 39).is_err());
+        assert!(format_units(1, 39).is_err());
+        assert_eq!(
+            format_units(u128::MAX, 38).unwrap(),
+            "3.40282366920938463463374607431768211455"
+        );
+    }
+
+    #[test]
+    fn derives_checksummed_addresses() {
+        assert_eq!(
+            address_from_private_key(
+                "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+            )
+            .unwrap(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert!(address_from_private_key("0x1234").is_none());
+        assert!(address_from_private_key(&format!("0x{}", "0".repeat(64))).is_none());
+    }
+
+    #[test]
+    fn rejects_keys_with_multibyte_characters() {
+        // 62 hex digits and a two-byte character make 64 bytes, with a char boundary inside a pair
+        let key = format!("0x{}é", "1".repeat(62));
+        assert_eq!(key.len(), 66);
+        assert!(address_from_private_key(&key).is_none());
+        let key = format!("0x{}é{}", "1".repeat(31), "1".repeat(31));
+        assert!(address_from_private_key(&key).is_none());
+        assert!(address_from_private_key(&"ü".repeat(32)).is_none());
+    }
+}
@@ -1,11 +1,30 @@
 mod commands;
 mod helpers;
-use crate::commands::create::create;
-use crate::commands::devnet::{start_devnet, stop_devnet};
+use crate::commands::accounts::{devnet_accounts, devnet_fund};
+use crate::commands::chain::{devnet_mine, devnet_reset, devnet_time_travel};
+use crate::commands::codegen::codegen;
+use crate::commands::completions::{
+    completions, contract_candidates, deployment_candidates, man, network_candidates,
+    snapshot_candidates,
+};
+use crate::commands::contracts::contracts_upgrade;
+use crate::commands::create::{create, create_from_template_repo, list_templates, parse_template};
+use crate::commands::deploy_script::regen_deploy_script;
+use crate::commands::devnet::{
+    devnet_export, devnet_logs, devnet_status, start_devnet, start_devnet_offline, stop_devnet,
+};
+use crate::commands::doctor::doctor;
+use crate::commands::init::init;
+use crate::commands::machine_hash::check_machine_hash_drift;
+use crate::commands::snapshot::{devnet_restore, devnet_snapshot};
+use crate::commands::verify::verify_contract;
 use crate::helpers::helpers::{check_dependencies_installed, check_network_and_confirm_status};
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, CommandFactory, Parser, Subcommand};
+use clap_complete::env::CompleteEnv;
+use clap_complete::{ArgValueCandidates, Shell};
 use helpers::helpers::{
-    address_book, check_deployment_environment, check_registration_environment,
+    address_book, check_deployment_environment, check_manifest_deployment_environment,
+    check_registration_environment,
 };
 use std::error::Error;
 
@@ -29,7 +48,8 @@ enum Commands {
         #[arg(
             short,
             long,
-            help = "Environment where your program will be deployed to, e.g. Devnet, Mainnet or Testnet"
+            help = "Environment where your program will be deployed to, e.g. Devnet, Mainnet or Testnet",
+            add = ArgValueCandidates::new(network_candidates)
         )]
         network: String,
     },
@@ -38,18 +58,165 @@ enum Commands {
         long_about = "Bootstrap a new directiry for your coprocessor program, this would contain both the cartesi template and also the solidity template"
     )]
     Create {
-        #[arg(short, long, help = "Name of your program")]
-        dapp_name: String,
+        #[arg(
+            short,
+            long,
+            help = "Name of your program",
+            required_unless_present = "list_templates"
+        )]
+        dapp_name: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Language you intend to build with, see --list-templates",
+            required_unless_present_any = ["list_templates", "template_repo"],
+            value_parser = parse_template
+        )]
+        template: Option<String>,
+
+        #[arg(long, help = "List the available templates")]
+        list_templates: bool,
+
+        #[arg(
+            long,
+            help = "Create the project from the templates embedded in the cli, without network access"
+        )]
+        offline: bool,
+
+        #[arg(
+            long,
+            help = "Git url or local path of a custom template repository to create the project from",
+            conflicts_with_all = ["template", "offline"]
+        )]
+        template_repo: Option<String>,
+
+        #[arg(
+            long = "ref",
+            value_name = "REF",
+            help = "Tag, branch or commit of the template repository to use",
+            requires = "template_repo",
+            conflicts_with = "template"
+        )]
+        git_ref: Option<String>,
+
+        #[arg(
+            long,
+            help = "Network whose task issuer the deploy script uses, also substituted in template repositories",
+            default_value = "devnet",
+            add = ArgValueCandidates::new(network_candidates)
+        )]
+        network: String,
+
+        #[arg(
+            long,
+            help = "Schema of the program's input and notice payloads, to generate the adapter contract and encoding helpers from",
+            conflicts_with = "template_repo"
+        )]
+        schema: Option<String>,
+
+        #[arg(
+            long,
+            help = "Tag of coprocessor-base-contract to install, defaults to the version the cli was tested with",
+            conflicts_with = "template_repo"
+        )]
+        base_contract_version: Option<String>,
+    },
+
+    #[command(
+        about = "Add co-processor support to the project in the current directory",
+        long_about = "Detect the foundry project, Cartesi program, base contract, adapter contract and deploy script of an existing project, and add only what's missing without overwriting existing files"
+    )]
+    Init {
+        #[arg(
+            short,
+            long,
+            help = "Network whose task issuer the deploy script uses",
+            default_value = "devnet",
+            add = ArgValueCandidates::new(network_candidates)
+        )]
+        network: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Name of the adapter contract to add when the project has none, defaults to MyContract"
+        )]
+        contract_name: Option<String>,
+
+        #[arg(
+            long,
+            help = "Tag of coprocessor-base-contract to install when it is missing, defaults to the version the cli was tested with"
+        )]
+        base_contract_version: Option<String>,
+    },
+
+    #[command(
+        about = "Generate the adapter contract and encoding helpers of a program from a schema",
+        long_about = "Generate a Solidity adapter with typed runExecution encoding and handleNotice decoding, and matching encode/decode helpers for the program, from a schema of its input and notice payloads"
+    )]
+    Codegen {
+        #[arg(short, long, help = "Path to the schema file")]
+        schema: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Language of the program, guessed from its files by default"
+        )]
+        language: Option<String>,
+
+        #[arg(long, help = "Replace files generated previously")]
+        force: bool,
+    },
+
+    #[command(
+        about = "Regenerate the deploy script of the project and record the machine hash of the current build",
+        long_about = "Regenerate contracts/script/Deploy.s.sol with the task issuer of a network, and record the machine hash of the current program build in coprocessor.lock for the script to read. Run it after each publish"
+    )]
+    RegenDeployScript {
+        #[arg(
+            short,
+            long,
+            help = "Network whose task issuer the script uses, defaults to the network recorded in coprocessor.lock or devnet",
+            add = ArgValueCandidates::new(network_candidates)
+        )]
+        network: Option<String>,
 
-        #[arg(short, long, help = "Language you intend to build with")]
-        template: String,
+        #[arg(
+            short,
+            long,
+            help = "Contract the script deploys, defaults to the contract recorded in coprocessor.lock or MyContract",
+            add = ArgValueCandidates::new(contract_candidates)
+        )]
+        contract_name: Option<String>,
     },
 
     #[command(
         about = "Start the devnet environment in detach mode",
         long_about = "Start the devnet environment in detach mode"
     )]
-    StartDevnet,
+    StartDevnet {
+        #[arg(
+            short,
+            long,
+            help = "Tag or commit of the co-processor to run, defaults to the version pinned in coprocessor.toml or the release branch"
+        )]
+        version: Option<String>,
+
+        #[arg(
+            long,
+            help = "Start without network access, skipping every clone, pull and build step"
+        )]
+        offline: bool,
+
+        #[arg(
+            long,
+            help = "Import a devnet image bundle created with the devnet export command before starting offline",
+            requires = "offline"
+        )]
+        import: Option<String>,
+    },
 
     #[command(
         about = "Stop the devnet environment",
@@ -57,6 +224,24 @@ enum Commands {
     )]
     StopDevnet,
 
+    #[command(
+        about = "Inspect and manage the devnet environment",
+        long_about = "Inspect and manage the devnet environment started with the start-devnet command"
+    )]
+    Devnet {
+        #[command(subcommand)]
+        command: DevnetCommands,
+    },
+
+    #[command(
+        about = "Manage the solidity contracts of the project",
+        long_about = "Manage the foundry project in the contracts directory of the project"
+    )]
+    Contracts {
+        #[command(subcommand)]
+        command: ContractsCommands,
+    },
+
     #[command(
         about = "Check the coprocessor solver for status of the program download process",
         long_about = "Check the coprocessor solver for status of the program download process"
@@ -65,27 +250,49 @@ enum Commands {
         #[arg(
             short,
             long,
-            help = "Environment where your program is registered to, e.g. Devnet, Mainnet or Testnet"
+            help = "Environment where your program is registered to, e.g. Devnet, Mainnet or Testnet",
+            add = ArgValueCandidates::new(network_candidates)
         )]
         network: String,
     },
 
     #[command(
         about = "Deploy the solidity code for your coprocessor program to any network of choice.",
-        long_about = "Deploy the solidity code for your coprocessor program to any network of choice, by running the default deploy script (Deploy.s.sol)"
+        long_about = "Deploy the solidity code for your coprocessor program to any network of choice, by running the default deploy script (Deploy.s.sol)",
+        group(ArgGroup::new("target").required(true).args(["contract_name", "manifest"]))
     )]
     Deploy {
-        #[arg(short, long, help = "Name of your contract file")]
-        contract_name: String,
+        #[arg(
+            short,
+            long,
+            help = "Name of your contract file",
+            add = ArgValueCandidates::new(contract_candidates)
+        )]
+        contract_name: Option<String>,
 
         #[arg(
             short,
             long,
-            help = "Environment where your program will be deployed to, e.g. Devnet, Mainnet or Testnet"
+            help = "Deploy every contract listed in a deployment manifest, in dependency order",
+            num_args = 0..=1,
+            default_missing_value = "deploy.toml",
+            conflicts_with_all = ["contract_name", "constructor_args", "estimate"]
+        )]
+        manifest: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Environment where your program will be deployed to, e.g. Devnet, Mainnet or Testnet",
+            add = ArgValueCandidates::new(network_candidates)
         )]
         network: String,
 
-        #[arg(short, long, help = "Private key for deploying to selected network")]
+        #[arg(
+            short,
+            long,
+            help = "Private key for deploying to selected network, defaults to the first anvil account on devnet"
+        )]
         private_key: Option<String>,
 
         #[arg(short, long, help = "RPC for deploying to network of choice")]
@@ -99,6 +306,72 @@ enum Commands {
         value_delimiter = ' '
         )]
         constructor_args: Option<Vec<String>>,
+
+        #[arg(
+            long,
+            help = "Verify the contract source on the network's block explorer after deploying"
+        )]
+        verify: bool,
+
+        #[arg(
+            long,
+            help = "Preview the estimated gas and cost of the deployment, and ask for confirmation on non-devnet networks"
+        )]
+        estimate: bool,
+    },
+
+    #[command(
+        about = "Verify the source of a deployed contract on the network's block explorer",
+        long_about = "Verify the source of a deployed contract on the network's block explorer, using the address and constructor arguments saved in deployment_history. Explorers are configured per network in coprocessor.toml"
+    )]
+    VerifyContract {
+        #[arg(
+            short,
+            long,
+            help = "Environment where your contract was deployed to, e.g. Devnet, Mainnet or Testnet",
+            add = ArgValueCandidates::new(network_candidates)
+        )]
+        network: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Address of the deployed contract, defaults to the latest deployment on the network",
+            add = ArgValueCandidates::new(deployment_candidates)
+        )]
+        address: Option<String>,
+    },
+
+    #[command(
+        about = "Check a deployed adapter for a stale machine hash and update or redeploy it",
+        long_about = "Compare the machine hash a deployed adapter was created with, read on-chain or from deployment_history, with the hash of the current program build. When they differ, offer to call setMachineHash on the adapter if it exposes one, or to redeploy it and link the old and new deployment records"
+    )]
+    UpdateMachineHash {
+        #[arg(
+            short,
+            long,
+            help = "Environment where your adapter is deployed, e.g. Devnet, Mainnet or Testnet",
+            add = ArgValueCandidates::new(network_candidates)
+        )]
+        network: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Address of the adapter, defaults to the latest deployment on the network",
+            add = ArgValueCandidates::new(deployment_candidates)
+        )]
+        address: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Private key for updating or redeploying the adapter"
+        )]
+        private_key: Option<String>,
+
+        #[arg(short, long, help = "RPC for updating or redeploying the adapter")]
+        rpc: Option<String>,
     },
 
     #[command(
@@ -106,28 +379,338 @@ enum Commands {
         long_about = "Displays the machine Hash and also co-processor address on different networks"
     )]
     AddressBook,
+
+    #[command(
+        about = "Check the tools, Docker daemon and disk space the CLI relies on",
+        long_about = "Report the path and version of every external tool the CLI runs against the supported versions, and check the Docker daemon is reachable, the npm global bin directory and the free disk space"
+    )]
+    Doctor,
+
+    #[command(
+        about = "Print the shell completion script",
+        long_about = "Print the completion script of a shell, completing subcommands, options, network names, the contracts of the current project, deployed addresses and devnet snapshots"
+    )]
+    Completions {
+        #[arg(help = "Shell to print the completion script for")]
+        shell: Shell,
+    },
+
+    #[command(
+        about = "Generate the man pages of the CLI",
+        long_about = "Print the man page of the CLI, or write a man page for every command into a folder"
+    )]
+    Man {
+        #[arg(short, long, help = "Folder to write a man page per command into")]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DevnetCommands {
+    #[command(
+        about = "Show the health of the devnet environment",
+        long_about = "Show the state, ports and images of the devnet containers, probe the anvil, solver and IPFS endpoints and check the task issuer is deployed"
+    )]
+    Status,
+
+    #[command(
+        about = "Show the logs of the devnet containers",
+        long_about = "Show the logs of the devnet containers, prefixed with the service each line came from"
+    )]
+    Logs {
+        #[arg(help = "Only show logs of this service, e.g. anvil, solver or ipfs")]
+        service: Option<String>,
+
+        #[arg(short, long, help = "Keep streaming new log lines")]
+        follow: bool,
+
+        #[arg(
+            short,
+            long,
+            help = "Only show logs since a timestamp or relative time, e.g. 2025-01-01T00:00:00 or 10m"
+        )]
+        since: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Only show lines containing this text, e.g. a machine hash or upload id"
+        )]
+        grep: Option<String>,
+    },
+
+    #[command(
+        about = "Export the devnet images into a tarball for offline use",
+        long_about = "Export the devnet images into a docker save tarball, which start-devnet --offline --import can load on a machine without network access"
+    )]
+    Export {
+        #[arg(
+            short,
+            long,
+            help = "Path of the tarball to create",
+            default_value = "cartesi-coprocessor-devnet.tar"
+        )]
+        output: String,
+    },
+
+    #[command(
+        about = "Save the devnet chain, volumes and deployment history into a snapshot",
+        long_about = "Save the anvil chain state, the IPFS and solver volumes and the deployment history of the running devnet into a named snapshot, which can be restored later or shared with a team"
+    )]
+    Snapshot {
+        #[arg(help = "Name of the snapshot, or a path to the snapshot folder to create")]
+        name: String,
+    },
+
+    #[command(
+        about = "Restore the devnet from a snapshot",
+        long_about = "Restore the anvil chain state, the IPFS and solver volumes and the deployment history of a snapshot into the running devnet"
+    )]
+    Restore {
+        #[arg(
+            help = "Name of the snapshot, or a path to a snapshot folder",
+            add = ArgValueCandidates::new(snapshot_candidates)
+        )]
+        name: String,
+    },
+
+    #[command(
+        about = "List the pre-funded devnet accounts",
+        long_about = "List the pre-funded anvil accounts of the devnet with their ETH and payment token balances and private keys"
+    )]
+    Accounts,
+
+    #[command(
+        about = "Send ETH and payment tokens from the devnet treasury",
+        long_about = "Send ETH and payment tokens from the devnet treasury, the first pre-funded anvil account, to an address"
+    )]
+    Fund {
+        #[arg(help = "Address to fund")]
+        address: String,
+
+        #[arg(help = "Amount of ETH to send, e.g. 1.5")]
+        amount: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Amount of payment tokens to send, defaults to the amount of ETH"
+        )]
+        tokens: Option<String>,
+    },
+
+    #[command(
+        about = "Mine blocks on the devnet",
+        long_about = "Mine blocks on the devnet anvil node, e.g. to reach the block an adapter times out at"
+    )]
+    Mine {
+        #[arg(help = "Number of blocks to mine", default_value_t = 1)]
+        blocks: u64,
+
+        #[arg(
+            short,
+            long,
+            help = "Seconds between the timestamps of the mined blocks"
+        )]
+        interval: Option<u64>,
+    },
+
+    #[command(
+        about = "Move the devnet clock forward",
+        long_about = "Move the devnet clock forward by a number of seconds, or to an exact timestamp, and mine a block with the new time"
+    )]
+    TimeTravel {
+        #[arg(
+            help = "Seconds to move the clock forward by",
+            required_unless_present = "to"
+        )]
+        seconds: Option<u64>,
+
+        #[arg(
+            long,
+            help = "Unix timestamp of the next block, instead of a number of seconds",
+            conflicts_with = "seconds"
+        )]
+        to: Option<u64>,
+    },
+
+    #[command(
+        about = "Reset the devnet chain",
+        long_about = "Revert the devnet chain to its state right after the devnet was started or restored from a snapshot, keeping the co-processor contracts"
+    )]
+    Reset,
+}
+
+#[derive(Subcommand, Debug)]
+enum ContractsCommands {
+    #[command(
+        about = "Upgrade the coprocessor-base-contract library of the project",
+        long_about = "Show the installed and latest versions of coprocessor-base-contract with the release notes in between, then install the new version with forge and check the project still compiles, rolling back if it doesn't"
+    )]
+    Upgrade {
+        #[arg(help = "Tag to upgrade to, defaults to the latest release")]
+        version: Option<String>,
+
+        #[arg(long, help = "Only show the available upgrade, without installing it")]
+        check: bool,
+
+        #[arg(short, long, help = "Upgrade without asking for confirmation")]
+        yes: bool,
+    },
+}
+
+/// @notice Function to get the external tools a command runs
+/// @param command The command to run
+/// @return the names of the tools that must be installed before running the command
+fn required_tools(command: &Commands) -> Vec<&'static str> {
+    match command {
+        Commands::Create {
+            list_templates: true,
+            ..
+        }
+        | Commands::Create {
+            dapp_name: None, ..
+        } => vec![],
+        Commands::Create {
+            template_repo: Some(_),
+            ..
+        } => vec!["git"],
+        Commands::Create { offline: true, .. } => vec![],
+        Commands::Create { .. } => vec!["cartesi", "forge", "git"],
+        Commands::Init { .. } => vec!["forge", "git"],
+        Commands::Codegen { .. } | Commands::RegenDeployScript { .. } => vec![],
+        Commands::StartDevnet { .. } | Commands::StopDevnet => vec!["docker", "git"],
+        Commands::Devnet { command } => match command {
+            DevnetCommands::Accounts | DevnetCommands::Fund { .. } => vec!["cast"],
+            DevnetCommands::Mine { .. }
+            | DevnetCommands::TimeTravel { .. }
+            | DevnetCommands::Reset => vec![],
+            DevnetCommands::Status => vec!["docker", "git"],
+            _ => vec!["docker"],
+        },
+        Commands::Contracts { .. } => vec!["forge", "git"],
+        Commands::Publish { network, .. } if network.to_lowercase() == "devnet" => {
+            vec!["cartesi", "docker", "curl", "xxd"]
+        }
+        Commands::Publish { .. } => vec!["cartesi", "docker", "curl", "xxd", "w3"],
+        Commands::PublishStatus { .. } | Commands::AddressBook => vec!["xxd"],
+        Commands::Deploy { .. }
+        | Commands::VerifyContract { .. }
+        | Commands::UpdateMachineHash { .. } => {
+            vec!["forge", "cast"]
+        }
+        Commands::Doctor | Commands::Completions { .. } | Commands::Man { .. } => vec![],
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    // Answer the completion requests of the scripts printed by the completions command
+    CompleteEnv::with_factory(Cli::command).complete();
+
     let cli = Cli::parse();
-    match check_dependencies_installed() {
+    match check_dependencies_installed(&required_tools(&cli.command)) {
         false => Ok(()),
         true => match cli.command {
             Commands::Create {
                 template,
                 dapp_name,
+                list_templates: list,
+                offline,
+                template_repo,
+                git_ref,
+                network,
+                schema,
+                base_contract_version,
+            } => {
+                match (dapp_name, template, template_repo) {
+                    (Some(dapp_name), _, Some(template_repo)) if !list => {
+                        create_from_template_repo(dapp_name, template_repo, git_ref, network)
+                    }
+                    (Some(dapp_name), Some(template), None) if !list => create(
+                        dapp_name,
+                        template,
+                        offline,
+                        schema,
+                        network,
+                        base_contract_version,
+                    ),
+                    _ => list_templates(),
+                }
+                Ok(())
+            }
+            Commands::Init {
+                network,
+                contract_name,
+                base_contract_version,
+            } => {
+                init(network, contract_name, base_contract_version);
+                Ok(())
+            }
+            Commands::Codegen {
+                schema,
+                language,
+                force,
             } => {
-                create(dapp_name, template);
+                codegen(schema, language, force);
                 Ok(())
             }
-            Commands::StartDevnet => {
-                start_devnet();
+            Commands::RegenDeployScript {
+                network,
+                contract_name,
+            } => {
+                regen_deploy_script(network, contract_name);
+                Ok(())
+            }
+            Commands::StartDevnet {
+                version,
+                offline,
+                import,
+            } => {
+                if offline {
+                    start_devnet_offline(version, import);
+                } else {
+                    start_devnet(version);
+                }
                 Ok(())
             }
             Commands::StopDevnet => {
                 stop_devnet();
                 Ok(())
             }
+            Commands::Devnet { command } => {
+                match command {
+                    DevnetCommands::Status => devnet_status(),
+                    DevnetCommands::Logs {
+                        service,
+                        follow,
+                        since,
+                        grep,
+                    } => devnet_logs(service, follow, since, grep),
+                    DevnetCommands::Export { output } => devnet_export(output),
+                    DevnetCommands::Snapshot { name } => devnet_snapshot(name),
+                    DevnetCommands::Restore { name } => devnet_restore(name),
+                    DevnetCommands::Accounts => devnet_accounts(),
+                    DevnetCommands::Fund {
+                        address,
+                        amount,
+                        tokens,
+                    } => devnet_fund(address, amount, tokens),
+                    DevnetCommands::Mine { blocks, interval } => devnet_mine(blocks, interval),
+                    DevnetCommands::TimeTravel { seconds, to } => devnet_time_travel(seconds, to),
+                    DevnetCommands::Reset => devnet_reset(),
+                }
+                Ok(())
+            }
+            Commands::Contracts { command } => {
+                match command {
+                    ContractsCommands::Upgrade {
+                        version,
+                        check,
+                        yes,
+                    } => contracts_upgrade(version, check, yes),
+                }
+                Ok(())
+            }
 
             Commands::Publish { email, network } => {
                 check_registration_environment(network, email);
@@ -140,24 +723,65 @@ fn main() -> Result<(), Box<dyn Error>> {
 
             Commands::Deploy {
                 contract_name,
+                manifest,
                 network,
                 private_key,
                 rpc,
                 constructor_args,
+                verify,
+                estimate,
             } => {
-                check_deployment_environment(
-                    network,
-                    private_key,
-                    rpc,
-                    constructor_args,
-                    contract_name,
-                );
+                // clap requires exactly one of a manifest and a contract name
+                if let Some(manifest) = manifest {
+                    check_manifest_deployment_environment(
+                        network,
+                        private_key,
+                        rpc,
+                        manifest,
+                        verify,
+                    );
+                } else if let Some(contract_name) = contract_name {
+                    check_deployment_environment(
+                        network,
+                        private_key,
+                        rpc,
+                        constructor_args,
+                        contract_name,
+                        verify,
+                        estimate,
+                    );
+                }
+                Ok(())
+            }
+            Commands::VerifyContract { network, address } => {
+                verify_contract(network, address);
+                Ok(())
+            }
+            Commands::UpdateMachineHash {
+                network,
+                address,
+                private_key,
+                rpc,
+            } => {
+                check_machine_hash_drift(network, address, private_key, rpc);
                 Ok(())
             }
             Commands::AddressBook => {
                 address_book();
                 Ok(())
             }
+            Commands::Doctor => {
+                doctor();
+                Ok(())
+            }
+            Commands::Completions { shell } => {
+                completions(shell, Cli::command);
+                Ok(())
+            }
+            Commands::Man { output } => {
+                man(Cli::command(), output);
+                Ok(())
+            }
         },
     }
 }
@@ -0,0 +1,92 @@
+// Generated by `cartesi-coprocessor codegen`, regenerate it instead of editing it.
+// ABI encoding of the task inputs and notices exchanged with the co-processor adapter contract.
+
+function to_bytes(value) {
+  if (typeof value === "string") {
+    return Buffer.from(value.startsWith("0x") ? value.slice(2) : value, "hex");
+  }
+  return Buffer.from(value);
+}
+
+function to_word(value) {
+  // Two's complement for negative integers
+  const word = BigInt.asUintN(256, BigInt(value));
+  return Buffer.from(word.toString(16).padStart(64, "0"), "hex");
+}
+
+function pad_right(data) {
+  const padded = Buffer.alloc(Math.ceil(data.length / 32) * 32);
+  data.copy(padded);
+  return padded;
+}
+
+function is_dynamic(abi_type) {
+  return abi_type === "bytes" || abi_type === "string";
+}
+
+function encode_static(abi_type, value) {
+  if (abi_type === "bool") {
+    return to_word(value ? 1 : 0);
+  }
+  if (abi_type === "address") {
+    return to_word("0x" + to_bytes(value).toString("hex"));
+  }
+  if (abi_type.startsWith("uint") || abi_type.startsWith("int")) {
+    return to_word(value);
+  }
+  const word = Buffer.alloc(32);
+  to_bytes(value).copy(word);
+  return word;
+}
+
+function encode_dynamic(abi_type, value) {
+  const data = abi_type === "string" ? Buffer.from(value, "utf8") : to_bytes(value);
+  return Buffer.concat([to_word(data.length), pad_right(data)]);
+}
+
+function decode_static(abi_type, word) {
+  if (abi_type === "bool") {
+    return word[31] === 1;
+  }
+  if (abi_type === "address") {
+    return "0x" + word.subarray(12).toString("hex");
+  }
+  const value = BigInt("0x" + word.toString("hex"));
+  if (abi_type.startsWith("uint")) {
+    return value;
+  }
+  if (abi_type.startsWith("int")) {
+    return BigInt.asIntN(256, value);
+  }
+  return "0x" + word.subarray(0, parseInt(abi_type.slice(5))).toString("hex");
+}
+
+function abi_encode(types, values) {
+  const head = [];
+  const tail = [];
+  let tail_size = 0;
+  types.forEach((abi_type, index) => {
+    if (is_dynamic(abi_type)) {
+      const encoded = encode_dynamic(abi_type, values[index]);
+      head.push(to_word(32 * types.length + tail_size));
+      tail.push(encoded);
+      tail_size += encoded.length;
+    } else {
+      head.push(encode_static(abi_type, values[index]));
+    }
+  });
+  return Buffer.concat([...head, ...tail]);
+}
+
+function abi_decode(types, data) {
+  return types.map((abi_type, index) => {
+    const word = data.subarray(32 * index, 32 * (index + 1));
+    if (!is_dynamic(abi_type)) {
+      return decode_static(abi_type, word);
+    }
+    const offset = Number(BigInt("0x" + word.toString("hex")));
+    const length = Number(BigInt("0x" + data.subarray(offset, offset + 32).toString("hex")));
+    const raw = data.subarray(offset + 32, offset + 32 + length);
+    return abi_type === "string" ? raw.toString("utf8") : "0x" + raw.toString("hex");
+  });
+}
@@ -0,0 +1,67 @@
+# Generated by `cartesi-coprocessor codegen`, regenerate it instead of editing it.
+# ABI encoding of the task inputs and notices exchanged with the co-processor adapter contract.
+
+
+def _to_bytes(value):
+    if isinstance(value, str):
+        return bytes.fromhex(value[2:] if value.startswith("0x") else value)
+    return bytes(value)
+
+
+def _is_dynamic(abi_type):
+    return abi_type in ("bytes", "string")
+
+
+def _encode_static(abi_type, value):
+    if abi_type == "bool":
+        return int(bool(value)).to_bytes(32, "big")
+    if abi_type == "address":
+        return _to_bytes(value).rjust(32, b"\0")
+    if abi_type.startswith("uint"):
+        return int(value).to_bytes(32, "big")
+    if abi_type.startswith("int"):
+        return int(value).to_bytes(32, "big", signed=True)
+    return _to_bytes(value).ljust(32, b"\0")
+
+
+def _encode_dynamic(abi_type, value):
+    data = value.encode("utf-8") if abi_type == "string" else _to_bytes(value)
+    padded = data.ljust((len(data) + 31) // 32 * 32, b"\0")
+    return len(data).to_bytes(32, "big") + padded
+
+
+def _decode_static(abi_type, word):
+    if abi_type == "bool":
+        return word[-1] == 1
+    if abi_type == "address":
+        return "0x" + word[12:].hex()
+    if abi_type.startswith("uint"):
+        return int.from_bytes(word, "big")
+    if abi_type.startswith("int"):
+        return int.from_bytes(word, "big", signed=True)
+    return "0x" + word[: int(abi_type[5:])].hex()
+
+
+def abi_encode(types, values):
+    head, tail = b"", b""
+    for abi_type, value in zip(types, values):
+        if _is_dynamic(abi_type):
+            head += (32 * len(types) + len(tail)).to_bytes(32, "big")
+            tail += _encode_dynamic(abi_type, value)
+        else:
+            head += _encode_static(abi_type, value)
+    return head + tail
+
+
+def abi_decode(types, data):
+    values = []
+    for index, abi_type in enumerate(types):
+        word = data[32 * index : 32 * (index + 1)]
+        if _is_dynamic(abi_type):
+            offset = int.from_bytes(word, "big")
+            length = int.from_bytes(data[offset : offset + 32], "big")
+            raw = data[offset + 32 : offset + 32 + length]
+            values.append(raw.decode("utf-8") if abi_type == "string" else "0x" + raw.hex())
+        else:
+            values.append(_decode_static(abi_type, word))
+    return values
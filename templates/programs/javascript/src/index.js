@@ -0,0 +1,50 @@
+const rollup_server = process.env.ROLLUP_HTTP_SERVER_URL;
+console.log("HTTP rollup_server url is " + rollup_server);
+
+// Notices are returned to the contract that issued the task, through the co-processor callback
+async function emit_notice(payload) {
+  const response = await fetch(rollup_server + "/notice", {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ payload }),
+  });
+  console.log("Received notice status " + response.status);
+}
+
+async function handle_advance(data) {
+  console.log("Received advance request data " + JSON.stringify(data));
+  await emit_notice(data["payload"]);
+  return "accept";
+}
+
+async function handle_inspect(data) {
+  console.log("Received inspect request data " + JSON.stringify(data));
+  return "accept";
+}
+
+const handlers = {
+  advance_state: handle_advance,
+  inspect_state: handle_inspect,
+};
+
+const finish = { status: "accept" };
+
+(async () => {
+  while (true) {
+    const finish_req = await fetch(rollup_server + "/finish", {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(finish),
+    });
+
+    console.log("Received finish status " + finish_req.status);
+
+    if (finish_req.status == 202) {
+      console.log("No pending rollup request, trying again");
+    } else {
+      const rollup_req = await finish_req.json();
+      const handler = handlers[rollup_req["request_type"]];
+      finish["status"] = await handler(rollup_req["data"]);
+    }
+  }
+})();